@@ -9,6 +9,61 @@ async fn main() {
     
     // Инициализация логирования
     env_logger::init();
+
+    // Ротация ключей шифрования: перешифровать сохранённые данные активным ключом и выйти
+    if std::env::args().any(|arg| arg == "--rotate-keys") {
+        match bot_core::storage::history::rotate_encryption_keys().await {
+            Ok(count) => println!("🔐 Перешифровано записей: {}", count),
+            Err(e) => eprintln!("❌ Ошибка ротации ключей: {}", e),
+        }
+        return;
+    }
+
+    let args: Vec<String> = std::env::args().collect();
+
+    // Синхронизация глоссариев DeepL с локальным файлом: --sync-glossaries [файл.json]
+    // (по умолчанию — DEEPL_GLOSSARY_PATH)
+    if let Some(pos) = args.iter().position(|arg| arg == "--sync-glossaries") {
+        let path = args
+            .get(pos + 1)
+            .filter(|arg| !arg.starts_with("--"))
+            .cloned()
+            .or_else(bot_core::language::translator::glossary::GlossaryFile::path_from_env);
+        let Some(path) = path else {
+            eprintln!("❌ Укажите файл: --sync-glossaries <файл.json> или DEEPL_GLOSSARY_PATH");
+            return;
+        };
+        match bot_core::language::translator::glossary::sync_glossaries(&path).await {
+            Ok(count) => println!("📘 Обновлено глоссариев: {}", count),
+            Err(e) => eprintln!("❌ Ошибка синхронизации глоссариев: {}", e),
+        }
+        return;
+    }
+
+    // Предзаполнение памяти переводов проверенными переводами: --seed-translations <файл.json>
+    if let Some(pos) = args.iter().position(|arg| arg == "--seed-translations") {
+        let Some(path) = args.get(pos + 1) else {
+            eprintln!("❌ Укажите файл: --seed-translations <файл.json>");
+            return;
+        };
+        if std::env::var("DATABASE_URL").is_ok() {
+            if let Err(e) = bot_core::storage::translation_memory::init_translation_memory_table().await {
+                eprintln!("❌ Не удалось создать таблицу памяти переводов: {}", e);
+                return;
+            }
+        }
+        let result = match std::fs::read_to_string(path) {
+            Ok(json) => bot_core::storage::translation_memory::translation_memory()
+                .seed_from_json(&json)
+                .await,
+            Err(e) => Err(format!("Не удалось прочитать {}: {}", path, e)),
+        };
+        match result {
+            Ok(count) => println!("🗂 Загружено проверенных переводов: {}", count),
+            Err(e) => eprintln!("❌ Ошибка загрузки переводов: {}", e),
+        }
+        return;
+    }
     
     println!("🚀 Запуск Telegram-бота Auraya...");
     println!("⚠️ Работаем без базы данных (режим тестирования)");
@@ -24,6 +79,46 @@ async fn main() {
         }
     }
     
+    // Инициализация таблиц, если база данных настроена
+    if std::env::var("DATABASE_URL").is_ok() {
+        if let Err(e) = bot_core::storage::db::init_cache_table().await {
+            eprintln!("⚠️ Не удалось создать таблицу кэша: {}", e);
+        }
+        if let Err(e) = bot_core::storage::analytics::init_analytics_table().await {
+            eprintln!("⚠️ Не удалось создать таблицу аналитики: {}", e);
+        }
+        if let Err(e) = bot_core::storage::users::init_users_table().await {
+            eprintln!("⚠️ Не удалось создать таблицу пользователей: {}", e);
+        }
+        if let Err(e) = bot_core::storage::history::init_history_tables().await {
+            eprintln!("⚠️ Не удалось создать таблицы истории: {}", e);
+        }
+        if let Err(e) = bot_core::storage::privacy::init_privacy_audit_table().await {
+            eprintln!("⚠️ Не удалось создать журнал аудита: {}", e);
+        }
+        if let Err(e) = bot_core::storage::translation_memory::init_translation_memory_table().await {
+            eprintln!("⚠️ Не удалось создать таблицу памяти переводов: {}", e);
+        }
+    }
+
+
+    // Проверяем ключи шифрования заранее, чтобы не упасть посреди диалога
+    match bot_core::storage::crypto::Keyring::from_env() {
+        Ok(keyring) if keyring.is_enabled() => {
+            println!("🔐 Шифрование данных включено (активный ключ: {})", keyring.active_key_id().unwrap_or("-"));
+        }
+        Ok(_) => println!("⚠️ ENCRYPTION_KEYS не задан — история и память хранятся без шифрования"),
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            return;
+        }
+    }
+
+    // Служебные команды (/stats) доступны только администраторам
+    if bot_core::integration::telegram::handler::admin_user_ids().is_empty() {
+        println!("⚠️ ADMIN_USER_IDS не задан — команда /stats недоступна никому");
+    }
+
     // Запуск бота
     println!("🤖 Подключение к Telegram API...");
     run_bot().await;
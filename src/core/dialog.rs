@@ -1,6 +1,32 @@
 use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone)]
+use crate::{
+    core::settings::UserSettings,
+    knowledge::{
+        arxiv::ArxivQuery,
+        citation::Citation,
+        github::RepoQuery,
+        wikipedia::{Disambiguation, ReadingRequest, WikiReading},
+    },
+    language::{code::Language, detect::LanguageHints, normalize::normalize_reply},
+};
+
+/// Сколько последних языков пользователя помнить для определения языка коротких сообщений
+const RECENT_LANGUAGES: usize = 5;
+
+/// Просьбы продолжить предыдущий ответ (следующий раздел статьи, следующая страница выдачи)
+const MORE_REQUESTS: &[&str] = &[
+    "more", "tell me more", "next", "continue", "go on",
+    "дальше", "ещё", "еще", "подробнее", "продолжай", "расскажи ещё", "расскажи еще", "расскажи подробнее",
+];
+
+/// Просит ли пользователь продолжить предыдущий ответ («дальше», «more»)
+pub fn is_more_request(input: &str) -> bool {
+    MORE_REQUESTS.contains(&normalize_reply(input).as_str())
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Message {
     pub user: String,
     pub text: String,
@@ -10,11 +36,68 @@ pub struct Message {
 #[derive(Debug)]
 pub struct DialogContext {
     pub history: Vec<Message>,
+    pub platform: String,        // например: "telegram", "cli", "ios"
+    pub user_id: Option<String>, // идентификатор пользователя на платформе
+    pub settings: UserSettings,
+    pub client_language: Option<Language>, // язык интерфейса клиента (Telegram `language_code`)
+    pub recent_languages: Vec<Language>,    // языки последних сообщений, от старых к новым
+    pub pending_disambiguation: Option<Disambiguation>, // варианты, предложенные последним ответом
+    pub continuation: Option<Continuation>,             // что продолжить по «дальше»
+    pub sources: Option<AnswerSources>,                 // источники последнего ответа с источниками
+}
+
+/// Что последний ответ позволяет продолжить по «дальше»
+#[derive(Debug, Clone)]
+pub enum Continuation {
+    /// Статья Википедии, которую можно читать по разделам
+    Reading(WikiReading),
+    /// Следующая страница поиска arXiv
+    Arxiv(ArxivQuery),
+    /// Следующая страница поиска GitHub
+    Github(RepoQuery),
+}
+
+/// Просьба продолжить предыдущий ответ
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContinuationRequest {
+    /// Следующий раздел, оглавление или раздел статьи по названию/номеру
+    Reading(ReadingRequest),
+    /// Следующая страница поиска arXiv
+    ArxivPage,
+    /// Следующая страница поиска GitHub
+    GithubPage,
+}
+
+/// Источники ответа (arXiv, Википедия, GitHub) для оформления ссылок
+#[derive(Debug, Clone)]
+pub struct AnswerSources {
+    pub citations: Vec<Citation>,
+    /// Индекс ответа в истории диалога
+    pub answer: usize,
 }
 
 impl DialogContext {
     pub fn new() -> Self {
-        Self { history: Vec::new() }
+        Self {
+            history: Vec::new(),
+            platform: "unknown".to_string(),
+            user_id: None,
+            settings: UserSettings::default(),
+            client_language: None,
+            recent_languages: Vec::new(),
+            pending_disambiguation: None,
+            continuation: None,
+            sources: None,
+        }
+    }
+
+    /// Контекст диалога конкретного пользователя на платформе
+    pub fn for_user(platform: &str, user_id: &str) -> Self {
+        Self {
+            platform: platform.to_string(),
+            user_id: Some(user_id.to_string()),
+            ..Self::new()
+        }
     }
 
     pub fn add_message(&mut self, user: &str, text: &str) {
@@ -25,6 +108,55 @@ impl DialogContext {
         });
     }
 
+    /// Запоминает язык очередного сообщения пользователя
+    pub fn remember_language(&mut self, lang: Language) {
+        self.recent_languages.push(lang);
+        if self.recent_languages.len() > RECENT_LANGUAGES {
+            self.recent_languages.remove(0);
+        }
+    }
+
+    /// Текущий язык диалога: язык последнего сообщения, иначе язык клиента
+    pub fn current_language(&self) -> Option<Language> {
+        self.recent_languages.last().copied().or(self.client_language)
+    }
+
+    /// Подсказки для определения языка следующего сообщения
+    pub fn language_hints(&self) -> LanguageHints {
+        LanguageHints {
+            client_language: self.client_language,
+            recent: self.recent_languages.clone(),
+        }
+    }
+
+    /// Разбирает сообщение как продолжение предыдущего ответа («дальше», «3», «содержание»).
+    /// Если сообщение его не продолжает, продолжение забывается: на сообщение ответит другой
+    /// навык, и через час «ещё» не должно открыть раздел давно прочитанной статьи.
+    pub fn continuation_request(&mut self, input: &str) -> Option<ContinuationRequest> {
+        let request = match &self.continuation {
+            Some(Continuation::Reading(reading)) => reading.parse_request(input).map(ContinuationRequest::Reading),
+            Some(Continuation::Arxiv(_)) if is_more_request(input) => Some(ContinuationRequest::ArxivPage),
+            Some(Continuation::Github(_)) if is_more_request(input) => Some(ContinuationRequest::GithubPage),
+            _ => None,
+        };
+        if request.is_none() {
+            self.continuation = None;
+        }
+        request
+    }
+
+    /// Запоминает источники ответа, который будет добавлен в историю следующим
+    pub fn record_sources(&mut self, citations: Vec<Citation>) {
+        if !citations.is_empty() {
+            self.sources = Some(AnswerSources { citations, answer: self.history.len() });
+        }
+    }
+
+    /// Источники, если они относятся к последнему сообщению диалога
+    pub fn latest_sources(&self) -> Option<&AnswerSources> {
+        self.sources.as_ref().filter(|sources| sources.answer + 1 == self.history.len())
+    }
+
     pub fn last_user_input(&self) -> Option<&Message> {
         self.history.iter().rev().find(|m| m.user == "user")
     }
@@ -37,3 +169,25 @@ impl DialogContext {
             .join("\n")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::knowledge::wikipedia::WikiArticle;
+
+    #[test]
+    fn test_continuation_cleared_by_other_intent() {
+        let article = WikiArticle { title: "Rust".to_string(), extract: String::new(), language: Language::ENGLISH };
+        let mut dialog = DialogContext::new();
+        dialog.continuation = Some(Continuation::Reading(WikiReading::new(&article)));
+
+        assert_eq!(dialog.continuation_request("дальше"), Some(ContinuationRequest::Reading(ReadingRequest::Next)));
+        // Вопрос о погоде — другой навык: старая статья больше не продолжается
+        assert_eq!(dialog.continuation_request("weather in London"), None);
+        assert!(dialog.continuation.is_none());
+        assert_eq!(dialog.continuation_request("ещё"), None);
+
+        dialog.continuation = Some(Continuation::Github(RepoQuery::new("parser")));
+        assert_eq!(dialog.continuation_request("next"), Some(ContinuationRequest::GithubPage));
+    }
+}
@@ -1,28 +1,128 @@
+use std::sync::OnceLock;
+use std::time::Instant;
+
+use chrono::Utc;
+
 use crate::{
-    core::dialog::DialogContext,
-    knowledge::{wikipedia::fetch_wikipedia_summary, github::search_github_repos},
-    language::{detect::detect_language, translator::translate_text},
-    storage::db::{get_from_cache, save_to_cache},
+    core::{
+        dialog::{Continuation, ContinuationRequest, DialogContext},
+        settings::{UserSettings, Verbosity},
+    },
+    knowledge::{
+        arxiv::{format_papers, is_arxiv_query, search_arxiv, ArxivQuery},
+        citation::{format_citations, is_cite_request, Citation},
+        wikipedia::{
+            fetch_wikipedia_page, fetch_wikipedia_sections, fetch_wikipedia_summary, Disambiguation, ReadingRequest,
+            WikiArticle, WikiLookup, WikiReading,
+        },
+        github::{
+            client::GithubError,
+            format_repositories,
+            insight::{fetch_repo_insight, format_insight, parse_repo_reference},
+            is_repo_search, search_github_repos, RepoQuery,
+        },
+    },
+    language::{
+        code::Language,
+        detect::{detect_with_hints, Detection},
+        fuzzy::default_matcher,
+        normalize::{remove_emoji, KeywordSet},
+        segment::grapheme_count,
+        translator::{default_chain, Formality},
+    },
+    storage::{
+        analytics::{anonymize_user_id, record_interaction, InteractionRecord},
+        db::{get_from_cache, record_cache_origin, save_to_cache},
+    },
     skills::{
-        code::{detect_code_query, fetch_code_examples},
+        code::{detect_code_query, fetch_code_snippets},
         math::{is_math_expression, solve_with_explanation},
         weather::{is_weather_query, extract_city_from_query, get_weather, get_weather_fallback},
     },
+    utils::{split_into_sentences, truncate},
 };
 
+/// Навык, выбранный для ответа на сообщение (используется в аналитике)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Intent {
+    Wikipedia,
+    Arxiv,
+    Citation,
+    Math,
+    Weather,
+    Code,
+    Github,
+    RepoInsight,
+    SmallTalk,
+}
+
+impl Intent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Intent::Wikipedia => "wikipedia",
+            Intent::Arxiv => "arxiv",
+            Intent::Citation => "citation",
+            Intent::Math => "math",
+            Intent::Weather => "weather",
+            Intent::Code => "code",
+            Intent::Github => "github",
+            Intent::RepoInsight => "github_repo",
+            Intent::SmallTalk => "small_talk",
+        }
+    }
+}
+
 /// Асинхронная функция, которая возвращает умный ответ с учётом языка пользователя
 pub async fn smart_answer_multilang(user_input: &str, dialog: &mut DialogContext) -> String {
+    let started = Instant::now();
+    let mut error_kind: Option<&'static str> = None;
     dialog.add_message("user", user_input);
 
+    // Ссылки на источники предыдущего ответа в форматах BibTeX, APA и Markdown
+    if is_cite_request(user_input) {
+        if let Some(reply) = citations_reply(dialog) {
+            dialog.add_message("bot", &reply);
+            let lang = dialog.current_language().unwrap_or(Language::ENGLISH).code();
+            track_interaction(dialog, &lang, Intent::Citation, started, false, None);
+            return reply;
+        }
+    }
+
+    // Ответ на список значений из предыдущей справки: номер или название варианта
+    if let Some(disambiguation) = dialog.pending_disambiguation.take() {
+        if let Some(index) = disambiguation.choice_index(user_input) {
+            return answer_disambiguation(dialog, disambiguation, index, started).await;
+        }
+    }
+
+    // Продолжение предыдущего ответа: следующий раздел статьи (или оглавление, раздел по названию),
+    // следующая страница поиска
+    // (иначе продолжение забывается — отвечать будет другой навык)
+    match dialog.continuation_request(user_input) {
+        Some(ContinuationRequest::Reading(request)) => return answer_reading(dialog, request, started).await,
+        Some(ContinuationRequest::ArxivPage) => return answer_arxiv_page(dialog, started).await,
+        Some(ContinuationRequest::GithubPage) => return answer_github_page(dialog, started).await,
+        None => {}
+    }
+
     // Определяем язык входного сообщения
-    let lang = detect_language(user_input).unwrap_or_else(|| "EN".to_string());
+    // Для коротких «ok», «2+2» язык не определить — остаёмся на языке диалога
+    let detected = match detect_with_hints(user_input, &dialog.language_hints()) {
+        Detection::Detected(lang) => {
+            dialog.remember_language(lang);
+            lang
+        }
+        Detection::Unknown => dialog.current_language().unwrap_or(Language::ENGLISH),
+    };
+    let lang = detected.code();
 
     // Переводим на английский, если нужно
-    let input_en = if lang != "EN" {
-        match translate_text(user_input, "EN").await {
+    let input_en = if detected != Language::ENGLISH {
+        match default_chain().translate(user_input, Some(&lang), "EN").await {
             Ok(translated) => translated,
             Err(e) => {
                 println!("Ошибка перевода: {}", e);
+                error_kind = Some("translation");
                 user_input.to_string()
             }
         }
@@ -30,112 +130,595 @@ pub async fn smart_answer_multilang(user_input: &str, dialog: &mut DialogContext
         user_input.to_string()
     };
 
+    let intent = classify_intent(&input_en);
+    let settings = dialog.settings.clone();
+    // Язык ответа: явная настройка пользователя важнее автоопределения
+    let reply_lang = settings.reply_language.clone().unwrap_or_else(|| lang.clone());
+    // Справку ищем в разделе Википедии на языке ответа
+    let wiki_lang = Language::parse(&reply_lang).unwrap_or(detected);
+    // Болтовня дешёвая и зависит от момента (например, время) — её не кэшируем
+    let mut use_cache = !matches!(intent, Intent::SmallTalk | Intent::Citation);
+    let cache_key = cache_key(&input_en, intent, &settings, wiki_lang, &reply_lang);
+
     // Проверка кэша
-    if let Some(cached) = get_from_cache(&input_en).await {
-        let translated = translate_if_needed(&cached, &lang).await;
-        dialog.add_message("bot", &translated);
-        return translated;
-    }
-
-    // Основная логика ответа с расширенными навыками
-    let response_en = if input_en.to_lowercase().starts_with("what is") || 
-                         input_en.to_lowercase().contains("что такое") {
-        
-        println!("📖 Запрос Wikipedia: {}", input_en);
-        match fetch_wikipedia_summary(&input_en).await {
-            Ok(summary) if !summary.is_empty() && summary != "No summary found." => {
-                println!("✅ Wikipedia ответ получен");
-                summary
+    if use_cache {
+        if let Some(user_id) = dialog.user_id.as_deref() {
+            record_cache_origin(&dialog.platform, user_id, &cache_key).await;
+        }
+        if let Some(cached) = get_from_cache(&cache_key).await {
+            // Справки кэшируются уже на языке раздела, сводки погоды — на русском, остальные ответы — на английском
+            let cached_lang = match intent {
+                Intent::Wikipedia => wiki_lang,
+                Intent::Weather => Language::RUSSIAN,
+                _ => Language::ENGLISH,
+            };
+            // Источники кэшируются только у справок: ссылки предыдущего, не связанного
+            // ответа к кэшированному ответу не относятся
+            dialog.sources = None;
+            if let Some(title) = WikiArticle::title_of(&cached).filter(|_| intent == Intent::Wikipedia) {
+                let article = WikiArticle { title: title.to_string(), extract: String::new(), language: wiki_lang };
+                dialog.continuation = Some(Continuation::Reading(WikiReading::new(&article)));
+                dialog.record_sources(vec![Citation::from_article(&article)]);
             }
-            Ok(_) => {
-                println!("⚠️ Wikipedia: пустой ответ");
-                generate_fallback_response(&input_en)
+            match intent {
+                Intent::Arxiv => dialog.continuation = Some(Continuation::Arxiv(ArxivQuery::parse(&input_en))),
+                Intent::Github => dialog.continuation = Some(Continuation::Github(RepoQuery::parse(&input_en))),
+                _ => {}
             }
-            Err(e) => {
-                println!("❌ Ошибка Wikipedia: {}", e);
-                generate_fallback_response(&input_en)
+            let translated = finalize_response(&cached, cached_lang, &reply_lang, &settings).await;
+            dialog.add_message("bot", &translated);
+            track_interaction(dialog, &lang, intent, started, true, error_kind);
+            return translated;
+        }
+    }
+
+    // Основная логика ответа с расширенными навыками (ответы на английском, кроме справок)
+    let mut response_lang = Language::ENGLISH;
+    let mut sources: Vec<Citation> = Vec::new();
+    // Ответ цитирует сообщение пользователя — переводим его мимо общей памяти переводов
+    let mut quotes_user = false;
+    let response_en = match intent {
+        Intent::Wikipedia => {
+            // Исходный запрос подходит, только если он на языке раздела
+            let query = if detected == wiki_lang { user_input } else { input_en.as_str() };
+            println!("📖 Запрос Wikipedia ({}): {}", wiki_lang, query);
+            match fetch_wikipedia_summary(query, wiki_lang, &input_en).await {
+                Ok(WikiLookup::Ambiguous(disambiguation)) => {
+                    // Варианты зависят от состояния диалога — такой ответ не кэшируем
+                    use_cache = false;
+                    response_lang = Language::parse(&reply_lang).unwrap_or(wiki_lang);
+                    disambiguation_prompt(dialog, disambiguation, &reply_lang).await
+                }
+                Ok(WikiLookup::Article(article)) => {
+                    println!("✅ Wikipedia ответ получен ({})", article.language);
+                    dialog.continuation = Some(Continuation::Reading(WikiReading::new(&article)));
+                    sources.push(Citation::from_article(&article));
+                    let summary = article_summary(&article, &settings);
+                    if article.language == wiki_lang {
+                        response_lang = wiki_lang;
+                        summary
+                    } else {
+                        // В разделе на языке ответа статьи нет — переводим английскую
+                        match default_chain()
+                            .translate_markup_with(&summary, Some(&article.language.code()), &reply_lang, settings.formality)
+                            .await
+                        {
+                            Ok(translated) => {
+                                response_lang = wiki_lang;
+                                translated
+                            }
+                            Err(e) => {
+                                println!("⚠️ Ошибка перевода справки: {}", e);
+                                error_kind = Some("translation");
+                                summary
+                            }
+                        }
+                    }
+                }
+                Err(e) => {
+                    println!("❌ Ошибка Wikipedia: {}", e);
+                    error_kind = Some("wikipedia_api");
+                    generate_fallback_response(&input_en)
+                }
             }
         }
-    } 
-    // Математические выражения - ИСПРАВЛЕНО: убрал .await
-    else if is_math_expression(&input_en) {
-        println!("🧮 Математическое выражение: {}", input_en);
-        match solve_with_explanation(&input_en) {
-            Ok(result) => result,
-            Err(e) => {
-                println!("❌ Ошибка вычисления: {}", e);
-                format!("❌ Не могу вычислить: **{}**\n\n💡 Проверьте правильность выражения. Поддерживаются: +, -, *, /, ^, sqrt(), sin(), cos() и т.д.", input_en)
+        // Поиск статей arXiv
+        Intent::Arxiv => {
+            let query = ArxivQuery::parse(&input_en);
+            println!("📚 Запрос arXiv: {:?}", query);
+            if query.is_empty() {
+                "📚 Уточните тему или категорию статей!\n\nПример: \"papers about diffusion models\" или \"latest in cs.CL\"".to_string()
+            } else {
+                match search_arxiv(&query).await {
+                    Ok(papers) if !papers.is_empty() => {
+                        println!("✅ arXiv: найдено {} статей", papers.len());
+                        let list = format_papers(&papers, query.start);
+                        sources.extend(papers.iter().map(Citation::from_paper));
+                        dialog.continuation = Some(Continuation::Arxiv(query));
+                        list
+                    }
+                    Ok(_) => {
+                        println!("⚠️ arXiv: статьи не найдены");
+                        error_kind = Some("arxiv_empty");
+                        "📚 На arXiv ничего не нашлось. Попробуйте другие ключевые слова.".to_string()
+                    }
+                    Err(e) => {
+                        println!("❌ Ошибка arXiv: {}", e);
+                        error_kind = Some("arxiv_api");
+                        "Извините, arXiv сейчас недоступен. Попробуйте позже! 🔧".to_string()
+                    }
+                }
             }
         }
-    }
-    // Погодные запросы - НОВЫЙ НАВЫК  
-    else if is_weather_query(&input_en) {
-        if let Some(city) = extract_city_from_query(&input_en) {
-            println!("🌤️ Запрос погоды для города: {}", city);
-            match get_weather(&city).await {
-                Ok(weather_info) => weather_info,
+        // Просьба процитировать, когда источников ещё не было (иначе ответ дан выше)
+        Intent::Citation => {
+            "📑 Пока нечего цитировать: ссылки оформляются для ответов из arXiv, Википедии и GitHub.".to_string()
+        }
+        // Математические выражения
+        Intent::Math => {
+            println!("🧮 Математическое выражение: {}", input_en);
+            match solve_with_explanation(&input_en) {
+                Ok(result) => result,
                 Err(e) => {
-                    println!("❌ Ошибка погоды: {}", e);
-                    if e.contains("API ключ") {
-                        get_weather_fallback(&city)
-                    } else {
-                        e
-                    }
+                    println!("❌ Ошибка вычисления: {}", e);
+                    error_kind = Some("math_eval");
+                    quotes_user = true;
+                    format!("❌ Не могу вычислить: **{}**\n\n💡 Проверьте правильность выражения. Поддерживаются: +, -, *, /, ^, sqrt(), sin(), cos() и т.д.", input_en)
                 }
             }
-        } else {
-            "🌤️ Укажите город для получения погоды!\n\nПример: \"Погода в Москве\" или \"Weather in London\" 🏙️".to_string()
         }
-    }
-    // Примеры кода - УЛУЧШЕННЫЙ НАВЫК
-    else if let Some(language) = detect_code_query(&input_en) {
-        println!("💻 Запрос примеров кода: {}", language);
-        match fetch_code_examples(&language).await {
-            Ok(examples) => examples,
-            Err(e) => {
-                println!("❌ Ошибка поиска кода: {}", e);
-                format!("К сожалению, примеры кода для **{}** сейчас недоступны. Попробуйте позже! 💻", language)
+        // Погодные запросы
+        Intent::Weather => {
+            let city = extract_city_from_query(&input_en).or_else(|| settings.default_city.clone());
+            if let Some(city) = city {
+                println!("🌤️ Запрос погоды для города: {}", city);
+                match get_weather(&city, &settings, &reply_lang).await {
+                    Ok(weather_info) => {
+                        // Подписи в сводке на русском, описание погоды — уже на языке ответа
+                        response_lang = Language::RUSSIAN;
+                        weather_info
+                    }
+                    Err(e) => {
+                        println!("❌ Ошибка погоды: {}", e);
+                        error_kind = Some("weather_api");
+                        quotes_user = true;
+                        if e.contains("API ключ") {
+                            get_weather_fallback(&city)
+                        } else {
+                            e
+                        }
+                    }
+                }
+            } else {
+                "🌤️ Укажите город для получения погоды!\n\nПример: \"Погода в Москве\" или \"Weather in London\" 🏙️\n\n💡 Город по умолчанию можно задать: /settings city Москва".to_string()
             }
         }
-    }
-    // GitHub репозитории
-    else if input_en.to_lowercase().contains("rust example") || 
-            input_en.to_lowercase().contains("github") ||
-            input_en.to_lowercase().contains("code") {
-        
-        println!("🔍 Запрос GitHub: {}", input_en);
-        match search_github_repos("rust example", 3).await {
-            Ok(repos) if !repos.is_empty() => {
-                println!("✅ GitHub: найдено {} репозиториев", repos.len());
-                repos.into_iter()
-                    .map(|(name, description, url)| {
-                        format!("📂 **{}**\n{}\n🔗 {}", name, description, url)
-                    })
-                    .collect::<Vec<_>>()
-                    .join("\n\n")
+        // Примеры кода
+        Intent::Code => {
+            let language = detect_code_query(&input_en).unwrap_or_default();
+            println!("💻 Запрос примеров кода: {}", language);
+            match fetch_code_snippets(&input_en, &language).await {
+                Ok(answer) => {
+                    sources.extend(answer.sources);
+                    answer.text
+                }
+                Err(e) => {
+                    println!("❌ Ошибка поиска кода: {}", e);
+                    error_kind = Some("github_api");
+                    match e {
+                        GithubError::RateLimited { .. } => e.to_string(),
+                        _ => format!("К сожалению, примеры кода для **{}** сейчас недоступны. Попробуйте позже! 💻", language),
+                    }
+                }
             }
-            Ok(_) => {
-                println!("⚠️ GitHub: репозитории не найдены");
-                "К сожалению, примеры кода сейчас недоступны. Попробуйте позже! 💻".to_string()
+        }
+        // GitHub репозитории
+        Intent::Github => {
+            println!("🔍 Запрос GitHub: {}", input_en);
+            let query = RepoQuery::parse(&input_en);
+            if query.is_empty() {
+                "🔍 Уточните, какие репозитории искать!\n\nПример: \"popular web frameworks in rust with more than 1000 stars\" или \"cli tools written in go updated this year\"".to_string()
+            } else {
+                match search_github_repos(&query).await {
+                    Ok(search) if !search.repositories.is_empty() => {
+                        println!("✅ GitHub: найдено {} репозиториев", search.total_count);
+                        sources.extend(search.repositories.iter().map(|repo| Citation::from_repo(&repo.name, &repo.url)));
+                        let list = format_repositories(&search, &query);
+                        dialog.continuation = Some(Continuation::Github(query));
+                        list
+                    }
+                    Ok(_) => {
+                        println!("⚠️ GitHub: репозитории не найдены");
+                        error_kind = Some("github_empty");
+                        format!("🔍 По запросу `{}` репозитории не найдены. Попробуйте ослабить условия.", query.to_query_string())
+                    }
+                    Err(e) => {
+                        println!("❌ Ошибка GitHub: {}", e);
+                        error_kind = Some("github_api");
+                        github_error_message(&e)
+                    }
+                }
             }
-            Err(e) => {
-                println!("❌ Ошибка GitHub: {}", e);
-                "Извините, сейчас не могу найти репозитории. Попробуйте позже! 🔧".to_string()
+        }
+        // Обзор конкретного репозитория по ссылке или `owner/repo`
+        Intent::RepoInsight => {
+            // Исходное сообщение надёжнее: перевод может исказить название
+            match parse_repo_reference(user_input).or_else(|| parse_repo_reference(&input_en)) {
+                Some(repo) => match fetch_repo_insight(&repo).await {
+                    Ok(insight) => {
+                        println!("✅ GitHub: обзор {}", insight.repository.full_name);
+                        sources.push(Citation::from_repo(&insight.repository.name, &insight.repository.url));
+                        format_insight(&insight)
+                    }
+                    Err(GithubError::NotFound) => {
+                        error_kind = Some("github_not_found");
+                        format!("🔍 Репозиторий **{}** не найден на GitHub. Проверьте название.", repo.full_name())
+                    }
+                    Err(e) => {
+                        println!("❌ Ошибка GitHub: {}", e);
+                        error_kind = Some("github_api");
+                        github_error_message(&e)
+                    }
+                },
+                None => "🔍 Пришлите ссылку на репозиторий или его имя в виде owner/repo.".to_string(),
             }
         }
-    } 
-    else {
-        // Простые ответы на часто задаваемые вопросы
-        generate_simple_response(&input_en)
+        // Простые ответы на часто задаваемые вопросы; длинное сообщение ответ может процитировать
+        Intent::SmallTalk => {
+            quotes_user = true;
+            generate_simple_response(&input_en, &settings)
+        }
     };
 
-    // Сохраняем ответ в кэш и переводим обратно
-    save_to_cache(&input_en, &response_en).await;
-    let final_response = translate_if_needed(&response_en, &lang).await;
+    // Сохраняем ответ в кэш (ошибки не кэшируем) и переводим обратно
+    if use_cache && error_kind.is_none() {
+        save_to_cache(&cache_key, &response_en).await;
+    }
+    let final_response = if quotes_user {
+        finalize_quoting_response(&response_en, response_lang, &reply_lang, &settings).await
+    } else {
+        finalize_response(&response_en, response_lang, &reply_lang, &settings).await
+    };
+    dialog.record_sources(sources);
     dialog.add_message("bot", &final_response);
+    track_interaction(dialog, &lang, intent, started, false, error_kind);
     final_response
 }
 
+/// Выбирает навык для ответа на (переведённое на английский) сообщение
+pub fn classify_intent(input_en: &str) -> Intent {
+    let input_lower = input_en.to_lowercase();
+
+    if is_cite_request(input_en) {
+        Intent::Citation
+    } else if parse_repo_reference(input_en).is_some() {
+        Intent::RepoInsight
+    } else if input_lower.starts_with("what is") || input_lower.contains("что такое") {
+        Intent::Wikipedia
+    } else if is_arxiv_query(input_en) {
+        Intent::Arxiv
+    } else if is_math_expression(input_en) {
+        Intent::Math
+    } else if is_weather_query(input_en) {
+        Intent::Weather
+    } else if is_repo_search(input_en) {
+        // Раньше кода: в поиске репозиториев часто назван язык («web frameworks in rust»)
+        Intent::Github
+    } else if detect_code_query(input_en).is_some() {
+        Intent::Code
+    } else if mentions(input_en, Topic::Github) {
+        Intent::Github
+    } else {
+        Intent::SmallTalk
+    }
+}
+
+/// Ключ кэша: ответы о погоде и справки зависят от настроек пользователя (погода — и от языка ответа),
+/// справки — ещё и от языкового раздела Википедии
+fn cache_key(input_en: &str, intent: Intent, settings: &UserSettings, wiki_lang: Language, reply_lang: &str) -> String {
+    match intent {
+        Intent::Weather => format!(
+            "{}|{}|{}|{}|{}",
+            input_en,
+            reply_lang,
+            settings.units.as_str(),
+            settings.verbosity.as_str(),
+            settings.default_city.as_deref().unwrap_or("")
+        ),
+        Intent::Wikipedia => format!("{}|{}|{}", input_en, wiki_lang.code(), settings.verbosity.as_str()),
+        _ => input_en.to_string(),
+    }
+}
+
+/// Ссылки на источники последнего ответа с источниками (`None`, если источников не было)
+pub fn citations_reply(dialog: &DialogContext) -> Option<String> {
+    dialog.sources.as_ref().map(|sources| format_citations(&sources.citations))
+}
+
+/// Ссылки на источники ответа с индексом `answer` в истории (кнопка «Цитировать» под ним).
+/// `None`, если с тех пор были ответы с другими источниками.
+pub fn citations_for_answer(dialog: &DialogContext, answer: usize) -> Option<String> {
+    dialog
+        .sources
+        .as_ref()
+        .filter(|sources| sources.answer == answer)
+        .map(|sources| format_citations(&sources.citations))
+}
+
+/// Вариант, выбранный кнопкой под списком значений (Telegram): `index` — номер варианта с нуля.
+/// Возвращает `None`, если список уже неактуален.
+pub async fn choose_disambiguation_option(dialog: &mut DialogContext, index: usize) -> Option<String> {
+    let disambiguation = dialog.pending_disambiguation.take()?;
+    let option = disambiguation.options.get(index)?.clone();
+    dialog.add_message("user", &option);
+    Some(answer_disambiguation(dialog, disambiguation, index, Instant::now()).await)
+}
+
+/// Справка по варианту `index` со страницы значений
+async fn answer_disambiguation(
+    dialog: &mut DialogContext,
+    disambiguation: Disambiguation,
+    index: usize,
+    started: Instant,
+) -> String {
+    let settings = dialog.settings.clone();
+    let lang = disambiguation.language;
+    let reply_lang = settings.reply_language.clone().unwrap_or_else(|| lang.code());
+    let title = &disambiguation.options[index];
+    println!("📖 Выбран вариант «{}» ({})", title, lang);
+
+    let mut error_kind = None;
+    let response = match fetch_wikipedia_page(lang, title).await {
+        Ok(WikiLookup::Article(article)) => {
+            dialog.continuation = Some(Continuation::Reading(WikiReading::new(&article)));
+            dialog.record_sources(vec![Citation::from_article(&article)]);
+            finalize_response(&article_summary(&article, &settings), article.language, &reply_lang, &settings).await
+        }
+        Ok(WikiLookup::Ambiguous(nested)) => disambiguation_prompt(dialog, nested, &reply_lang).await,
+        Err(e) => {
+            println!("❌ Ошибка Wikipedia: {}", e);
+            error_kind = Some("wikipedia_api");
+            finalize_response(&generate_fallback_response(title), Language::RUSSIAN, &reply_lang, &settings).await
+        }
+    };
+
+    dialog.add_message("bot", &response);
+    track_interaction(dialog, &lang.code(), Intent::Wikipedia, started, false, error_kind);
+    response
+}
+
+/// Следующий раздел, оглавление или выбранный раздел статьи, которую читает пользователь
+async fn answer_reading(dialog: &mut DialogContext, request: ReadingRequest, started: Instant) -> String {
+    let Some(Continuation::Reading(mut reading)) = dialog.continuation.take() else {
+        return String::new();
+    };
+    let settings = dialog.settings.clone();
+    let reply_lang = settings.reply_language.clone().unwrap_or_else(|| reading.language.code());
+    println!("📖 Чтение «{}» ({}): {:?}", reading.title, reading.language, request);
+
+    let mut error_kind = None;
+    if reading.sections.is_none() {
+        match fetch_wikipedia_sections(reading.language, &reading.title).await {
+            Ok(sections) => reading.sections = Some(sections),
+            Err(e) => {
+                println!("❌ Ошибка загрузки разделов: {}", e);
+                error_kind = Some("wikipedia_api");
+            }
+        }
+    }
+
+    // Тексты статьи — на языке раздела Википедии, служебные сообщения — на русском
+    let (text, source) = if error_kind.is_some() {
+        (format!("😔 Не удалось загрузить разделы статьи «{}». Попробуйте позже.", reading.title), Language::RUSSIAN)
+    } else if reading.sections.as_ref().is_some_and(Vec::is_empty) {
+        (format!("📖 В статье «{}» нет других разделов.", reading.title), Language::RUSSIAN)
+    } else {
+        match request {
+            ReadingRequest::Contents => (reading.table_of_contents(), reading.language),
+            ReadingRequest::Next | ReadingRequest::Section(_) => {
+                let section = match request {
+                    ReadingRequest::Section(index) => reading.select(index).cloned(),
+                    _ => reading.next_section().cloned(),
+                };
+                match section {
+                    Some(section) => (reading.format_section(&section), reading.language),
+                    None => (
+                        format!("📖 Это был последний раздел статьи «{}». Оглавление — «содержание».", reading.title),
+                        Language::RUSSIAN,
+                    ),
+                }
+            }
+        }
+    };
+
+    let response = finalize_response(&text, source, &reply_lang, &settings).await;
+    let lang = reading.language.code();
+    dialog.record_sources(vec![Citation::wikipedia(&reading.title, reading.language)]);
+    dialog.continuation = Some(Continuation::Reading(reading));
+    dialog.add_message("bot", &response);
+    track_interaction(dialog, &lang, Intent::Wikipedia, started, false, error_kind);
+    response
+}
+
+/// Следующая страница последнего поиска arXiv
+async fn answer_arxiv_page(dialog: &mut DialogContext, started: Instant) -> String {
+    let Some(Continuation::Arxiv(previous)) = dialog.continuation.take() else {
+        return String::new();
+    };
+    let query = previous.next_page();
+    let settings = dialog.settings.clone();
+    let lang = dialog.current_language().unwrap_or(Language::ENGLISH);
+    let reply_lang = settings.reply_language.clone().unwrap_or_else(|| lang.code());
+    println!("📚 arXiv, следующая страница: {:?}", query);
+
+    let mut error_kind = None;
+    // Список статей — на английском, служебные сообщения — на русском
+    let (text, source) = match search_arxiv(&query).await {
+        Ok(papers) if !papers.is_empty() => {
+            let list = format_papers(&papers, query.start);
+            dialog.record_sources(papers.iter().map(Citation::from_paper).collect());
+            dialog.continuation = Some(Continuation::Arxiv(query));
+            (list, Language::ENGLISH)
+        }
+        Ok(_) => ("📚 Больше статей по этому запросу нет.".to_string(), Language::RUSSIAN),
+        Err(e) => {
+            println!("❌ Ошибка arXiv: {}", e);
+            error_kind = Some("arxiv_api");
+            // Страницу можно будет запросить ещё раз
+            dialog.continuation = Some(Continuation::Arxiv(previous));
+            ("Извините, arXiv сейчас недоступен. Попробуйте позже! 🔧".to_string(), Language::RUSSIAN)
+        }
+    };
+
+    let response = finalize_response(&text, source, &reply_lang, &settings).await;
+    dialog.add_message("bot", &response);
+    track_interaction(dialog, &lang.code(), Intent::Arxiv, started, false, error_kind);
+    response
+}
+
+/// Следующая страница последнего поиска GitHub
+async fn answer_github_page(dialog: &mut DialogContext, started: Instant) -> String {
+    let Some(Continuation::Github(previous)) = dialog.continuation.take() else {
+        return String::new();
+    };
+    let query = previous.next_page();
+    let settings = dialog.settings.clone();
+    let lang = dialog.current_language().unwrap_or(Language::ENGLISH);
+    let reply_lang = settings.reply_language.clone().unwrap_or_else(|| lang.code());
+    println!("🔍 GitHub, страница {}: {}", query.page, query.to_query_string());
+
+    let mut error_kind = None;
+    // Список репозиториев — на английском, служебные сообщения — на русском
+    let (text, source) = match search_github_repos(&query).await {
+        Ok(search) if !search.repositories.is_empty() => {
+            let list = format_repositories(&search, &query);
+            dialog.record_sources(search.repositories.iter().map(|repo| Citation::from_repo(&repo.name, &repo.url)).collect());
+            dialog.continuation = Some(Continuation::Github(query));
+            (list, Language::ENGLISH)
+        }
+        Ok(_) => ("🔍 Больше репозиториев по этому запросу нет.".to_string(), Language::RUSSIAN),
+        Err(e) => {
+            println!("❌ Ошибка GitHub: {}", e);
+            error_kind = Some("github_api");
+            dialog.continuation = Some(Continuation::Github(previous));
+            (github_error_message(&e), Language::RUSSIAN)
+        }
+    };
+
+    let response = finalize_response(&text, source, &reply_lang, &settings).await;
+    dialog.add_message("bot", &response);
+    track_interaction(dialog, &lang.code(), Intent::Github, started, false, error_kind);
+    response
+}
+
+/// Сообщение пользователю об ошибке GitHub: об исчерпанном лимите говорим прямо
+fn github_error_message(error: &GithubError) -> String {
+    match error {
+        GithubError::RateLimited { .. } => error.to_string(),
+        _ => "Извините, сейчас GitHub недоступен. Попробуйте позже! 🔧".to_string(),
+    }
+}
+
+/// Предлагает выбрать вариант со страницы значений и запоминает варианты в диалоге.
+/// Переводится только вступление: названия статей остаются как в разделе Википедии.
+async fn disambiguation_prompt(dialog: &mut DialogContext, disambiguation: Disambiguation, reply_lang: &str) -> String {
+    println!("🔀 Страница значений «{}»: {} вариантов", disambiguation.title, disambiguation.options.len());
+    let intro = translate_if_needed(
+        &format!("🔀 \"{}\" may refer to several articles. Which one did you mean? Reply with its number.", disambiguation.title),
+        Language::ENGLISH,
+        reply_lang,
+        dialog.settings.formality,
+    )
+    .await;
+    let prompt = format!("{}\n\n{}", intro, disambiguation.format());
+    dialog.pending_disambiguation = Some(disambiguation);
+    prompt
+}
+
+/// Текст справки с учётом настройки подробности
+fn article_summary(article: &WikiArticle, settings: &UserSettings) -> String {
+    if settings.verbosity == Verbosity::Brief {
+        shorten_summary(&article.format())
+    } else {
+        article.format()
+    }
+}
+
+/// Сокращает справку для режима «кратко»: заголовок и первые два предложения
+fn shorten_summary(summary: &str) -> String {
+    let (title, body) = summary.split_once("\n\n").unwrap_or(("", summary));
+    let sentences = split_into_sentences(body);
+    if sentences.len() <= 2 {
+        return summary.to_string();
+    }
+    let short = sentences[..2].join(" ");
+    if title.is_empty() {
+        short
+    } else {
+        format!("{}\n\n{}", title, short)
+    }
+}
+
+/// Переводит ответ с языка `source` на язык пользователя и применяет настройки оформления
+async fn finalize_response(text: &str, source: Language, target_lang: &str, settings: &UserSettings) -> String {
+    let translated = translate_if_needed(text, source, target_lang, settings.formality).await;
+    apply_style(translated, settings)
+}
+
+/// Как [`finalize_response`] для ответов, которые цитируют сообщение пользователя:
+/// перевод идёт мимо общей памяти переводов
+async fn finalize_quoting_response(text: &str, source: Language, target_lang: &str, settings: &UserSettings) -> String {
+    let translated = translate_reply(text, source, target_lang, settings.formality, false).await;
+    apply_style(translated, settings)
+}
+
+/// Убирает эмодзи, если пользователь их отключил
+fn apply_style(translated: String, settings: &UserSettings) -> String {
+    if settings.emoji {
+        translated
+    } else {
+        strip_emoji(&translated)
+    }
+}
+
+/// Удаляет эмодзи из ответа, сохраняя переносы строк
+fn strip_emoji(text: &str) -> String {
+    remove_emoji(text)
+        .replace('\u{FE0F}', "")
+        .lines()
+        .map(|line| line.trim_start())
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+/// Записывает взаимодействие в журнал аналитики в фоне, не задерживая ответ
+fn track_interaction(
+    dialog: &DialogContext,
+    lang: &str,
+    intent: Intent,
+    started: Instant,
+    cache_hit: bool,
+    error_kind: Option<&str>,
+) {
+    let user_id = dialog.user_id.as_deref().unwrap_or("anonymous");
+    let record = InteractionRecord {
+        timestamp: Utc::now(),
+        platform: dialog.platform.clone(),
+        user_hash: anonymize_user_id(&dialog.platform, user_id),
+        language: lang.to_string(),
+        intent: intent.as_str().to_string(),
+        latency_ms: started.elapsed().as_millis() as i64,
+        cache_hit,
+        error_kind: error_kind.map(|e| e.to_string()),
+    };
+
+    tokio::spawn(async move {
+        record_interaction(&record).await;
+    });
+}
+
 /// Генерирует резервный ответ когда внешние API недоступны
 fn generate_fallback_response(query: &str) -> String {
     if query.to_lowercase().contains("rust") {
@@ -158,32 +741,87 @@ fn generate_fallback_response(query: &str) -> String {
     }
 }
 
+/// Темы, которые узнаём по ключевым словам
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Topic {
+    Github,
+    Greeting,
+    HowAreYou,
+    Thanks,
+    Bye,
+    About,
+    Help,
+    Question,
+    Love,
+    Hate,
+    Weather,
+    Time,
+    Learn,
+}
+
+impl Topic {
+    /// Все темы в порядке объявления (индекс — номер варианта)
+    const ALL: [Topic; 13] = [
+        Topic::Github, Topic::Greeting, Topic::HowAreYou, Topic::Thanks, Topic::Bye, Topic::About, Topic::Help,
+        Topic::Question, Topic::Love, Topic::Hate, Topic::Weather, Topic::Time, Topic::Learn,
+    ];
+
+    fn keywords(self) -> &'static [&'static str] {
+        match self {
+            Topic::Github => &["rust example", "github", "code", "repository", "repositories", "repos"],
+            Topic::Greeting => &["hello", "привет", "hi"],
+            Topic::HowAreYou => &["how are you", "как дела"],
+            Topic::Thanks => &["thank", "спасибо"],
+            Topic::Bye => &["bye", "пока", "до свидания"],
+            Topic::About => &["what are you", "кто ты", "что ты"],
+            Topic::Help => &["help", "помощь", "что умеешь"],
+            Topic::Question => &["как", "что", "where", "how", "what"],
+            Topic::Love => &["love", "люблю"],
+            Topic::Hate => &["hate", "ненавижу"],
+            Topic::Weather => &["weather", "погода"],
+            Topic::Time => &["time", "время"],
+            Topic::Learn => &["learn", "учить", "изучать"],
+        }
+    }
+
+    /// Ключевые слова темы (собираются один раз на все сообщения)
+    fn keyword_set(self) -> &'static KeywordSet {
+        static SETS: OnceLock<Vec<KeywordSet>> = OnceLock::new();
+        &SETS.get_or_init(|| Topic::ALL.iter().map(|topic| KeywordSet::new(topic.keywords())).collect())[self as usize]
+    }
+}
+
+/// Есть ли в тексте одно из слов или фраз темы (по основам и границам слов, с допуском опечаток)
+fn mentions(input: &str, topic: Topic) -> bool {
+    topic.keyword_set().matches_fuzzy(input, default_matcher())
+}
+
 /// Генерирует простые ответы на общие вопросы и ведет диалог как ИИ
-fn generate_simple_response(input: &str) -> String {
+fn generate_simple_response(input: &str, settings: &UserSettings) -> String {
     let input_lower = input.to_lowercase();
     
     // Приветствия
-    if input_lower.contains("hello") || input_lower.contains("привет") || input_lower.contains("hi") {
+    if mentions(input, Topic::Greeting) {
         "👋 Привет! Как дела? О чём поговорим?".to_string()
     } 
     // Состояние бота
-    else if input_lower.contains("how are you") || input_lower.contains("как дела") {
+    else if mentions(input, Topic::HowAreYou) {
         "Отлично! Готов помочь с любыми вопросами. Что тебя интересует? 😊".to_string()
     }
     // Благодарности
-    else if input_lower.contains("thank") || input_lower.contains("спасибо") {
+    else if mentions(input, Topic::Thanks) {
         "Пожалуйста! Рад был помочь! Есть ещё вопросы? 😊".to_string()
     }
     // Прощания
-    else if input_lower.contains("bye") || input_lower.contains("пока") || input_lower.contains("до свидания") {
+    else if mentions(input, Topic::Bye) {
         "До свидания! Удачного дня! Обращайся, если что-то понадобится! 👋".to_string()
     }
     // Вопросы о боте - ОБНОВЛЕННЫЙ СПИСОК НАВЫКОВ
-    else if input_lower.contains("what are you") || input_lower.contains("кто ты") || input_lower.contains("что ты") {
+    else if mentions(input, Topic::About) {
         "Я Auraya - умный ИИ-ассистент! 🤖 Могу помочь с поиском информации, ответить на вопросы о программировании, найти репозитории на GitHub, решить математические задачи, узнать погоду и многое другое. Чем могу быть полезен?".to_string()
     }
     // Помощь - ОБНОВЛЕННЫЙ СПИСОК
-    else if input_lower.contains("help") || input_lower.contains("помощь") || input_lower.contains("что умеешь") {
+    else if mentions(input, Topic::Help) {
         "Я умею:\n• 🔍 Искать информацию в Wikipedia\n• 💻 Находить репозитории на GitHub\n• 🧮 Решать математические задачи\n• 🌤️ Узнавать погоду в городах\n• 💡 Показывать примеры кода\n• 🌍 Переводить тексты\n• 💬 Поддерживать диалог на разных языках\n\nПросто задавай вопросы или используй команды /help!".to_string()
     }
     // Короткие сообщения
@@ -197,31 +835,34 @@ fn generate_simple_response(input: &str) -> String {
         responses[input.len() % responses.len()].to_string()
     }
     // Вопросы
-    else if input_lower.contains('?') || input_lower.contains("как") || input_lower.contains("что") || input_lower.contains("where") || input_lower.contains("how") || input_lower.contains("what") {
+    else if input_lower.contains('?') || mentions(input, Topic::Question) {
         "Хороший вопрос! 🤔 Попробуй спросить более конкретно:\n\n• 📖 \"Что такое Rust?\" - поиск в Wikipedia\n• 🧮 \"2 + 2 * 3\" - математические вычисления\n• 🌤️ \"Погода в Москве\" - прогноз погоды\n• 💻 \"Примеры кода на Python\" - поиск репозиториев\n\nЯ постараюсь найти информацию!".to_string()
     }
     // Эмоции
-    else if input_lower.contains("love") || input_lower.contains("люблю") {
+    else if mentions(input, Topic::Love) {
         "Приятно слышать! 😊 А что именно тебя вдохновляет?".to_string()
     }
-    else if input_lower.contains("hate") || input_lower.contains("ненавижу") {
+    else if mentions(input, Topic::Hate) {
         "Понимаю, бывают сложные моменты. Может, поговорим о чём-то более позитивном? 🌟".to_string()
     }
     // Общие темы - ОБНОВЛЕНЫ ДЛЯ НОВЫХ НАВЫКОВ
-    else if input_lower.contains("weather") || input_lower.contains("погода") {
+    else if mentions(input, Topic::Weather) {
         "🌤️ Хочешь узнать погоду? Скажи мне город!\n\nПример: \"Погода в Москве\" или \"Weather in London\" 🏙️".to_string()
     }
-    else if input_lower.contains("time") || input_lower.contains("время") {
-        "Время лучше проверить на своём устройстве! ⏰ А у меня есть время помочь тебе с любыми вопросами!".to_string()
+    else if mentions(input, Topic::Time) {
+        match settings.local_time() {
+            Some(time) => format!("⏰ Сейчас {}", time),
+            None => "Время лучше проверить на своём устройстве! ⏰ Или задай часовой пояс: /settings tz Europe/Moscow".to_string(),
+        }
     }
     // Обучение и знания
-    else if input_lower.contains("learn") || input_lower.contains("учить") || input_lower.contains("изучать") {
+    else if mentions(input, Topic::Learn) {
         "Обучение - это здорово! 📚 Что хочешь изучить?\n\n• 💻 Программирование - покажу примеры кода\n• 🧮 Математика - решу задачи\n• 🌍 Технологии - найдю информацию\n\nПросто спроси конкретно!".to_string()
     }
     // Длинные сообщения
-    else if input.len() > 50 {
+    else if grapheme_count(input) > 50 {
         format!("Понимаю, что ты говоришь о \"{}\". Это интересная тема! 🤔 Попробуй задать более конкретный вопрос - возможно, смогу помочь с:\n\n• 📖 Поиском информации\n• 🧮 Вычислениями\n• 💻 Примерами кода\n• 🌤️ Погодой", 
-                &input[..input.len().min(40)])
+                truncate(input, 40))
     }
     // Общий ответ
     else {
@@ -235,14 +876,26 @@ fn generate_simple_response(input: &str) -> String {
     }
 }
 
-/// Переводит текст, если требуется, на язык пользователя
-async fn translate_if_needed(text: &str, target_lang: &str) -> String {
-    if target_lang != "EN" {
-        match translate_text(text, target_lang).await {
+/// Переводит текст, если требуется, на язык пользователя (с его формальностью обращения)
+async fn translate_if_needed(text: &str, source: Language, target_lang: &str, formality: Option<Formality>) -> String {
+    translate_reply(text, source, target_lang, formality, true).await
+}
+
+/// Перевод ответа; `use_memory = false` — мимо общей памяти переводов
+async fn translate_reply(text: &str, source: Language, target_lang: &str, formality: Option<Formality>, use_memory: bool) -> String {
+    // Ответ уже на нужном языке; `EN-GB` для английского и т.п. тоже не переводим
+    if Language::parse(target_lang) != Some(source) {
+        let chain = default_chain();
+        let source_code = source.code();
+        let result = if use_memory {
+            chain.translate_markup_with(text, Some(&source_code), target_lang, formality).await
+        } else {
+            chain.translate_markup_private(text, Some(&source_code), target_lang, formality).await
+        };
+        match result {
             Ok(translated) => translated,
             Err(e) => {
                 println!("⚠️ Ошибка перевода ответа: {} | Используем fallback", e);
-                // Можно добавить простой переводчик-заглушку
                 text.to_string()
             }
         }
@@ -251,4 +904,59 @@ async fn translate_if_needed(text: &str, target_lang: &str) -> String {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
 
+    #[test]
+    fn test_topic_order() {
+        for (i, topic) in Topic::ALL.iter().enumerate() {
+            assert_eq!(*topic as usize, i);
+        }
+    }
+
+    #[test]
+    fn test_small_talk_typos() {
+        let settings = UserSettings::default();
+        let thanks = generate_simple_response("thanks!", &settings);
+        assert!(thanks.starts_with("Пожалуйста"));
+        for phrase in ["I think so", "I think rust is cool", "thinking about it"] {
+            assert_ne!(generate_simple_response(phrase, &settings), thanks, "{}", phrase);
+        }
+    }
+
+    #[test]
+    fn test_citations_for_answer() {
+        let mut dialog = DialogContext::new();
+        dialog.add_message("user", "what is rust");
+        dialog.record_sources(vec![Citation::wikipedia("Rust", Language::ENGLISH)]);
+        dialog.add_message("bot", "Rust is a language");
+        assert!(citations_for_answer(&dialog, 1).is_some());
+
+        dialog.add_message("user", "papers about rust");
+        dialog.record_sources(vec![Citation::wikipedia("Ferris", Language::ENGLISH)]);
+        dialog.add_message("bot", "1. Ferris");
+        // Кнопка под старым ответом не цитирует источники нового
+        assert_eq!(citations_for_answer(&dialog, 1), None);
+        assert!(citations_for_answer(&dialog, 3).is_some_and(|reply| reply.contains("Ferris")));
+    }
+
+    #[test]
+    fn test_classify_intent() {
+        assert_eq!(classify_intent("pogoda v moskve"), Intent::Weather);
+        assert_eq!(classify_intent("weather in London"), Intent::Weather);
+        assert_eq!(classify_intent("let's go home"), Intent::SmallTalk);
+        assert_eq!(classify_intent("I need a swift answer"), Intent::SmallTalk);
+        assert_eq!(classify_intent("show me an example in go"), Intent::Code);
+        assert_eq!(classify_intent("swift code for parsing json"), Intent::Code);
+        assert_eq!(classify_intent("popular web frameworks in rust with more than 1000 stars"), Intent::Github);
+        assert_eq!(classify_intent("find github repos for web frameworks in rust"), Intent::Github);
+        assert_eq!(classify_intent("how to read a file in rust"), Intent::Code);
+        assert_eq!(classify_intent("how do I use Math.floor in JavaScript"), Intent::Code);
+        assert_eq!(classify_intent("python math.sqrt example"), Intent::Code);
+        assert_ne!(classify_intent("how to fold a paper airplane"), Intent::Arxiv);
+        assert_eq!(classify_intent("papers about diffusion models"), Intent::Arxiv);
+        assert_eq!(classify_intent("tell me about CI/CD"), Intent::SmallTalk);
+        assert_eq!(classify_intent("tell me about tokio-rs/axum"), Intent::RepoInsight);
+    }
+}
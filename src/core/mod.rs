@@ -2,3 +2,4 @@ pub mod dialog;
 pub mod logic;
 pub mod memory;
 pub mod planner;
+pub mod settings;
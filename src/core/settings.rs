@@ -0,0 +1,292 @@
+use chrono::Utc;
+use chrono_tz::Tz;
+
+use crate::language::{
+    code::normalize_code,
+    segment::{grapheme_count, truncate},
+    translator::Formality,
+};
+use crate::skills::weather::normalize_city;
+
+/// Максимальная длина названия города по умолчанию (в символах)
+const MAX_CITY_LEN: usize = 64;
+
+/// Система единиц измерения для погоды и конвертаций
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Units {
+    Metric,
+    Imperial,
+}
+
+impl Units {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Units::Metric => "metric",
+            Units::Imperial => "imperial",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "metric" | "метрические" | "c" => Some(Units::Metric),
+            "imperial" | "имперские" | "f" => Some(Units::Imperial),
+            _ => None,
+        }
+    }
+
+    /// Значение параметра `units` для OpenWeather
+    pub fn api_param(&self) -> &'static str {
+        self.as_str()
+    }
+
+    pub fn temperature_symbol(&self) -> &'static str {
+        match self {
+            Units::Metric => "°C",
+            Units::Imperial => "°F",
+        }
+    }
+
+    pub fn wind_speed_unit(&self) -> &'static str {
+        match self {
+            Units::Metric => "м/с",
+            Units::Imperial => "mph",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Units::Metric => "метрические (°C, м/с)",
+            Units::Imperial => "имперские (°F, mph)",
+        }
+    }
+}
+
+/// Подробность ответов
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verbosity {
+    Brief,
+    Normal,
+    Detailed,
+}
+
+impl Verbosity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Verbosity::Brief => "brief",
+            Verbosity::Normal => "normal",
+            Verbosity::Detailed => "detailed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "brief" | "кратко" => Some(Verbosity::Brief),
+            "normal" | "обычно" => Some(Verbosity::Normal),
+            "detailed" | "подробно" => Some(Verbosity::Detailed),
+            _ => None,
+        }
+    }
+
+    /// Следующее значение по кругу (для кнопки-переключателя)
+    pub fn next(&self) -> Self {
+        match self {
+            Verbosity::Brief => Verbosity::Normal,
+            Verbosity::Normal => Verbosity::Detailed,
+            Verbosity::Detailed => Verbosity::Brief,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Verbosity::Brief => "кратко",
+            Verbosity::Normal => "обычно",
+            Verbosity::Detailed => "подробно",
+        }
+    }
+}
+
+/// Персональные настройки пользователя
+#[derive(Debug, Clone, PartialEq)]
+pub struct UserSettings {
+    pub reply_language: Option<String>, // None — отвечать на языке сообщения
+    pub units: Units,
+    pub timezone: Option<String>,       // IANA, например "Europe/Moscow"
+    pub default_city: Option<String>,
+    pub verbosity: Verbosity,
+    pub emoji: bool,
+    pub formality: Option<Formality>, // None — как настроено для развёртывания
+}
+
+impl Default for UserSettings {
+    fn default() -> Self {
+        Self {
+            reply_language: None,
+            units: Units::Metric,
+            timezone: None,
+            default_city: None,
+            verbosity: Verbosity::Normal,
+            emoji: true,
+            formality: None,
+        }
+    }
+}
+
+impl UserSettings {
+    /// Применяет изменение вида `ключ значение` (из команды `/settings` или кнопки).
+    /// Пустое значение или `auto`/`off` сбрасывает необязательные настройки.
+    pub fn apply(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let value = value.trim();
+        let reset = value.is_empty() || matches!(value.to_lowercase().as_str(), "auto" | "off" | "none" | "-");
+
+        match key.trim().to_lowercase().as_str() {
+            "lang" | "language" | "язык" => {
+                if reset {
+                    self.reply_language = None;
+                } else {
+                    let code = normalize_code(value)
+                        .ok_or_else(|| format!("Неизвестный код языка «{}». Пример: EN, RU, DE, PT-BR", value))?;
+                    self.reply_language = Some(code);
+                }
+            }
+            "units" | "единицы" => {
+                self.units = Units::parse(value)
+                    .ok_or_else(|| "Единицы: metric или imperial".to_string())?;
+            }
+            "tz" | "timezone" | "пояс" => {
+                if reset {
+                    self.timezone = None;
+                } else {
+                    let tz: Tz = value
+                        .parse()
+                        .map_err(|_| format!("Неизвестный часовой пояс «{}». Пример: Europe/Moscow", value))?;
+                    self.timezone = Some(tz.name().to_string());
+                }
+            }
+            "city" | "город" => {
+                self.default_city = if reset { None } else { Some(parse_city(value)?) };
+            }
+            "verbosity" | "подробность" => {
+                self.verbosity = Verbosity::parse(value)
+                    .ok_or_else(|| "Подробность: brief, normal или detailed".to_string())?;
+            }
+            "emoji" | "эмодзи" => {
+                self.emoji = match value.to_lowercase().as_str() {
+                    "on" | "вкл" | "true" | "1" => true,
+                    "off" | "выкл" | "false" | "0" => false,
+                    _ => return Err("Эмодзи: on или off".to_string()),
+                };
+            }
+            "formality" | "обращение" => {
+                self.formality = match Formality::parse(value) {
+                    _ if reset => None,
+                    Some(Formality::Default) => None,
+                    Some(formality) => Some(formality),
+                    None => return Err("Обращение: formal (на «вы»), informal (на «ты») или auto".to_string()),
+                };
+            }
+            other => return Err(format!("Неизвестная настройка «{}»", other)),
+        }
+
+        Ok(())
+    }
+
+    /// Текущее время в часовом поясе пользователя, если он задан
+    pub fn local_time(&self) -> Option<String> {
+        let tz: Tz = self.timezone.as_deref()?.parse().ok()?;
+        Some(Utc::now().with_timezone(&tz).format("%H:%M (%Z)").to_string())
+    }
+
+    /// Краткое описание настроек для пользователя
+    pub fn describe(&self) -> String {
+        format!(
+            "⚙️ **Ваши настройки**\n\n\
+            🌍 Язык ответов: {}\n\
+            📏 Единицы: {}\n\
+            🕒 Часовой пояс: {}\n\
+            🏙️ Город по умолчанию: {}\n\
+            📝 Подробность: {}\n\
+            😊 Эмодзи: {}\n\
+            🎩 Обращение: {}",
+            self.reply_language.as_deref().unwrap_or("автоопределение"),
+            self.units.label(),
+            self.timezone.as_deref().unwrap_or("не задан"),
+            self.default_city.as_deref().unwrap_or("не задан"),
+            self.verbosity.label(),
+            if self.emoji { "вкл" } else { "выкл" },
+            self.formality.unwrap_or_default().label()
+        )
+    }
+}
+
+/// Проверяет название города из настроек и приводит его к именительному падежу
+fn parse_city(value: &str) -> Result<String, String> {
+    let city = value.split_whitespace().collect::<Vec<_>>().join(" ");
+    if grapheme_count(&city) > MAX_CITY_LEN {
+        return Err(format!(
+            "Слишком длинное название города «{}» — не больше {} символов",
+            truncate(&city, 30),
+            MAX_CITY_LEN
+        ));
+    }
+    let allowed = |c: char| c.is_alphabetic() || c == ' ' || matches!(c, '-' | '\'' | '’' | '.');
+    if !city.chars().all(allowed) || !city.chars().any(char::is_alphabetic) {
+        return Err(format!("Не похоже на название города: «{}». Пример: /settings city Москва", truncate(&city, 30)));
+    }
+    Ok(normalize_city(&city))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_settings() {
+        let mut settings = UserSettings::default();
+        settings.apply("lang", "de").unwrap();
+        settings.apply("units", "imperial").unwrap();
+        settings.apply("tz", "Europe/Berlin").unwrap();
+        settings.apply("city", "Berlin").unwrap();
+        settings.apply("emoji", "off").unwrap();
+
+        assert_eq!(settings.reply_language.as_deref(), Some("DE"));
+        assert_eq!(settings.units, Units::Imperial);
+        assert_eq!(settings.timezone.as_deref(), Some("Europe/Berlin"));
+        assert_eq!(settings.default_city.as_deref(), Some("Berlin"));
+        assert!(!settings.emoji);
+        assert!(settings.local_time().is_some());
+
+        settings.apply("city", "  нижнем   новгороде ").unwrap();
+        assert_eq!(settings.default_city.as_deref(), Some("Нижний Новгород"));
+
+        settings.apply("lang", "pt_br").unwrap();
+        assert_eq!(settings.reply_language.as_deref(), Some("PT-BR"));
+
+        settings.apply("lang", "auto").unwrap();
+        assert_eq!(settings.reply_language, None);
+
+        settings.apply("formality", "ты").unwrap();
+        assert_eq!(settings.formality, Some(Formality::Less));
+        settings.apply("обращение", "auto").unwrap();
+        assert_eq!(settings.formality, None);
+    }
+
+    #[test]
+    fn test_apply_invalid() {
+        let mut settings = UserSettings::default();
+        assert!(settings.apply("tz", "Mars/Olympus").is_err());
+        assert!(settings.apply("units", "parsecs").is_err());
+        assert!(settings.apply("colour", "red").is_err());
+        assert!(settings.apply("lang", "xx").is_err());
+        assert!(settings.apply("formality", "sometimes").is_err());
+        assert!(settings.apply("city", &"Москва".repeat(20)).is_err());
+        assert!(settings.apply("city", "<script>alert(1)</script>").is_err());
+        assert!(settings.apply("city", "42").is_err());
+        assert_eq!(settings, UserSettings::default());
+    }
+
+    #[test]
+    fn test_verbosity_cycle() {
+        assert_eq!(Verbosity::Brief.next(), Verbosity::Normal);
+        assert_eq!(Verbosity::Detailed.next(), Verbosity::Brief);
+    }
+}
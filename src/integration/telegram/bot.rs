@@ -1,5 +1,7 @@
 use teloxide::{prelude::*, utils::command::BotCommands};
-use crate::integration::telegram::handler::{handle_command, handle_message};
+use crate::integration::telegram::handler::{
+    handle_callback_query, handle_command, handle_message, handle_unknown_command,
+};
 
 #[derive(BotCommands, Clone)]
 #[command(rename_rule = "lowercase", description = "Telegram-команды, доступные пользователю")]
@@ -10,6 +12,14 @@ pub enum Command {
     Start,
     #[command(description = "Задать вопрос боту")]
     Ask(String),
+    #[command(description = "Статистика использования: /stats [ГГГГ-ММ-ДД] [ГГГГ-ММ-ДД]")]
+    Stats(String),
+    #[command(description = "Настройки: /settings [city|tz|lang|units|verbosity|emoji|formality значение]")]
+    Settings(String),
+    #[command(description = "Выгрузить все мои данные (JSON)")]
+    MyData,
+    #[command(description = "Удалить все мои данные")]
+    Forget,
 }
 
 pub async fn run_bot() {
@@ -20,16 +30,21 @@ pub async fn run_bot() {
 
     Dispatcher::builder(
         bot,
-        Update::filter_message()
+        dptree::entry()
             .branch(
-                dptree::entry()
-                    .filter_command::<Command>()
-                    .endpoint(handle_command), // Обработка команд
+                Update::filter_message()
+                    .branch(
+                        dptree::entry()
+                            .filter_command::<Command>()
+                            .endpoint(handle_command), // Обработка команд
+                    )
+                    .branch(
+                        dptree::filter(|msg: Message| !msg.text().unwrap_or("").starts_with('/'))
+                            .endpoint(handle_message), // Обработка обычных сообщений
+                    )
+                    .branch(dptree::endpoint(handle_unknown_command)), // Опечатки в командах
             )
-            .branch(
-                dptree::filter(|msg: Message| !msg.text().unwrap_or("").starts_with('/'))
-                    .endpoint(handle_message), // Обработка обычных сообщений
-            ),
+            .branch(Update::filter_callback_query().endpoint(handle_callback_query)), // Нажатия inline-кнопок
     )
     .dependencies(dptree::deps![])
     .enable_ctrlc_handler()
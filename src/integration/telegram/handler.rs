@@ -1,19 +1,235 @@
-use teloxide::{prelude::*, types::Message, utils::command::BotCommands};
+use teloxide::{
+    prelude::*,
+    types::{InlineKeyboardButton, InlineKeyboardMarkup, InputFile, Message},
+    utils::command::BotCommands,
+};
 use crate::{
     integration::telegram::bot::Command,
-    core::{dialog::DialogContext, logic::smart_answer_multilang},
+    core::{
+        dialog::DialogContext,
+        logic::{choose_disambiguation_option, citations_for_answer, smart_answer_multilang},
+        settings::{Units, UserSettings},
+    },
+    language::{
+        code::{Language, LanguageTag},
+        fuzzy::{default_matcher, did_you_mean},
+    },
+    storage::{
+        analytics::{parse_report_range, usage_report},
+        history::{append_dialog_message, load_dialog_history},
+        privacy::{export_user_data, forget_user},
+        translation_memory::translation_memory,
+        users::{load_user_settings, save_user_settings},
+    },
 };
 use std::collections::HashMap;
 use tokio::sync::Mutex;
-use std::sync::OnceLock;
+use std::sync::{Arc, OnceLock};
+
+/// Диалог пользователя в чате. У каждого диалога своя блокировка: ответ может ждать сети
+/// (GitHub — до сброса лимита), и другие диалоги при этом не должны простаивать
+type ChatState = Arc<Mutex<DialogContext>>;
 
-// Глобальное хранилище состояний диалогов для каждого чата
-static CHAT_STATES: OnceLock<Mutex<HashMap<ChatId, DialogContext>>> = OnceLock::new();
+/// Чат и отправитель: в группе у каждого участника свой диалог, настройки и данные
+type ChatKey = (ChatId, String);
 
-fn get_chat_states() -> &'static Mutex<HashMap<ChatId, DialogContext>> {
+// Глобальное хранилище состояний диалогов для каждого участника каждого чата
+static CHAT_STATES: OnceLock<Mutex<HashMap<ChatKey, ChatState>>> = OnceLock::new();
+
+fn get_chat_states() -> &'static Mutex<HashMap<ChatKey, ChatState>> {
     CHAT_STATES.get_or_init(|| Mutex::new(HashMap::new()))
 }
 
+/// Активный диалог пользователя `user_id` в чате, если он есть.
+/// Общая блокировка держится только на время поиска
+async fn chat_state(chat_id: ChatId, user_id: &str) -> Option<ChatState> {
+    get_chat_states().lock().await.get(&(chat_id, user_id.to_string())).cloned()
+}
+
+/// Активный диалог отправителя сообщения в чате или новый диалог для него
+async fn chat_state_or_new(msg: &Message) -> ChatState {
+    let user_id = sender_id(msg);
+    if let Some(state) = chat_state(msg.chat.id, &user_id).await {
+        return state;
+    }
+    // Настройки читаются из БД — без общей блокировки
+    let dialog = dialog_for(msg).await;
+    get_chat_states()
+        .lock()
+        .await
+        .entry((msg.chat.id, user_id))
+        .or_insert_with(|| Arc::new(Mutex::new(dialog)))
+        .clone()
+}
+
+/// Идентификатор отправителя сообщения (для каналов — id чата)
+fn sender_id(msg: &Message) -> String {
+    match msg.from.as_ref() {
+        Some(user) => user.id.0.to_string(),
+        None => msg.chat.id.0.to_string(),
+    }
+}
+
+/// Язык интерфейса Telegram у отправителя (`language_code`)
+fn client_language(msg: &Message) -> Option<Language> {
+    let code = msg.from.as_ref()?.language_code.as_deref()?;
+    LanguageTag::from_telegram(code).map(|tag| tag.language)
+}
+
+/// Хранить ли историю диалогов в БД (`STORE_DIALOG_HISTORY=1`)
+fn history_enabled() -> bool {
+    matches!(std::env::var("STORE_DIALOG_HISTORY").as_deref(), Ok("1") | Ok("true"))
+}
+
+/// Создаёт контекст диалога для отправителя сообщения с его сохранёнными настройками
+/// (и историей, если её хранение включено)
+async fn dialog_for(msg: &Message) -> DialogContext {
+    let user_id = sender_id(msg);
+    let mut dialog = DialogContext::for_user("telegram", &user_id);
+    dialog.settings = load_user_settings("telegram", &user_id).await;
+    dialog.client_language = client_language(msg);
+
+    if history_enabled() {
+        match load_dialog_history("telegram", &user_id, 20).await {
+            Ok(history) => dialog.history = history,
+            Err(e) => log::error!("{}", e),
+        }
+    }
+    dialog
+}
+
+/// Сохраняет последний обмен репликами (вопрос и ответ) в историю
+async fn persist_exchange(dialog: &DialogContext) {
+    if !history_enabled() {
+        return;
+    }
+    let Some(user_id) = dialog.user_id.as_deref() else {
+        return;
+    };
+
+    let start = dialog.history.len().saturating_sub(2);
+    for message in &dialog.history[start..] {
+        if let Err(e) = append_dialog_message(&dialog.platform, user_id, message).await {
+            log::error!("{}", e);
+        }
+    }
+}
+
+/// Кнопки под ответом ассистента: выбор варианта, если ответ — список значений из Википедии,
+/// или «Цитировать», если у ответа есть источники
+fn reply_keyboard(dialog: &DialogContext) -> Option<InlineKeyboardMarkup> {
+    if let Some(disambiguation) = dialog.pending_disambiguation.as_ref() {
+        return Some(InlineKeyboardMarkup::new(
+            disambiguation
+                .options
+                .iter()
+                .enumerate()
+                .map(|(i, option)| vec![InlineKeyboardButton::callback(format!("{}. {}", i + 1, option), format!("wiki:{}", i))]),
+        ));
+    }
+    dialog.latest_sources().map(|sources| {
+        InlineKeyboardMarkup::new(vec![vec![InlineKeyboardButton::callback(
+            "📑 Цитировать",
+            format!("cite:{}", sources.answer),
+        )]])
+    })
+}
+
+/// Отправляет ответ ассистента вместе с кнопками
+async fn send_reply(bot: &Bot, chat_id: ChatId, reply: String, dialog: &DialogContext) -> ResponseResult<()> {
+    match reply_keyboard(dialog) {
+        Some(keyboard) => bot.send_message(chat_id, reply).reply_markup(keyboard).await?,
+        None => bot.send_message(chat_id, reply).await?,
+    };
+    Ok(())
+}
+
+/// Inline-клавиатура меню настроек
+fn settings_keyboard(settings: &UserSettings) -> InlineKeyboardMarkup {
+    let units_toggle = match settings.units {
+        Units::Metric => "imperial",
+        Units::Imperial => "metric",
+    };
+
+    InlineKeyboardMarkup::new(vec![
+        vec![InlineKeyboardButton::callback(
+            format!("🌍 Язык: {}", settings.reply_language.as_deref().unwrap_or("авто")),
+            "settings:menu:lang",
+        )],
+        vec![InlineKeyboardButton::callback(
+            format!("📏 Единицы: {}", settings.units.label()),
+            format!("settings:units:{}", units_toggle),
+        )],
+        vec![InlineKeyboardButton::callback(
+            format!("📝 Подробность: {}", settings.verbosity.label()),
+            format!("settings:verbosity:{}", settings.verbosity.next().as_str()),
+        )],
+        vec![InlineKeyboardButton::callback(
+            format!("😊 Эмодзи: {}", if settings.emoji { "вкл" } else { "выкл" }),
+            format!("settings:emoji:{}", if settings.emoji { "off" } else { "on" }),
+        )],
+        vec![InlineKeyboardButton::callback(
+            format!("🎩 Обращение: {}", settings.formality.unwrap_or_default().label()),
+            format!("settings:formality:{}", settings.formality.unwrap_or_default().next().as_str()),
+        )],
+        vec![
+            InlineKeyboardButton::callback("🏙️ Город", "settings:help:city"),
+            InlineKeyboardButton::callback("🕒 Часовой пояс", "settings:help:tz"),
+        ],
+    ])
+}
+
+/// Inline-клавиатура выбора языка ответов
+fn language_keyboard() -> InlineKeyboardMarkup {
+    let languages = [("EN", "English"), ("RU", "Русский"), ("DE", "Deutsch"), ("FR", "Français"), ("ES", "Español")];
+    let mut rows: Vec<Vec<InlineKeyboardButton>> = languages
+        .chunks(3)
+        .map(|chunk| {
+            chunk
+                .iter()
+                .map(|(code, name)| InlineKeyboardButton::callback(*name, format!("settings:lang:{}", code)))
+                .collect()
+        })
+        .collect();
+    rows.push(vec![
+        InlineKeyboardButton::callback("🔄 Автоопределение", "settings:lang:auto"),
+        InlineKeyboardButton::callback("⬅️ Назад", "settings:menu:main"),
+    ]);
+    InlineKeyboardMarkup::new(rows)
+}
+
+/// Сохраняет настройки в БД и обновляет их в активном диалоге пользователя в чате
+async fn persist_settings(chat_id: ChatId, user_id: &str, settings: &UserSettings) -> Result<(), String> {
+    save_user_settings("telegram", user_id, settings)
+        .await
+        .map_err(|e| format!("Ошибка сохранения настроек: {}", e))?;
+
+    if let Some(state) = chat_state(chat_id, user_id).await {
+        state.lock().await.settings = settings.clone();
+    }
+    Ok(())
+}
+
+/// Id администраторов из `ADMIN_USER_IDS` (через запятую). Пустой список — администраторов нет
+pub fn admin_user_ids() -> Vec<String> {
+    std::env::var("ADMIN_USER_IDS")
+        .unwrap_or_default()
+        .split(',')
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty())
+        .collect()
+}
+
+/// Проверяет доступ к служебным командам: они доступны только пользователям из `ADMIN_USER_IDS`;
+/// если переменная не задана, служебные команды недоступны никому
+fn is_admin(msg: &Message) -> bool {
+    let user_id = match msg.from.as_ref() {
+        Some(user) => user.id.0.to_string(),
+        None => return false,
+    };
+    admin_user_ids().contains(&user_id)
+}
+
 /// Обрабатывает команды бота
 pub async fn handle_command(bot: Bot, msg: Message, cmd: Command) -> ResponseResult<()> {
     let chat_id = msg.chat.id;
@@ -25,8 +241,8 @@ pub async fn handle_command(bot: Bot, msg: Message, cmd: Command) -> ResponseRes
         }
 
         Command::Start => {
-            let mut states = get_chat_states().lock().await;
-            states.insert(chat_id, DialogContext::new());
+            let dialog = dialog_for(&msg).await;
+            get_chat_states().lock().await.insert((chat_id, sender_id(&msg)), Arc::new(Mutex::new(dialog)));
             
             let welcome_text = "👋 Привет! Я Auraya - умный ассистент.\n\n\
                 Я могу помочь вам с:\n\
@@ -37,6 +253,8 @@ pub async fn handle_command(bot: Bot, msg: Message, cmd: Command) -> ResponseRes
                 Теперь можете просто писать мне сообщения без команд! 💬\n\
                 Или используйте команды:\n\
                 /ask <вопрос> - задать конкретный вопрос\n\
+                /settings - язык, единицы, город и другие настройки\n\
+                /mydata, /forget - выгрузить или удалить свои данные\n\
                 /help - показать справку";
             
             bot.send_message(chat_id, welcome_text).await?;
@@ -49,14 +267,279 @@ pub async fn handle_command(bot: Bot, msg: Message, cmd: Command) -> ResponseRes
                 return Ok(());
             }
 
-            let mut states = get_chat_states().lock().await;
-            let dialog = states.entry(chat_id).or_insert_with(DialogContext::new);
-            
-            let reply = smart_answer_multilang(&question, dialog).await;
+            let state = chat_state_or_new(&msg).await;
+            let mut dialog = state.lock().await;
+
+            let reply = smart_answer_multilang(&question, &mut dialog).await;
+            persist_exchange(&dialog).await;
+            send_reply(&bot, chat_id, reply, &dialog).await?;
+        }
+
+        Command::Stats(args) => {
+            if !is_admin(&msg) {
+                bot.send_message(chat_id, "⛔ Эта команда доступна только администраторам").await?;
+                return Ok(());
+            }
+
+            let reply = match parse_report_range(&args, chrono::Utc::now().date_naive()) {
+                Ok((from, to)) => match usage_report(from, to).await {
+                    Ok(report) => format!("{}\n\n{}", report.format(), translation_memory().stats().format()),
+                    Err(e) => {
+                        log::error!("Ошибка построения отчёта: {}", e);
+                        "❌ Не удалось построить отчёт. Попробуйте позже.".to_string()
+                    }
+                },
+                Err(e) => format!("❓ {}", e),
+            };
             bot.send_message(chat_id, reply).await?;
         }
+
+        Command::Settings(args) => {
+            let user_id = sender_id(&msg);
+            let mut settings = load_user_settings("telegram", &user_id).await;
+            let args = args.trim();
+
+            if args.is_empty() {
+                bot.send_message(chat_id, settings.describe())
+                    .reply_markup(settings_keyboard(&settings))
+                    .await?;
+                return Ok(());
+            }
+
+            let (key, value) = args.split_once(char::is_whitespace).unwrap_or((args, ""));
+            let result = if key.eq_ignore_ascii_case("reset") {
+                settings = UserSettings::default();
+                Ok(())
+            } else {
+                settings.apply(key, value)
+            };
+
+            let reply = match result {
+                Ok(()) => match persist_settings(chat_id, &user_id, &settings).await {
+                    Ok(()) => format!("✅ Сохранено!\n\n{}", settings.describe()),
+                    Err(e) => {
+                        log::error!("{}", e);
+                        "❌ Не удалось сохранить настройки. Попробуйте позже.".to_string()
+                    }
+                },
+                Err(e) => format!("❓ {}", e),
+            };
+            bot.send_message(chat_id, reply).await?;
+        }
+
+        Command::MyData => {
+            let user_id = sender_id(&msg);
+            let active_dialog = match chat_state(chat_id, &user_id).await {
+                Some(state) => state.lock().await.history.clone(),
+                None => Vec::new(),
+            };
+            let export = export_user_data("telegram", &user_id, &active_dialog).await;
+
+            match export.and_then(|doc| serde_json::to_vec_pretty(&doc).map_err(|e| e.to_string())) {
+                Ok(bytes) => {
+                    bot.send_document(chat_id, InputFile::memory(bytes).file_name("mydata.json"))
+                        .caption("📦 Все данные, которые бот хранит о вас")
+                        .await?;
+                }
+                Err(e) => {
+                    log::error!("Ошибка экспорта данных: {}", e);
+                    bot.send_message(chat_id, "❌ Не удалось выгрузить данные. Попробуйте позже.").await?;
+                }
+            }
+        }
+
+        Command::Forget => {
+            let keyboard = InlineKeyboardMarkup::new(vec![vec![
+                InlineKeyboardButton::callback("🗑 Да, удалить всё", "forget:confirm"),
+                InlineKeyboardButton::callback("Отмена", "forget:cancel"),
+            ]]);
+            bot.send_message(
+                chat_id,
+                "⚠️ Будут удалены ваши настройки, история диалога, память, кэшированные ответы \
+                на ваши вопросы и статистика. Это действие необратимо. Продолжить?",
+            )
+            .reply_markup(keyboard)
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Обрабатывает нажатия inline-кнопок (меню настроек, подтверждение удаления данных,
+/// выбор варианта со страницы значений, цитирование источников)
+pub async fn handle_callback_query(bot: Bot, q: CallbackQuery) -> ResponseResult<()> {
+    let data = q.data.clone().unwrap_or_default();
+    let message = match q.regular_message() {
+        Some(message) => message.clone(),
+        None => {
+            bot.answer_callback_query(q.id.clone()).await?;
+            return Ok(());
+        }
+    };
+
+    if let Some(action) = data.strip_prefix("settings:") {
+        handle_settings_callback(bot, q, message, action).await
+    } else if let Some(action) = data.strip_prefix("forget:") {
+        handle_forget_callback(bot, q, message, action).await
+    } else if let Some(index) = data.strip_prefix("wiki:") {
+        handle_disambiguation_callback(bot, q, message, index).await
+    } else if let Some(answer) = data.strip_prefix("cite:") {
+        handle_cite_callback(bot, q, message, answer).await
+    } else {
+        bot.answer_callback_query(q.id.clone()).await?;
+        Ok(())
+    }
+}
+
+/// Выбор варианта кнопкой под списком значений
+async fn handle_disambiguation_callback(bot: Bot, q: CallbackQuery, message: Message, index: &str) -> ResponseResult<()> {
+    let chat_id = message.chat.id;
+    // Список значений — в диалоге того, кто нажал кнопку
+    let Some(state) = chat_state(chat_id, &q.from.id.0.to_string()).await else {
+        bot.answer_callback_query(q.id.clone()).text("Этот список уже неактуален").await?;
+        return Ok(());
+    };
+    let mut dialog = state.lock().await;
+    let reply = match index.parse::<usize>() {
+        Ok(index) => choose_disambiguation_option(&mut dialog, index).await,
+        Err(_) => None,
+    };
+    let Some(reply) = reply else {
+        bot.answer_callback_query(q.id.clone()).text("Этот список уже неактуален").await?;
+        return Ok(());
+    };
+
+    bot.answer_callback_query(q.id.clone()).await?;
+    persist_exchange(&dialog).await;
+    // Убираем кнопки у списка, чтобы не выбирать повторно
+    bot.edit_message_reply_markup(chat_id, message.id).await?;
+    send_reply(&bot, chat_id, reply, &dialog).await
+}
+
+/// Ссылки на источники ответа, под которым нажата кнопка, в форматах BibTeX, APA и Markdown
+async fn handle_cite_callback(bot: Bot, q: CallbackQuery, message: Message, answer: &str) -> ResponseResult<()> {
+    let reply = match (answer.parse::<usize>(), chat_state(message.chat.id, &q.from.id.0.to_string()).await) {
+        (Ok(answer), Some(state)) => citations_for_answer(&*state.lock().await, answer),
+        _ => None,
+    };
+    match reply {
+        Some(reply) => {
+            bot.answer_callback_query(q.id.clone()).await?;
+            bot.send_message(message.chat.id, reply).await?;
+        }
+        None => {
+            bot.answer_callback_query(q.id.clone()).text("Источники этого ответа уже недоступны").await?;
+        }
     }
+    Ok(())
+}
+
+/// Подтверждение или отмена `/forget`
+async fn handle_forget_callback(bot: Bot, q: CallbackQuery, message: Message, action: &str) -> ResponseResult<()> {
+    bot.answer_callback_query(q.id.clone()).await?;
+
+    if action != "confirm" {
+        bot.edit_message_text(message.chat.id, message.id, "👌 Удаление отменено.").await?;
+        return Ok(());
+    }
+
+    let user_id = q.from.id.0.to_string();
+    let text = match forget_user("telegram", &user_id).await {
+        Ok(report) => {
+            // Удаляем и активные диалоги пользователя в памяти процесса (во всех чатах)
+            get_chat_states().lock().await.retain(|(_, user), _| *user != user_id);
+            log::info!("Данные пользователя удалены: {}", report.summary());
+            "✅ Все ваши данные удалены.".to_string()
+        }
+        Err(e) => {
+            log::error!("Ошибка удаления данных: {}", e);
+            "❌ Не удалось удалить данные. Попробуйте позже.".to_string()
+        }
+    };
+
+    bot.edit_message_text(message.chat.id, message.id, text).await?;
+    Ok(())
+}
+
+/// Изменение настроек через кнопки меню `/settings`
+async fn handle_settings_callback(bot: Bot, q: CallbackQuery, message: Message, action: &str) -> ResponseResult<()> {
+    let (key, value) = action.split_once(':').unwrap_or((action, ""));
+    let user_id = q.from.id.0.to_string();
+    let mut settings = load_user_settings("telegram", &user_id).await;
+
+    match (key, value) {
+        ("menu", "lang") => {
+            bot.answer_callback_query(q.id.clone()).await?;
+            bot.edit_message_text(message.chat.id, message.id, "🌍 Выберите язык ответов:")
+                .reply_markup(language_keyboard())
+                .await?;
+            return Ok(());
+        }
+        ("menu", _) => {}
+        ("help", "city") => {
+            bot.answer_callback_query(q.id.clone())
+                .text("Отправьте: /settings city <город>")
+                .show_alert(true)
+                .await?;
+            return Ok(());
+        }
+        ("help", _) => {
+            bot.answer_callback_query(q.id.clone())
+                .text("Отправьте: /settings tz <Europe/Moscow>")
+                .show_alert(true)
+                .await?;
+            return Ok(());
+        }
+        _ => {
+            let notice = match settings.apply(key, value) {
+                Ok(()) => match persist_settings(message.chat.id, &user_id, &settings).await {
+                    Ok(()) => "✅ Сохранено".to_string(),
+                    Err(e) => {
+                        log::error!("{}", e);
+                        "❌ Не удалось сохранить".to_string()
+                    }
+                },
+                Err(e) => e,
+            };
+            bot.answer_callback_query(q.id.clone()).text(notice).await?;
+        }
+    }
+
+    bot.edit_message_text(message.chat.id, message.id, settings.describe())
+        .reply_markup(settings_keyboard(&settings))
+        .await?;
+
+    Ok(())
+}
+
+/// Нераспознанная команда: подсказывает ближайшую по написанию (в том числе набранную
+/// в другой раскладке, например `/ыуеештпы`)
+pub async fn handle_unknown_command(bot: Bot, msg: Message) -> ResponseResult<()> {
+    let text = msg.text().unwrap_or("");
+    let name = text
+        .trim_start_matches('/')
+        .split(|c: char| c.is_whitespace() || c == '@')
+        .next()
+        .unwrap_or("")
+        .to_lowercase();
+
+    let commands: Vec<String> = Command::bot_commands()
+        .into_iter()
+        .map(|c| c.command.trim_start_matches('/').to_string())
+        .collect();
+    let names: Vec<&str> = commands.iter().map(String::as_str).collect();
+
+    let reply = if names.contains(&name.as_str()) {
+        format!("❓ Не удалось разобрать аргументы команды /{}. Справка: /help", name)
+    } else {
+        match default_matcher().suggest(&name, &names) {
+            Some(suggestion) => format!("❓ Неизвестная команда /{}. {}", name, did_you_mean(&format!("/{}", suggestion))),
+            None => format!("❓ Неизвестная команда /{}. Список команд: /help", name),
+        }
+    };
 
+    bot.send_message(msg.chat.id, reply).await?;
     Ok(())
 }
 
@@ -70,13 +553,14 @@ pub async fn handle_message(bot: Bot, msg: Message) -> ResponseResult<()> {
         bot.send_chat_action(chat_id, teloxide::types::ChatAction::Typing).await?;
         
         // Получаем или создаем контекст диалога
-        let mut states = get_chat_states().lock().await;
-        let dialog = states.entry(chat_id).or_insert_with(DialogContext::new);
+        let state = chat_state_or_new(&msg).await;
+        let mut dialog = state.lock().await;
         
         // Генерируем ответ как ИИ-ассистент
-        let reply = smart_answer_multilang(text, dialog).await;
+        let reply = smart_answer_multilang(text, &mut dialog).await;
+        persist_exchange(&dialog).await;
         
-        bot.send_message(chat_id, reply).await?;
+        send_reply(&bot, chat_id, reply, &dialog).await?;
     }
 
     Ok(())
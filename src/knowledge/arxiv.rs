@@ -1,6 +1,14 @@
-use reqwest;
+use chrono::{DateTime, Utc};
 use quick_xml::de::from_str;
+use regex::Regex;
+use reqwest;
 use serde::Deserialize;
+use std::sync::OnceLock;
+
+use crate::language::{
+    normalize::{is_filler_word, SEARCH_PAGE_SIZE},
+    segment::truncate,
+};
 
 #[derive(Debug, Deserialize)]
 struct Feed {
@@ -14,20 +22,287 @@ struct Entry {
     id: String,
     #[serde(rename = "summary")]
     abstract_text: Option<String>,
+    published: Option<String>,
+    updated: Option<String>,
+    #[serde(rename = "author", default)]
+    authors: Vec<Author>,
+    #[serde(rename = "category", default)]
+    categories: Vec<Category>,
+    #[serde(rename = "primary_category", alias = "arxiv:primary_category", default)]
+    primary_category: Option<Category>,
+    #[serde(rename = "link", default)]
+    links: Vec<Link>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Author {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Category {
+    #[serde(rename = "@term")]
+    term: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Link {
+    #[serde(rename = "@href")]
+    href: String,
+    #[serde(rename = "@title", default)]
+    title: Option<String>,
+}
+
+/// Статья arXiv с метаданными
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArxivPaper {
+    /// Идентификатор с версией: `2301.01234v2`
+    pub id: String,
+    pub title: String,
+    pub authors: Vec<String>,
+    pub summary: String,
+    /// Основная категория идёт первой
+    pub categories: Vec<String>,
+    pub published: Option<DateTime<Utc>>,
+    pub updated: Option<DateTime<Utc>>,
+    pub abs_url: String,
+    pub pdf_url: Option<String>,
+}
+
+impl ArxivPaper {
+    /// Идентификатор без версии: `2301.01234`
+    pub fn base_id(&self) -> &str {
+        match self.id.rsplit_once('v') {
+            Some((base, version)) if !version.is_empty() && version.chars().all(|c| c.is_ascii_digit()) => base,
+            _ => &self.id,
+        }
+    }
+
+    /// Авторы для списка: не больше трёх, остальные — «et al.»
+    fn authors_line(&self) -> String {
+        match self.authors.len() {
+            0 => "—".to_string(),
+            1..=3 => self.authors.join(", "),
+            _ => format!("{} et al.", self.authors[..3].join(", ")),
+        }
+    }
+}
+
+impl From<Entry> for ArxivPaper {
+    fn from(entry: Entry) -> Self {
+        let id = entry.id.trim().rsplit("/abs/").next().unwrap_or_default().to_string();
+        let mut categories: Vec<String> = entry.primary_category.into_iter().map(|c| c.term).collect();
+        for category in entry.categories {
+            if !categories.contains(&category.term) {
+                categories.push(category.term);
+            }
+        }
+        let pdf_url = entry
+            .links
+            .iter()
+            .find(|link| link.title.as_deref() == Some("pdf"))
+            .map(|link| link.href.clone());
+
+        Self {
+            abs_url: entry.id.trim().to_string(),
+            id,
+            title: collapse_whitespace(&entry.title),
+            authors: entry.authors.into_iter().map(|a| collapse_whitespace(&a.name)).collect(),
+            summary: collapse_whitespace(entry.abstract_text.as_deref().unwrap_or_default()),
+            categories,
+            published: entry.published.as_deref().and_then(parse_date),
+            updated: entry.updated.as_deref().and_then(parse_date),
+            pdf_url,
+        }
+    }
+}
+
+/// Заголовки и аннотации arXiv приходят с переносами строк и отступами
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn parse_date(date: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(date.trim()).ok().map(|d| d.with_timezone(&Utc))
+}
+
+/// Порядок выдачи
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ArxivSort {
+    #[default]
+    Relevance,
+    /// Новые публикации первыми
+    Submitted,
+    /// Недавно обновлённые первыми
+    Updated,
+}
+
+impl ArxivSort {
+    fn as_param(&self) -> &'static str {
+        match self {
+            ArxivSort::Relevance => "relevance",
+            ArxivSort::Submitted => "submittedDate",
+            ArxivSort::Updated => "lastUpdatedDate",
+        }
+    }
+}
+
+/// Длина аннотации в списке
+const ABSTRACT_GRAPHEMES: usize = 240;
+
+/// Слова запроса к arXiv, которые не относятся к теме (кроме общих служебных слов)
+const FILLER_WORDS: &[&str] = &[
+    "arxiv", "paper", "papers", "preprint", "preprints", "article", "articles", "research", "publications",
+    "about", "latest", "recent", "newest", "new", "recently", "updated", "from", "category",
+];
+
+/// Поисковый запрос к arXiv: тема, категория, сортировка и страница
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ArxivQuery {
+    pub terms: String,
+    pub category: Option<String>,
+    pub sort: ArxivSort,
+    pub start: usize,
+    pub max_results: usize,
+}
+
+impl ArxivQuery {
+    /// Разбирает запрос на английском: «papers about diffusion models», «latest in cs.CL»,
+    /// «recently updated papers on transformers in stat.ML»
+    pub fn parse(input: &str) -> Self {
+        let category = category_regex().find(input).map(|m| normalize_category(m.as_str()));
+        let lower = input.to_lowercase();
+        let sort = if lower.contains("updated") {
+            ArxivSort::Updated
+        } else if ["latest", "recent", "newest", "new "].iter().any(|w| lower.contains(w)) {
+            ArxivSort::Submitted
+        } else {
+            ArxivSort::Relevance
+        };
+
+        let without_category = category_regex().replace_all(input, " ");
+        let terms = without_category
+            .split(|c: char| !c.is_alphanumeric() && c != '-' && c != '\'')
+            .filter(|word| !word.is_empty() && !is_filler_word(&word.to_lowercase(), FILLER_WORDS))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        Self { terms, category, sort, start: 0, max_results: SEARCH_PAGE_SIZE }
+    }
+
+    /// Есть ли в запросе что искать
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty() && self.category.is_none()
+    }
+
+    /// Запрос следующей страницы
+    pub fn next_page(&self) -> Self {
+        Self { start: self.start + self.max_results, ..self.clone() }
+    }
+
+    /// Параметр `search_query` API: `all:` по каждому слову и `cat:` для категории
+    fn search_query(&self) -> String {
+        let mut parts: Vec<String> = self
+            .terms
+            .split_whitespace()
+            .map(|word| format!("all:{}", urlencoding::encode(word)))
+            .collect();
+        if let Some(category) = &self.category {
+            parts.push(format!("cat:{}", category));
+        }
+        parts.join("+AND+")
+    }
+
+    fn url(&self) -> String {
+        format!(
+            "https://export.arxiv.org/api/query?search_query={}&start={}&max_results={}&sortBy={}&sortOrder=descending",
+            self.search_query(),
+            self.start,
+            self.max_results,
+            self.sort.as_param()
+        )
+    }
 }
 
-/// Ищет статьи на arXiv по запросу и возвращает заголовки с ссылками
-pub async fn search_arxiv(query: &str, max_results: usize) -> Result<Vec<(String, String)>, Box<dyn std::error::Error>> {
-    let url = format!(
-        "https://export.arxiv.org/api/query?search_query=all:{}&start=0&max_results={}",
-        query.replace(" ", "+"),
-        max_results
-    );
+/// Слова рядом с «paper(s)», по которым понятно, что речь о научных статьях
+/// («research papers», «papers about diffusion models»), а не о бумаге
+const RESEARCH_WORDS: &[&str] = &[
+    "research", "scientific", "academic", "scholarly", "recent", "latest", "newest", "new", "published",
+    "about", "on", "survey", "cited",
+];
+
+/// Похоже ли сообщение на поиск статей: упоминает arXiv, препринты, научные статьи
+/// или категорию arXiv
+pub fn is_arxiv_query(input: &str) -> bool {
+    let lower = input.to_lowercase();
+    let words: Vec<&str> = lower.split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty()).collect();
+    let research_paper = words.iter().enumerate().any(|(i, word)| {
+        matches!(*word, "paper" | "papers")
+            && [i.checked_sub(1), Some(i + 1)]
+                .into_iter()
+                .flatten()
+                .any(|j| words.get(j).is_some_and(|w| RESEARCH_WORDS.contains(w)))
+    });
+    ["arxiv", "preprint", "preprints"].iter().any(|w| words.contains(w))
+        || research_paper
+        || category_regex().is_match(input)
+}
+
+/// Категории arXiv: `cs.CL`, `math.AG`, `astro-ph.CO`, `hep-th`, `quant-ph`.
+/// Сравниваются с учётом регистра и только с настоящими разделами, чтобы `Math.floor`
+/// и `math.sqrt` из вопросов о коде не считались категориями.
+fn category_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        let archives = [
+            ("cs", "AI|AR|CC|CE|CG|CL|CR|CV|CY|DB|DC|DL|DM|DS|ET|FL|GL|GR|GT|HC|IR|IT|LG|LO|MA|MM|MS|NA|NE|NI|OH|OS|PF|PL|RO|SC|SD|SE|SI|SY"),
+            ("math", "AC|AG|AP|AT|CA|CO|CT|CV|DG|DS|FA|GM|GN|GR|GT|HO|IT|KT|LO|MG|MP|NA|NT|OA|OC|PR|QA|RA|RT|SG|SP|ST"),
+            ("stat", "AP|CO|ME|ML|OT|TH"),
+            ("eess", "AS|IV|SP|SY"),
+            ("econ", "EM|GN|TH"),
+            ("q-bio", "BM|CB|GN|MN|NC|OT|PE|QM|SC|TO"),
+            ("q-fin", "CP|EC|GN|MF|PM|PR|RM|ST|TR"),
+            ("astro-ph", "CO|EP|GA|HE|IM|SR"),
+            ("nlin", "AO|CD|CG|PS|SI"),
+            ("cond-mat", "dis-nn|mes-hall|mtrl-sci|other|quant-gas|soft|stat-mech|str-el|supr-con"),
+            (
+                "physics",
+                "acc-ph|ao-ph|app-ph|atm-clus|atom-ph|bio-ph|chem-ph|class-ph|comp-ph|data-an|ed-ph|flu-dyn|gen-ph|geo-ph|hist-ph|ins-det|med-ph|optics|plasm-ph|pop-ph|soc-ph|space-ph",
+            ),
+        ];
+        let subjects = archives
+            .iter()
+            .map(|(archive, subjects)| format!(r"{}\.(?:{})", regex::escape(archive), subjects))
+            .collect::<Vec<_>>()
+            .join("|");
+        Regex::new(&format!(
+            r"\b(?:{}|hep-th|hep-ph|hep-ex|hep-lat|gr-qc|quant-ph|nucl-th|nucl-ex|math-ph)\b",
+            subjects
+        ))
+        .unwrap()
+    })
+}
+
+/// Приводит категорию к написанию arXiv: архив строчными, двухбуквенный раздел — прописными
+/// (`cs.cl` → `cs.CL`, `astro-ph.co` → `astro-ph.CO`), длинные разделы строчными (`physics.optics`)
+fn normalize_category(category: &str) -> String {
+    let category = category.to_lowercase();
+    match category.split_once('.') {
+        Some((archive, subject)) if subject.len() == 2 => format!("{}.{}", archive, subject.to_uppercase()),
+        _ => category,
+    }
+}
+
+/// Ищет статьи на arXiv
+pub async fn search_arxiv(query: &ArxivQuery) -> Result<Vec<ArxivPaper>, Box<dyn std::error::Error>> {
+    let url = query.url();
+    println!("🔍 arXiv URL: {}", url);
 
     let client = reqwest::Client::new();
     let response = client
         .get(&url)
         .header("User-Agent", "Bot-Auraya/1.0")
+        .timeout(std::time::Duration::from_secs(15))
         .send()
         .await?;
 
@@ -36,15 +311,119 @@ pub async fn search_arxiv(query: &str, max_results: usize) -> Result<Vec<(String
     }
 
     let body = response.text().await?;
-    let feed: Feed = from_str(&body)?;
+    parse_feed(&body)
+}
+
+fn parse_feed(body: &str) -> Result<Vec<ArxivPaper>, Box<dyn std::error::Error>> {
+    let feed: Feed = from_str(body)?;
+    Ok(feed.entries.into_iter().map(ArxivPaper::from).collect())
+}
+
+/// Список статей: заголовок, авторы, дата, категории, короткая аннотация и ссылки.
+/// Нумерация продолжается со страницы `start`.
+pub fn format_papers(papers: &[ArxivPaper], start: usize) -> String {
+    papers
+        .iter()
+        .enumerate()
+        .map(|(i, paper)| {
+            let mut meta = vec![format!("👥 {}", paper.authors_line())];
+            if let Some(published) = paper.published {
+                meta.push(format!("📅 {}", published.format("%Y-%m-%d")));
+            }
+            if !paper.categories.is_empty() {
+                meta.push(format!("🏷️ {}", paper.categories.join(", ")));
+            }
+            let mut links = format!("🔗 {}", paper.abs_url);
+            if let Some(pdf) = &paper.pdf_url {
+                links.push_str(&format!(" · PDF: {}", pdf));
+            }
+            format!(
+                "📄 **{}. {}**\n{}\n{}\n{}",
+                start + i + 1,
+                paper.title,
+                meta.join(" · "),
+                truncate(&paper.summary, ABSTRACT_GRAPHEMES),
+                links
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
 
-    if feed.entries.is_empty() {
-        return Err("No arXiv articles found".into());
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FEED: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom" xmlns:arxiv="http://arxiv.org/schemas/atom">
+  <title type="html">ArXiv Query</title>
+  <entry>
+    <id>http://arxiv.org/abs/2006.11239v2</id>
+    <updated>2020-12-16T20:35:09Z</updated>
+    <published>2020-06-19T17:24:44Z</published>
+    <title>Denoising Diffusion Probabilistic
+      Models</title>
+    <summary>  We present high quality image synthesis results
+  using diffusion probabilistic models.</summary>
+    <author><name>Jonathan Ho</name></author>
+    <author><name>Ajay Jain</name></author>
+    <author><name>Pieter Abbeel</name></author>
+    <author><name>Someone Else</name></author>
+    <link href="http://arxiv.org/abs/2006.11239v2" rel="alternate" type="text/html"/>
+    <link title="pdf" href="http://arxiv.org/pdf/2006.11239v2" rel="related" type="application/pdf"/>
+    <arxiv:primary_category term="cs.LG" scheme="http://arxiv.org/schemas/atom"/>
+    <category term="cs.LG" scheme="http://arxiv.org/schemas/atom"/>
+    <category term="stat.ML" scheme="http://arxiv.org/schemas/atom"/>
+  </entry>
+</feed>"#;
+
+    #[test]
+    fn test_parse_feed() {
+        let papers = parse_feed(FEED).unwrap();
+        let paper = &papers[0];
+        assert_eq!(paper.id, "2006.11239v2");
+        assert_eq!(paper.base_id(), "2006.11239");
+        assert_eq!(paper.title, "Denoising Diffusion Probabilistic Models");
+        assert_eq!(paper.authors.len(), 4);
+        assert_eq!(paper.categories, vec!["cs.LG", "stat.ML"]);
+        assert_eq!(paper.pdf_url.as_deref(), Some("http://arxiv.org/pdf/2006.11239v2"));
+        assert_eq!(paper.published.unwrap().format("%Y-%m-%d").to_string(), "2020-06-19");
+
+        let list = format_papers(&papers, 5);
+        assert!(list.starts_with("📄 **6. Denoising Diffusion Probabilistic Models**"));
+        assert!(list.contains("Jonathan Ho, Ajay Jain, Pieter Abbeel et al."));
+    }
+
+    #[test]
+    fn test_is_arxiv_query() {
+        assert!(is_arxiv_query("Papers about diffusion models"));
+        assert!(is_arxiv_query("latest in cs.CL"));
+        assert!(!is_arxiv_query("weather in London"));
+        assert!(is_arxiv_query("recent research papers on protein folding"));
+        assert!(is_arxiv_query("quant-ph preprints"));
+        // Методы Math из вопросов о коде и бумага — не статьи
+        assert!(!is_arxiv_query("how do I use Math.floor in JavaScript"));
+        assert!(!is_arxiv_query("python math.sqrt example"));
+        assert!(!is_arxiv_query("how to fold a paper airplane"));
+        assert!(!is_arxiv_query("what is cs.cl"));
     }
 
-    Ok(feed
-        .entries
-        .into_iter()
-        .map(|entry| (entry.title.trim().to_string(), entry.id))
-        .collect())
+    #[test]
+    fn test_parse_query() {
+        let query = ArxivQuery::parse("papers about diffusion models");
+        assert_eq!(query.terms, "diffusion models");
+        assert_eq!(query.category, None);
+        assert_eq!(query.sort, ArxivSort::Relevance);
+
+        let query = ArxivQuery::parse("latest in cs.CL");
+        assert_eq!(query.terms, "");
+        assert_eq!(query.category.as_deref(), Some("cs.CL"));
+        assert_eq!(query.sort, ArxivSort::Submitted);
+        assert!(query.url().contains("search_query=cat:cs.CL&start=0&max_results=5&sortBy=submittedDate"));
+
+        let query = ArxivQuery::parse("recently updated arxiv papers on transformers in stat.ML").next_page();
+        assert_eq!(query.search_query(), "all:transformers+AND+cat:stat.ML");
+        assert_eq!(query.sort, ArxivSort::Updated);
+        assert_eq!(query.start, 5);
+    }
 }
@@ -0,0 +1,325 @@
+use chrono::{Datelike, NaiveDate, Utc};
+
+use super::{
+    arxiv::ArxivPaper,
+    github::code_search::CodeSnippet,
+    wikipedia::{article_url, WikiArticle},
+};
+use crate::language::{code::Language, normalize::normalize_reply};
+
+/// Просьбы оформить ссылки на источники последнего ответа
+const CITE_REQUESTS: &[&str] = &[
+    "cite", "cite that", "cite this", "cite it", "citation", "citations", "bibtex", "apa",
+    "процитируй", "цитата", "цитировать", "оформи ссылку", "оформи ссылки", "ссылка на источник", "источники",
+];
+
+/// Просит ли пользователь оформить ссылки на источники («cite that», «процитируй»)
+pub fn is_cite_request(input: &str) -> bool {
+    CITE_REQUESTS.contains(&normalize_reply(input).as_str())
+}
+
+/// Откуда взят источник
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceKind {
+    Arxiv,
+    Wikipedia,
+    Github,
+}
+
+/// Метаданные источника для библиографической ссылки
+#[derive(Debug, Clone, PartialEq)]
+pub struct Citation {
+    pub kind: SourceKind,
+    pub title: String,
+    /// Полные имена: «Jonathan Ho»
+    pub authors: Vec<String>,
+    pub year: Option<i32>,
+    pub url: String,
+    /// Идентификатор arXiv без версии
+    pub arxiv_id: Option<String>,
+    pub accessed: NaiveDate,
+}
+
+impl Citation {
+    pub fn from_paper(paper: &ArxivPaper) -> Self {
+        Self {
+            kind: SourceKind::Arxiv,
+            title: paper.title.clone(),
+            authors: paper.authors.clone(),
+            year: paper.published.map(|date| date.year()),
+            url: format!("https://arxiv.org/abs/{}", paper.base_id()),
+            arxiv_id: Some(paper.base_id().to_string()),
+            accessed: Utc::now().date_naive(),
+        }
+    }
+
+    pub fn from_article(article: &WikiArticle) -> Self {
+        Self::wikipedia(&article.title, article.language)
+    }
+
+    /// Статья `title` из раздела Википедии `lang`
+    pub fn wikipedia(title: &str, lang: Language) -> Self {
+        Self {
+            kind: SourceKind::Wikipedia,
+            title: title.to_string(),
+            authors: Vec::new(),
+            year: None,
+            url: article_url(lang, title),
+            arxiv_id: None,
+            accessed: Utc::now().date_naive(),
+        }
+    }
+
+    /// Фрагмент кода из поиска GitHub: файл `owner/repo: path` по постоянной ссылке
+    pub fn from_snippet(snippet: &CodeSnippet) -> Self {
+        Self::from_repo(&format!("{}: {}", snippet.repository, snippet.path), &snippet.permalink)
+    }
+
+    /// Репозиторий GitHub: автор — владелец из адреса `https://github.com/<owner>/<repo>`
+    pub fn from_repo(name: &str, url: &str) -> Self {
+        let owner = url
+            .trim_start_matches("https://github.com/")
+            .split('/')
+            .next()
+            .filter(|owner| !owner.is_empty() && !owner.contains(':'));
+        Self {
+            kind: SourceKind::Github,
+            title: name.to_string(),
+            authors: owner.map(|owner| vec![owner.to_string()]).unwrap_or_default(),
+            year: None,
+            url: url.to_string(),
+            arxiv_id: None,
+            accessed: Utc::now().date_naive(),
+        }
+    }
+
+    /// Ключ BibTeX: `ho2020denoising`, `wikipedia_rust_programming_language`, `github_rust-lang_rust`
+    fn bibtex_key(&self) -> String {
+        let slug = |text: &str| {
+            text.to_lowercase()
+                .split(|c: char| !c.is_alphanumeric() && c != '-')
+                .filter(|w| !w.is_empty())
+                .collect::<Vec<_>>()
+                .join("_")
+        };
+        match self.kind {
+            SourceKind::Arxiv => {
+                let surname = self.authors.first().map(|a| surname(a).to_lowercase()).unwrap_or_else(|| "arxiv".to_string());
+                let word = slug(&self.title).split('_').find(|w| w.len() > 3).unwrap_or("paper").to_string();
+                format!("{}{}{}", slug(&surname), self.year.map(|y| y.to_string()).unwrap_or_default(), word)
+            }
+            SourceKind::Wikipedia => format!("wikipedia_{}", slug(&self.title)),
+            SourceKind::Github => format!("github_{}_{}", slug(&self.authors.join("_")), slug(&self.title)),
+        }
+    }
+
+    /// Запись BibTeX
+    pub fn bibtex(&self) -> String {
+        let mut fields = vec![("title", format!("{{{}}}", escape_bibtex(&self.title)))];
+        match self.kind {
+            SourceKind::Wikipedia => fields.push(("author", "{{Wikipedia contributors}}".to_string())),
+            _ if !self.authors.is_empty() => {
+                let authors = self.authors.iter().map(|a| inverted_name(a)).collect::<Vec<_>>().join(" and ");
+                fields.push(("author", format!("{{{}}}", escape_bibtex(&authors))));
+            }
+            _ => {}
+        }
+        if let Some(year) = self.year {
+            fields.push(("year", format!("{{{}}}", year)));
+        }
+        if let Some(id) = &self.arxiv_id {
+            fields.push(("eprint", format!("{{{}}}", id)));
+            fields.push(("archivePrefix", "{arXiv}".to_string()));
+        }
+        match self.kind {
+            SourceKind::Wikipedia => fields.push(("howpublished", "{Wikipedia}".to_string())),
+            SourceKind::Github => fields.push(("howpublished", "{GitHub}".to_string())),
+            SourceKind::Arxiv => {}
+        }
+        fields.push(("url", format!("{{{}}}", self.url)));
+        fields.push(("note", format!("{{Accessed: {}}}", self.accessed.format("%Y-%m-%d"))));
+
+        let body = fields
+            .iter()
+            .map(|(name, value)| format!("  {} = {}", name, value))
+            .collect::<Vec<_>>()
+            .join(",\n");
+        format!("@misc{{{},\n{}\n}}", self.bibtex_key(), body)
+    }
+
+    /// Ссылка в стиле APA (7-е издание)
+    pub fn apa(&self) -> String {
+        let date = self.year.map(|y| y.to_string()).unwrap_or_else(|| "n.d.".to_string());
+        let retrieved = format!("Retrieved {}, from {}", self.accessed.format("%B %-d, %Y"), self.url);
+        match self.kind {
+            SourceKind::Arxiv => format!(
+                "{} ({}). {} (arXiv:{}). arXiv. {}",
+                apa_authors(&self.authors),
+                date,
+                self.title,
+                self.arxiv_id.as_deref().unwrap_or_default(),
+                self.url
+            ),
+            SourceKind::Wikipedia => format!("{}. ({}). In Wikipedia. {}", self.title, date, retrieved),
+            SourceKind::Github => match self.authors.first() {
+                Some(owner) => format!("{}. ({}). {} [Computer software]. GitHub. {}", owner, date, self.title, retrieved),
+                None => format!("{} [Computer software]. ({}). GitHub. {}", self.title, date, retrieved),
+            },
+        }
+    }
+
+    /// Ссылка в Markdown: `[Название](url) — авторы, год (arXiv:id)`
+    pub fn markdown(&self) -> String {
+        let mut details = Vec::new();
+        match self.authors.len() {
+            0 => {}
+            1 | 2 => details.push(self.authors.iter().map(|a| surname(a)).collect::<Vec<_>>().join(" & ")),
+            _ => details.push(format!("{} et al.", surname(&self.authors[0]))),
+        }
+        if let Some(year) = self.year {
+            details.push(year.to_string());
+        }
+        let mut line = format!("[{}]({})", self.title.replace(['[', ']'], ""), self.url);
+        if !details.is_empty() {
+            line.push_str(&format!(" — {}", details.join(", ")));
+        }
+        if let Some(id) = &self.arxiv_id {
+            line.push_str(&format!(" (arXiv:{})", id));
+        }
+        match self.kind {
+            SourceKind::Wikipedia => line.push_str(" — Wikipedia"),
+            SourceKind::Github => line.push_str(" — GitHub"),
+            SourceKind::Arxiv => {}
+        }
+        line
+    }
+}
+
+/// Все источники в трёх форматах: BibTeX, APA и Markdown
+pub fn format_citations(citations: &[Citation]) -> String {
+    let bibtex = citations.iter().map(Citation::bibtex).collect::<Vec<_>>().join("\n\n");
+    let apa = citations.iter().map(Citation::apa).collect::<Vec<_>>().join("\n\n");
+    let markdown = citations.iter().map(|c| format!("- {}", c.markdown())).collect::<Vec<_>>().join("\n");
+    format!("📑 BibTeX\n\n{}\n\n📑 APA\n\n{}\n\n📑 Markdown\n\n{}", bibtex, apa, markdown)
+}
+
+/// Фамилия — последнее слово имени
+fn surname(name: &str) -> &str {
+    name.split_whitespace().last().unwrap_or(name)
+}
+
+/// «Jonathan Ho» → «Ho, Jonathan» (так BibTeX однозначно определяет фамилию)
+fn inverted_name(name: &str) -> String {
+    match name.trim().rsplit_once(' ') {
+        Some((given, family)) => format!("{}, {}", family, given),
+        None => name.trim().to_string(),
+    }
+}
+
+/// «John Paul Smith» → «Smith, J. P.»
+fn apa_name(name: &str) -> String {
+    match name.trim().rsplit_once(' ') {
+        Some((given, family)) => {
+            let initials = given
+                .split_whitespace()
+                .filter_map(|part| part.chars().next())
+                .map(|c| format!("{}.", c))
+                .collect::<Vec<_>>()
+                .join(" ");
+            format!("{}, {}", family, initials)
+        }
+        None => name.trim().to_string(),
+    }
+}
+
+/// Авторы в APA: до 20 через запятую с «&» перед последним; больше 20 — первые 19, «...» и последний
+fn apa_authors(authors: &[String]) -> String {
+    let names: Vec<String> = authors.iter().map(|a| apa_name(a)).collect();
+    match names.len() {
+        0 => "Anonymous".to_string(),
+        1 => names[0].clone(),
+        2..=20 => format!("{}, & {}", names[..names.len() - 1].join(", "), names[names.len() - 1]),
+        _ => format!("{}, ... {}", names[..19].join(", "), names[names.len() - 1]),
+    }
+}
+
+/// Экранирует символы, которые BibTeX/LaTeX воспринимает как команды
+fn escape_bibtex(text: &str) -> String {
+    text.replace('&', "\\&").replace('%', "\\%").replace('_', "\\_").replace('#', "\\#")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paper_citation() -> Citation {
+        Citation {
+            kind: SourceKind::Arxiv,
+            title: "Denoising Diffusion Probabilistic Models".to_string(),
+            authors: vec!["Jonathan Ho".to_string(), "Ajay Jain".to_string(), "Pieter Abbeel".to_string()],
+            year: Some(2020),
+            url: "https://arxiv.org/abs/2006.11239".to_string(),
+            arxiv_id: Some("2006.11239".to_string()),
+            accessed: NaiveDate::from_ymd_opt(2026, 10, 19).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_arxiv_formats() {
+        let citation = paper_citation();
+        assert_eq!(
+            citation.bibtex(),
+            "@misc{ho2020denoising,\n  title = {Denoising Diffusion Probabilistic Models},\n  author = {Ho, Jonathan and Jain, Ajay and Abbeel, Pieter},\n  year = {2020},\n  eprint = {2006.11239},\n  archivePrefix = {arXiv},\n  url = {https://arxiv.org/abs/2006.11239},\n  note = {Accessed: 2026-10-19}\n}"
+        );
+        assert_eq!(
+            citation.apa(),
+            "Ho, J., Jain, A., & Abbeel, P. (2020). Denoising Diffusion Probabilistic Models (arXiv:2006.11239). arXiv. https://arxiv.org/abs/2006.11239"
+        );
+        assert_eq!(
+            citation.markdown(),
+            "[Denoising Diffusion Probabilistic Models](https://arxiv.org/abs/2006.11239) — Ho et al., 2020 (arXiv:2006.11239)"
+        );
+    }
+
+    #[test]
+    fn test_wikipedia_and_github_formats() {
+        let article = WikiArticle {
+            title: "Rust (programming language)".to_string(),
+            extract: String::new(),
+            language: Language::ENGLISH,
+        };
+        let mut citation = Citation::from_article(&article);
+        citation.accessed = NaiveDate::from_ymd_opt(2026, 10, 19).unwrap();
+        assert_eq!(citation.url, "https://en.wikipedia.org/wiki/Rust_(programming_language)");
+        assert_eq!(
+            citation.apa(),
+            "Rust (programming language). (n.d.). In Wikipedia. Retrieved October 19, 2026, from https://en.wikipedia.org/wiki/Rust_(programming_language)"
+        );
+        assert!(citation.bibtex().starts_with("@misc{wikipedia_rust_programming_language,"));
+
+        let repo = Citation::from_repo("rust", "https://github.com/rust-lang/rust");
+        assert_eq!(repo.authors, vec!["rust-lang"]);
+        assert!(repo.apa().starts_with("rust-lang. (n.d.). rust [Computer software]. GitHub."));
+        assert_eq!(repo.markdown(), "[rust](https://github.com/rust-lang/rust) — rust-lang — GitHub");
+
+        let snippet = CodeSnippet {
+            repository: "rust-lang/rust".to_string(),
+            path: "library/std/src/fs.rs".to_string(),
+            language: "rust".to_string(),
+            code: "pub fn read_to_string".to_string(),
+            lines: Some((10, 12)),
+            permalink: "https://github.com/rust-lang/rust/blob/abc123/library/std/src/fs.rs#L10-L12".to_string(),
+        };
+        let file = Citation::from_snippet(&snippet);
+        assert_eq!(file.title, "rust-lang/rust: library/std/src/fs.rs");
+        assert_eq!(file.authors, vec!["rust-lang"]);
+        assert_eq!(file.url, snippet.permalink);
+    }
+
+    #[test]
+    fn test_cite_requests() {
+        assert!(is_cite_request("Cite that!"));
+        assert!(is_cite_request("процитируй"));
+        assert!(!is_cite_request("what is citation analysis"));
+    }
+}
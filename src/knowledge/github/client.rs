@@ -0,0 +1,353 @@
+use chrono::{DateTime, TimeZone, Utc};
+use reqwest::{header::HeaderMap, Client, StatusCode};
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+pub(super) const API_URL: &str = "https://api.github.com";
+
+/// Сколько секунд можно подождать сброса лимита, прежде чем отказать пользователю
+const DEFAULT_MAX_WAIT_SECS: u64 = 10;
+
+/// Сколько ответов хранить для условных запросов (`If-None-Match`)
+const ETAG_CACHE_SIZE: usize = 200;
+
+/// Лимит запросов из заголовков `X-RateLimit-*`
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateLimit {
+    pub limit: u32,
+    pub remaining: u32,
+    pub reset: DateTime<Utc>,
+    /// Группа лимита: `core`, `search`, `code_search`
+    pub resource: String,
+}
+
+impl RateLimit {
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok()).map(str::trim);
+        Some(Self {
+            limit: header("x-ratelimit-limit")?.parse().ok()?,
+            remaining: header("x-ratelimit-remaining")?.parse().ok()?,
+            reset: Utc.timestamp_opt(header("x-ratelimit-reset")?.parse().ok()?, 0).single()?,
+            resource: header("x-ratelimit-resource").unwrap_or("core").to_string(),
+        })
+    }
+}
+
+/// Что делать с запросом при известном лимите
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Decision {
+    Proceed,
+    /// Лимит скоро сбросится — подождать
+    Wait(Duration),
+    /// Ждать слишком долго — сразу отказать
+    Fail,
+}
+
+fn decide(limit: Option<&RateLimit>, now: DateTime<Utc>, max_wait: Duration) -> Decision {
+    let Some(limit) = limit.filter(|limit| limit.remaining == 0 && limit.reset > now) else {
+        return Decision::Proceed;
+    };
+    // Секунда запаса: часы GitHub и наши могут немного расходиться
+    let wait = (limit.reset - now).to_std().unwrap_or_default() + Duration::from_secs(1);
+    if wait <= max_wait {
+        Decision::Wait(wait)
+    } else {
+        Decision::Fail
+    }
+}
+
+/// Ошибка запроса к GitHub API
+#[derive(Debug)]
+pub enum GithubError {
+    /// Квота запросов исчерпана до `reset`
+    RateLimited { reset: DateTime<Utc>, authenticated: bool },
+    NotFound,
+    /// Запрос требует токена (поиск кода без `GITHUB_TOKEN`)
+    Unauthorized,
+    Api(String),
+}
+
+impl fmt::Display for GithubError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GithubError::RateLimited { reset, authenticated } => {
+                let minutes = (*reset - Utc::now()).num_minutes().max(0) + 1;
+                write!(
+                    f,
+                    "⏳ Лимит запросов к GitHub исчерпан. Попробуйте через {} мин. (после {} UTC).",
+                    minutes,
+                    reset.format("%H:%M")
+                )?;
+                if !authenticated {
+                    write!(f, " С токеном GITHUB_TOKEN лимит намного выше.")?;
+                }
+                Ok(())
+            }
+            GithubError::NotFound => write!(f, "GitHub: не найдено"),
+            GithubError::Unauthorized => write!(f, "GitHub: для этого запроса нужен токен GITHUB_TOKEN"),
+            GithubError::Api(message) => write!(f, "GitHub API: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for GithubError {}
+
+/// Сохранённый ответ для условного запроса
+struct CachedResponse {
+    etag: String,
+    body: String,
+}
+
+/// Клиент GitHub API: токен из `GITHUB_TOKEN`, учёт лимитов по заголовкам `X-RateLimit-*`
+/// и условные запросы по ETag (ответ 304 не расходует лимит)
+pub struct GithubClient {
+    http: Client,
+    token: Option<String>,
+    max_wait: Duration,
+    limits: Mutex<HashMap<String, RateLimit>>,
+    etags: Mutex<HashMap<String, CachedResponse>>,
+}
+
+impl GithubClient {
+    pub fn new(token: Option<String>, max_wait: Duration) -> Self {
+        let http = Client::builder()
+            .timeout(Duration::from_secs(15))
+            .user_agent("Bot-Auraya/1.0")
+            .build()
+            .unwrap_or_default();
+        Self {
+            http,
+            token: token.map(|t| t.trim().to_string()).filter(|t| !t.is_empty()),
+            max_wait,
+            limits: Mutex::new(HashMap::new()),
+            etags: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// `GITHUB_TOKEN` (необязателен) и `GITHUB_MAX_WAIT_SECS`
+    pub fn from_env() -> Self {
+        let max_wait = std::env::var("GITHUB_MAX_WAIT_SECS")
+            .ok()
+            .and_then(|secs| secs.trim().parse().ok())
+            .unwrap_or(DEFAULT_MAX_WAIT_SECS);
+        Self::new(std::env::var("GITHUB_TOKEN").ok(), Duration::from_secs(max_wait))
+    }
+
+    pub fn is_authenticated(&self) -> bool {
+        self.token.is_some()
+    }
+
+    /// Последний известный лимит группы `resource`
+    pub fn rate_limit(&self, resource: &str) -> Option<RateLimit> {
+        self.limits.lock().ok()?.get(resource).cloned()
+    }
+
+    /// GET-запрос к API (`path` — от корня: `/search/repositories?q=...`) с разбором JSON
+    pub async fn get_json<T: DeserializeOwned>(&self, path: &str) -> Result<T, GithubError> {
+        self.get_json_as(path, "application/vnd.github+json").await
+    }
+
+    /// То же, что [`GithubClient::get_json`], с другим типом ответа: например,
+    /// `application/vnd.github.text-match+json` добавляет к поиску совпавшие фрагменты
+    pub async fn get_json_as<T: DeserializeOwned>(&self, path: &str, accept: &str) -> Result<T, GithubError> {
+        let body = self.get(path, accept).await?;
+        serde_json::from_str(&body).map_err(|e| GithubError::Api(format!("ошибка разбора JSON: {}", e)))
+    }
+
+    async fn get(&self, path: &str, accept: &str) -> Result<String, GithubError> {
+        let resource = resource_of(path);
+        // Повторяем не больше одного раза: после ожидания сброса лимита
+        for attempt in 0..2 {
+            let known = self.rate_limit(resource);
+            match decide(known.as_ref(), Utc::now(), self.max_wait) {
+                Decision::Proceed => {}
+                Decision::Wait(wait) => {
+                    println!("⏳ GitHub: лимит {} исчерпан, ждём {} с", resource, wait.as_secs());
+                    tokio::time::sleep(wait).await;
+                }
+                Decision::Fail => return Err(self.rate_limited(known)),
+            }
+
+            let url = format!("{}{}", API_URL, path);
+            // Тело ответа зависит и от Accept (text-match добавляет фрагменты)
+            let cache_key = etag_key(&url, accept);
+            let mut request = self.http.get(&url).header("Accept", accept);
+            if let Some(token) = &self.token {
+                request = request.bearer_auth(token);
+            }
+            if let Some(etag) = self.cached_etag(&cache_key) {
+                request = request.header("If-None-Match", etag);
+            }
+
+            let response = request.send().await.map_err(|e| GithubError::Api(format!("запрос не удался: {}", e)))?;
+            let limit = RateLimit::from_headers(response.headers());
+            if let (Some(limit), Ok(mut limits)) = (limit.clone(), self.limits.lock()) {
+                limits.insert(limit.resource.clone(), limit);
+            }
+
+            let status = response.status();
+            if status == StatusCode::NOT_MODIFIED {
+                if let Some(body) = self.cached_body(&cache_key) {
+                    println!("♻️ GitHub: ответ не изменился (ETag)");
+                    return Ok(body);
+                }
+            }
+            if status == StatusCode::NOT_FOUND {
+                return Err(GithubError::NotFound);
+            }
+            if status == StatusCode::UNAUTHORIZED {
+                return Err(GithubError::Unauthorized);
+            }
+
+            // Основной (remaining = 0) или вторичный (Retry-After) лимит
+            let retry_after = response
+                .headers()
+                .get("retry-after")
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.trim().parse::<u64>().ok());
+            let exhausted = limit.as_ref().is_some_and(|l| l.remaining == 0) || retry_after.is_some();
+            if (status == StatusCode::FORBIDDEN || status == StatusCode::TOO_MANY_REQUESTS) && exhausted {
+                let reset = match retry_after {
+                    Some(secs) => Utc::now() + chrono::Duration::seconds(secs as i64),
+                    None => limit.as_ref().map(|l| l.reset).unwrap_or_else(Utc::now),
+                };
+                println!("⚠️ GitHub: лимит {} исчерпан до {}", resource, reset);
+                if let Ok(mut limits) = self.limits.lock() {
+                    let known = limits.entry(resource.to_string()).or_insert_with(|| RateLimit {
+                        limit: 0,
+                        remaining: 0,
+                        reset,
+                        resource: resource.to_string(),
+                    });
+                    known.remaining = 0;
+                    known.reset = known.reset.max(reset);
+                }
+                if attempt == 0 {
+                    continue;
+                }
+                return Err(self.rate_limited(self.rate_limit(resource)));
+            }
+
+            if !status.is_success() {
+                let error_text = response.text().await.unwrap_or_default();
+                return Err(GithubError::Api(format!("статус {}: {}", status, error_text)));
+            }
+
+            let etag = response.headers().get("etag").and_then(|v| v.to_str().ok()).map(str::to_string);
+            let body = response.text().await.map_err(|e| GithubError::Api(format!("ошибка чтения ответа: {}", e)))?;
+            if let Some(etag) = etag {
+                self.store_etag(&cache_key, etag, &body);
+            }
+            return Ok(body);
+        }
+        Err(self.rate_limited(self.rate_limit(resource)))
+    }
+
+    fn rate_limited(&self, limit: Option<RateLimit>) -> GithubError {
+        GithubError::RateLimited {
+            reset: limit.map(|l| l.reset).unwrap_or_else(Utc::now),
+            authenticated: self.is_authenticated(),
+        }
+    }
+
+    fn cached_etag(&self, key: &str) -> Option<String> {
+        self.etags.lock().ok()?.get(key).map(|cached| cached.etag.clone())
+    }
+
+    fn cached_body(&self, key: &str) -> Option<String> {
+        self.etags.lock().ok()?.get(key).map(|cached| cached.body.clone())
+    }
+
+    fn store_etag(&self, key: &str, etag: String, body: &str) {
+        if let Ok(mut etags) = self.etags.lock() {
+            if etags.len() >= ETAG_CACHE_SIZE && !etags.contains_key(key) {
+                etags.clear();
+            }
+            etags.insert(key.to_string(), CachedResponse { etag, body: body.to_string() });
+        }
+    }
+}
+
+/// Ключ сохранённого ответа: адрес и тип ответа (`Accept`)
+fn etag_key(url: &str, accept: &str) -> String {
+    format!("{}|{}", url, accept)
+}
+
+/// Группа лимита по пути запроса: у поиска кода и остального поиска свои квоты
+fn resource_of(path: &str) -> &'static str {
+    if path.starts_with("/search/code") {
+        "code_search"
+    } else if path.starts_with("/search/") {
+        "search"
+    } else {
+        "core"
+    }
+}
+
+/// Общий клиент GitHub (лимиты и ETag общие для всех запросов процесса)
+pub fn github_client() -> &'static GithubClient {
+    static CLIENT: OnceLock<GithubClient> = OnceLock::new();
+    CLIENT.get_or_init(GithubClient::from_env)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn test_rate_limit_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ratelimit-limit", HeaderValue::from_static("10"));
+        headers.insert("x-ratelimit-remaining", HeaderValue::from_static("0"));
+        headers.insert("x-ratelimit-reset", HeaderValue::from_static("1792400000"));
+        headers.insert("x-ratelimit-resource", HeaderValue::from_static("search"));
+        let limit = RateLimit::from_headers(&headers).unwrap();
+        assert_eq!((limit.limit, limit.remaining, limit.resource.as_str()), (10, 0, "search"));
+        assert_eq!(limit.reset.timestamp(), 1792400000);
+        assert_eq!(RateLimit::from_headers(&HeaderMap::new()), None);
+    }
+
+    #[test]
+    fn test_wait_or_fail() {
+        let now = Utc.timestamp_opt(1_000_000, 0).unwrap();
+        let max_wait = Duration::from_secs(10);
+        let limit = |remaining, reset_in: i64| RateLimit {
+            limit: 10,
+            remaining,
+            reset: now + chrono::Duration::seconds(reset_in),
+            resource: "search".to_string(),
+        };
+        assert_eq!(decide(None, now, max_wait), Decision::Proceed);
+        assert_eq!(decide(Some(&limit(3, 30)), now, max_wait), Decision::Proceed);
+        assert_eq!(decide(Some(&limit(0, 5)), now, max_wait), Decision::Wait(Duration::from_secs(6)));
+        assert_eq!(decide(Some(&limit(0, 600)), now, max_wait), Decision::Fail);
+        // Время сброса прошло — лимит уже восстановлен
+        assert_eq!(decide(Some(&limit(0, -5)), now, max_wait), Decision::Proceed);
+    }
+
+    #[test]
+    fn test_etag_key_includes_accept() {
+        let client = GithubClient::new(None, Duration::from_secs(0));
+        let url = "https://api.github.com/search/code?q=x";
+        client.store_etag(&etag_key(url, "application/vnd.github+json"), "\"a\"".to_string(), "plain");
+        client.store_etag(&etag_key(url, "application/vnd.github.text-match+json"), "\"b\"".to_string(), "matches");
+        assert_eq!(client.cached_body(&etag_key(url, "application/vnd.github+json")).as_deref(), Some("plain"));
+        assert_eq!(client.cached_etag(&etag_key(url, "application/vnd.github.text-match+json")).as_deref(), Some("\"b\""));
+    }
+
+    #[test]
+    fn test_resource_and_message() {
+        assert_eq!(resource_of("/search/code?q=x"), "code_search");
+        assert_eq!(resource_of("/search/repositories?q=x"), "search");
+        assert_eq!(resource_of("/repos/rust-lang/rust"), "core");
+
+        let error = GithubError::RateLimited { reset: Utc::now() + chrono::Duration::minutes(5), authenticated: false };
+        let message = error.to_string();
+        assert!(message.starts_with("⏳ Лимит запросов к GitHub исчерпан"));
+        assert!(message.contains("GITHUB_TOKEN"));
+    }
+}
@@ -0,0 +1,350 @@
+use serde::Deserialize;
+
+use super::{
+    client::{github_client, GithubError, API_URL},
+    insight::decode_content,
+};
+use crate::language::normalize::{is_filler_word, tokenize, SEARCH_PAGE_SIZE};
+
+/// Сколько фрагментов показывать в ответе
+const MAX_SNIPPETS: usize = 3;
+
+/// Строк до и после совпадения
+const CONTEXT_LINES: usize = 3;
+
+/// Длина фрагмента в строках, чтобы ответ не превращался в целый файл
+const MAX_SNIPPET_LINES: usize = 25;
+
+/// Слова вопроса, которые не нужны поиску кода (кроме общих служебных слов):
+/// «how to read a file in rust» → `read file`
+const FILLER_WORDS: &[&str] = &[
+    "how", "to", "do", "does", "i", "you", "we", "can", "using", "is", "what", "way", "best", "code", "example",
+    "examples", "snippet", "snippets", "write", "github", "language", "programming",
+];
+
+#[derive(Debug, Deserialize)]
+struct CodeSearchResponse {
+    #[serde(default)]
+    total_count: u64,
+    #[serde(default)]
+    items: Vec<CodeItem>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CodeItem {
+    path: String,
+    /// Ссылка на файл в конкретном коммите
+    html_url: String,
+    /// Адрес файла в contents API
+    url: String,
+    repository: CodeRepository,
+    #[serde(default)]
+    text_matches: Vec<TextMatch>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CodeRepository {
+    full_name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TextMatch {
+    #[serde(default)]
+    fragment: String,
+    /// `content` — совпадение в тексте файла, `path` — в имени
+    property: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ContentItem {
+    content: String,
+}
+
+/// Запрос к поиску кода: ключевые слова и язык
+#[derive(Debug, Clone, PartialEq)]
+pub struct CodeQuery {
+    pub terms: String,
+    pub language: Option<String>,
+    pub page: u32,
+    pub per_page: u32,
+}
+
+impl CodeQuery {
+    /// Разбирает вопрос на английском; `language` — уже распознанный язык программирования
+    pub fn parse(input: &str, language: Option<&str>) -> Self {
+        let language = language.filter(|l| !l.is_empty()).map(str::to_lowercase);
+        let terms: Vec<String> = tokenize(input)
+            .into_iter()
+            .filter(|token| !is_filler_word(token, FILLER_WORDS))
+            .filter(|token| !language.as_deref().is_some_and(|l| is_language_name(token, l)))
+            .take(5)
+            .collect();
+        Self { terms: terms.join(" "), language, page: 1, per_page: SEARCH_PAGE_SIZE as u32 }
+    }
+
+    /// Есть ли что искать, кроме языка
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// Параметр `q` поиска
+    pub fn to_query_string(&self) -> String {
+        match &self.language {
+            Some(language) => format!("{} language:{}", self.terms, language),
+            None => self.terms.clone(),
+        }
+    }
+
+    /// Путь запроса к API
+    fn path(&self) -> String {
+        format!(
+            "/search/code?q={}&per_page={}&page={}",
+            urlencoding::encode(&self.to_query_string()),
+            self.per_page,
+            self.page
+        )
+    }
+}
+
+/// Слово — название языка `language` или его сокращение («rs», «golang»)
+fn is_language_name(token: &str, language: &str) -> bool {
+    let aliases: &[&str] = match language {
+        "rust" => &["rs"],
+        "python" => &["py"],
+        "javascript" => &["js"],
+        "typescript" => &["ts"],
+        "go" => &["golang"],
+        "cpp" => &["c++"],
+        "csharp" => &["c#", "cs"],
+        _ => &[],
+    };
+    token == language || aliases.contains(&token)
+}
+
+/// Найденный фрагмент кода
+#[derive(Debug, Clone, PartialEq)]
+pub struct CodeSnippet {
+    /// `owner/name`
+    pub repository: String,
+    pub path: String,
+    /// Язык для подсветки блока кода (`rust`, `python`), пустой — без подсветки
+    pub language: String,
+    pub code: String,
+    /// Номера первой и последней строки (с 1), если файл удалось загрузить
+    pub lines: Option<(usize, usize)>,
+    /// Постоянная ссылка на строки в конкретном коммите
+    pub permalink: String,
+}
+
+/// Результат поиска кода
+#[derive(Debug, Clone, PartialEq)]
+pub struct CodeSearch {
+    pub total_count: u64,
+    pub snippets: Vec<CodeSnippet>,
+}
+
+/// Ищет код на GitHub и возвращает совпавшие фрагменты с окружающими строками.
+/// Поиск кода доступен только с токеном: без `GITHUB_TOKEN` — [`GithubError::Unauthorized`].
+pub async fn search_code(query: &CodeQuery) -> Result<CodeSearch, GithubError> {
+    let client = github_client();
+    if !client.is_authenticated() {
+        return Err(GithubError::Unauthorized);
+    }
+    let path = query.path();
+    println!("🔍 GitHub: {}", path);
+
+    // text-match добавляет к каждому файлу совпавшие фрагменты
+    let search: CodeSearchResponse = client.get_json_as(&path, "application/vnd.github.text-match+json").await?;
+    let mut snippets = Vec::new();
+    for item in search.items {
+        if snippets.len() >= MAX_SNIPPETS {
+            break;
+        }
+        let Some(fragment) = item
+            .text_matches
+            .iter()
+            .find(|m| m.property.as_deref().unwrap_or("content") == "content" && !m.fragment.trim().is_empty())
+            .map(|m| m.fragment.clone())
+        else {
+            continue;
+        };
+
+        // Фрагменты поиска обрезаны посреди строки — берём целые строки из самого файла
+        let content_path = item.url.strip_prefix(API_URL).unwrap_or(&item.url);
+        let content = match client.get_json::<ContentItem>(content_path).await {
+            Ok(file) => decode_content(&file.content),
+            Err(e) => {
+                println!("⚠️ GitHub: файл {} недоступен: {}", item.path, e);
+                None
+            }
+        };
+        let language = fence_language(&item.path, query.language.as_deref()).to_string();
+        let snippet = match content.as_deref().and_then(|content| surrounding_lines(content, &fragment, CONTEXT_LINES)) {
+            Some((first, last, code)) => CodeSnippet {
+                repository: item.repository.full_name,
+                path: item.path,
+                language,
+                code,
+                lines: Some((first, last)),
+                permalink: format!("{}#L{}-L{}", item.html_url, first, last),
+            },
+            None => CodeSnippet {
+                repository: item.repository.full_name,
+                path: item.path,
+                language,
+                code: dedent(&fragment.lines().collect::<Vec<_>>()),
+                lines: None,
+                permalink: item.html_url,
+            },
+        };
+        snippets.push(snippet);
+    }
+
+    Ok(CodeSearch { total_count: search.total_count, snippets })
+}
+
+/// Строки файла вокруг фрагмента: номера первой и последней строки (с 1) и текст без общего отступа
+fn surrounding_lines(content: &str, fragment: &str, context: usize) -> Option<(usize, usize, String)> {
+    let fragment = fragment.trim();
+    let offset = content.find(fragment)?;
+    let first = content[..offset].matches('\n').count();
+    let count = fragment.lines().count().max(1);
+
+    let lines: Vec<&str> = content.lines().collect();
+    let start = first.saturating_sub(context);
+    let end = (first + count + context).min(lines.len()).min(start + MAX_SNIPPET_LINES);
+    // Пустые строки по краям не нужны
+    let mut range = start..end;
+    while range.start < range.end && lines[range.start].trim().is_empty() {
+        range.start += 1;
+    }
+    while range.end > range.start && lines[range.end - 1].trim().is_empty() {
+        range.end -= 1;
+    }
+    if range.is_empty() {
+        return None;
+    }
+    Some((range.start + 1, range.end, dedent(&lines[range])))
+}
+
+/// Убирает общий отступ строк
+fn dedent(lines: &[&str]) -> String {
+    let indent = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    lines
+        .iter()
+        .map(|line| line.get(indent..).unwrap_or("").trim_end())
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim_matches('\n')
+        .to_string()
+}
+
+/// Язык подсветки по расширению файла, иначе — язык запроса
+fn fence_language<'a>(path: &str, fallback: Option<&'a str>) -> &'a str {
+    let extension = path.rsplit_once('.').map(|(_, ext)| ext.to_lowercase()).unwrap_or_default();
+    match extension.as_str() {
+        "rs" => "rust",
+        "py" => "python",
+        "js" | "mjs" | "cjs" | "jsx" => "javascript",
+        "ts" | "tsx" => "typescript",
+        "go" => "go",
+        "java" => "java",
+        "kt" | "kts" => "kotlin",
+        "swift" => "swift",
+        "rb" => "ruby",
+        "php" => "php",
+        "c" | "h" => "c",
+        "cpp" | "cc" | "cxx" | "hpp" => "cpp",
+        "cs" => "csharp",
+        "scala" => "scala",
+        "hs" => "haskell",
+        "lua" => "lua",
+        "sh" | "bash" => "bash",
+        "sql" => "sql",
+        "html" => "html",
+        "css" => "css",
+        _ => fallback.unwrap_or(""),
+    }
+}
+
+/// Фрагменты кода: репозиторий, файл, строки, блок кода с подсветкой и постоянная ссылка
+pub fn format_snippets(search: &CodeSearch, query: &CodeQuery) -> String {
+    let list = search
+        .snippets
+        .iter()
+        .enumerate()
+        .map(|(i, snippet)| {
+            let lines = match snippet.lines {
+                Some((first, last)) => format!(", lines {}–{}", first, last),
+                None => String::new(),
+            };
+            format!(
+                "{}. 📂 **{}** · `{}`{}\n```{}\n{}\n```\n🔗 {}",
+                i + 1,
+                snippet.repository,
+                snippet.path,
+                lines,
+                snippet.language,
+                snippet.code,
+                snippet.permalink
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    format!("🔎 `{}` — {} files\n\n{}", query.to_query_string(), search.total_count, list)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_code_query_parse() {
+        let query = CodeQuery::parse("How to read a file in Rust?", Some("rust"));
+        assert_eq!(query.terms, "read file");
+        assert_eq!(query.to_query_string(), "read file language:rust");
+        assert_eq!(query.path(), "/search/code?q=read%20file%20language%3Arust&per_page=5&page=1");
+
+        assert!(CodeQuery::parse("show me python code", Some("python")).is_empty());
+        assert_eq!(CodeQuery::parse("parse json", None).to_query_string(), "parse json");
+    }
+
+    #[test]
+    fn test_surrounding_lines() {
+        let content = "use std::fs;\n\nfn main() {\n    let text = fs::read_to_string(\"a.txt\")\n        .unwrap();\n    println!(\"{}\", text);\n}\n\n// end\n";
+        // Фрагмент поиска начинается посреди строки
+        let (first, last, code) = surrounding_lines(content, "fs::read_to_string(\"a.txt\")\n        .unwrap", 1).unwrap();
+        assert_eq!((first, last), (3, 6));
+        assert_eq!(code, "fn main() {\n    let text = fs::read_to_string(\"a.txt\")\n        .unwrap();\n    println!(\"{}\", text);");
+        assert_eq!(surrounding_lines(content, "not in file", 1), None);
+    }
+
+    #[test]
+    fn test_fence_language_and_format() {
+        assert_eq!(fence_language("src/main.rs", None), "rust");
+        assert_eq!(fence_language("lib/util.PY", Some("rust")), "python");
+        assert_eq!(fence_language("Makefile", Some("go")), "go");
+
+        let query = CodeQuery::parse("read file", Some("rust"));
+        let search = CodeSearch {
+            total_count: 42,
+            snippets: vec![CodeSnippet {
+                repository: "owner/repo".to_string(),
+                path: "src/io.rs".to_string(),
+                language: "rust".to_string(),
+                code: "let s = fs::read_to_string(path)?;".to_string(),
+                lines: Some((10, 12)),
+                permalink: "https://github.com/owner/repo/blob/abc/src/io.rs#L10-L12".to_string(),
+            }],
+        };
+        let text = format_snippets(&search, &query);
+        assert!(text.starts_with("🔎 `read file language:rust` — 42 files"));
+        assert!(text.contains("1. 📂 **owner/repo** · `src/io.rs`, lines 10–12\n```rust\nlet s"));
+        assert!(text.ends_with("🔗 https://github.com/owner/repo/blob/abc/src/io.rs#L10-L12"));
+    }
+}
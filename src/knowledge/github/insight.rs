@@ -0,0 +1,353 @@
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use super::{
+    client::{github_client, GithubError},
+    compact_number, RepoItem, Repository,
+};
+use crate::language::segment::{sentences, truncate};
+
+/// Пары через косую черту, которые не являются репозиториями
+const NOT_REPOSITORIES: &[&str] = &[
+    "and/or", "either/or", "km/h", "m/s", "input/output", "true/false", "yes/no", "client/server", "read/write",
+    "on/off", "his/her", "he/she", "tcp/ip", "i/o",
+];
+
+/// Слова, с которыми `owner/repo` без ссылки считается репозиторием
+const REPO_CONTEXT: &[&str] = &["repo", "repository", "github", "project", "crate", "library"];
+
+/// Ссылка на репозиторий GitHub
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepoRef {
+    pub owner: String,
+    pub name: String,
+}
+
+impl RepoRef {
+    pub fn full_name(&self) -> String {
+        format!("{}/{}", self.owner, self.name)
+    }
+}
+
+fn url_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"(?i)(?:https?://)?(?:www\.)?github\.com/([a-z0-9](?:[a-z0-9-]{0,38}))/([a-z0-9._-]{1,100})").unwrap()
+    })
+}
+
+fn bare_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?i)(?:^|[\s(«])([a-z0-9](?:[a-z0-9-]{0,38}))/([a-z0-9._-]{1,100})").unwrap())
+}
+
+/// Находит репозиторий в сообщении: ссылку `https://github.com/tokio-rs/axum/...` или
+/// `tokio-rs/axum` (без ссылки — только если сообщение состоит из неё, говорит о репозитории
+/// или в имени есть `-`, `_`, `.`, как у репозиториев и не бывает у «CI/CD», «GNU/Linux»)
+pub fn parse_repo_reference(input: &str) -> Option<RepoRef> {
+    let make = |owner: &str, name: &str| {
+        let name = name.trim_end_matches(".git").trim_end_matches(['.', ',']);
+        (!name.is_empty()).then(|| RepoRef { owner: owner.to_string(), name: name.to_string() })
+    };
+    if let Some(caps) = url_regex().captures(input) {
+        return make(&caps[1], &caps[2]);
+    }
+
+    let caps = bare_regex().captures(input)?;
+    let (owner, name) = (&caps[1], caps[2].trim_end_matches(['.', ',', '?', '!']));
+    let pair = format!("{}/{}", owner, name).to_lowercase();
+    if NOT_REPOSITORIES.contains(&pair.as_str()) || owner.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let lower = input.to_lowercase();
+    let only_reference = input.trim().trim_end_matches(['?', '!', '.']).eq_ignore_ascii_case(&pair);
+    let has_context = lower
+        .split(|c: char| !c.is_alphanumeric())
+        .any(|word| REPO_CONTEXT.contains(&word));
+    let looks_like_repo = pair.contains(['-', '_', '.']);
+    (only_reference || has_context || looks_like_repo).then(|| make(owner, name)).flatten()
+}
+
+#[derive(Debug, Deserialize)]
+struct RepoDetails {
+    #[serde(flatten)]
+    item: RepoItem,
+    #[serde(default)]
+    open_issues_count: u64,
+    homepage: Option<String>,
+    #[serde(default)]
+    archived: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReleaseItem {
+    tag_name: String,
+    name: Option<String>,
+    published_at: Option<DateTime<Utc>>,
+    html_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReadmeItem {
+    content: String,
+}
+
+/// Последний релиз
+#[derive(Debug, Clone, PartialEq)]
+pub struct Release {
+    pub tag: String,
+    pub name: Option<String>,
+    pub published_at: Option<DateTime<Utc>>,
+    pub url: String,
+}
+
+/// Обзор репозитория: метаданные, последний релиз, языки и краткое содержание README
+#[derive(Debug, Clone, PartialEq)]
+pub struct RepoInsight {
+    pub repository: Repository,
+    pub open_issues: u64,
+    pub homepage: Option<String>,
+    pub archived: bool,
+    pub release: Option<Release>,
+    /// Языки по убыванию доли (в процентах)
+    pub languages: Vec<(String, f64)>,
+    pub readme_summary: Option<String>,
+}
+
+/// Собирает обзор репозитория. Релиз, языки и README необязательны: их ошибки
+/// только логируются.
+pub async fn fetch_repo_insight(repo: &RepoRef) -> Result<RepoInsight, GithubError> {
+    let base = format!("/repos/{}/{}", urlencoding::encode(&repo.owner), urlencoding::encode(&repo.name));
+    println!("🔍 GitHub: обзор {}", repo.full_name());
+
+    let client = github_client();
+    let (release_path, languages_path, readme_path) =
+        (format!("{}/releases/latest", base), format!("{}/languages", base), format!("{}/readme", base));
+    let (details, release, languages, readme) = tokio::join!(
+        client.get_json::<RepoDetails>(&base),
+        client.get_json::<ReleaseItem>(&release_path),
+        client.get_json::<HashMap<String, u64>>(&languages_path),
+        client.get_json::<ReadmeItem>(&readme_path),
+    );
+    let details = details?;
+
+    let release = match release {
+        Ok(release) => Some(Release {
+            tag: release.tag_name,
+            name: release.name.filter(|n| !n.trim().is_empty()),
+            published_at: release.published_at,
+            url: release.html_url,
+        }),
+        // 404 — у репозитория просто нет релизов
+        Err(GithubError::NotFound) => None,
+        Err(e) => {
+            println!("⚠️ GitHub: релиз недоступен: {}", e);
+            None
+        }
+    };
+    let languages = match languages {
+        Ok(bytes) => language_shares(bytes),
+        Err(e) => {
+            println!("⚠️ GitHub: языки недоступны: {}", e);
+            Vec::new()
+        }
+    };
+    let readme_summary = match readme {
+        Ok(readme) => decode_content(&readme.content).map(|text| summarize_readme(&text)).filter(|s| !s.is_empty()),
+        Err(GithubError::NotFound) => None,
+        Err(e) => {
+            println!("⚠️ GitHub: README недоступен: {}", e);
+            None
+        }
+    };
+
+    Ok(RepoInsight {
+        repository: Repository::from(details.item),
+        open_issues: details.open_issues_count,
+        homepage: details.homepage.filter(|h| !h.trim().is_empty()),
+        archived: details.archived,
+        release,
+        languages,
+        readme_summary,
+    })
+}
+
+/// Доли языков в процентах по убыванию
+fn language_shares(bytes: HashMap<String, u64>) -> Vec<(String, f64)> {
+    let total: u64 = bytes.values().sum();
+    if total == 0 {
+        return Vec::new();
+    }
+    let mut shares: Vec<(String, f64)> = bytes
+        .into_iter()
+        .map(|(language, count)| (language, count as f64 * 100.0 / total as f64))
+        .collect();
+    shares.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    shares
+}
+
+/// Файлы (README, исходники) приходят из API в base64 с переносами строк
+pub(super) fn decode_content(content: &str) -> Option<String> {
+    let compact: String = content.chars().filter(|c| !c.is_whitespace()).collect();
+    let bytes = BASE64.decode(compact).ok()?;
+    String::from_utf8(bytes).ok()
+}
+
+/// Краткое содержание README: первые абзацы текста без заголовков, бейджей, HTML и кода,
+/// не больше трёх предложений
+pub fn summarize_readme(markdown: &str) -> String {
+    static LINK: OnceLock<Regex> = OnceLock::new();
+    static HTML: OnceLock<Regex> = OnceLock::new();
+    let link = LINK.get_or_init(|| Regex::new(r"!?\[([^\]]*)\]\([^)]*\)").unwrap());
+    let html = HTML.get_or_init(|| Regex::new(r"<[^>]+>").unwrap());
+
+    let mut paragraphs: Vec<String> = Vec::new();
+    let mut current = String::new();
+    let mut in_code = false;
+    for line in markdown.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_code = !in_code;
+            continue;
+        }
+        // Заголовки, бейджи, таблицы, цитаты и списки — не описание проекта
+        let skip = in_code
+            || trimmed.starts_with('#')
+            || trimmed.starts_with("![")
+            || trimmed.starts_with("[![")
+            || trimmed.starts_with('|')
+            || trimmed.starts_with('>')
+            || trimmed.starts_with("- ")
+            || trimmed.starts_with("* ")
+            || trimmed.starts_with('<')
+            || trimmed.chars().all(|c| matches!(c, '=' | '-' | '*' | '_'));
+        if trimmed.is_empty() || skip {
+            if !current.trim().is_empty() {
+                paragraphs.push(std::mem::take(&mut current));
+            }
+            current.clear();
+            continue;
+        }
+        let without_links = link.replace_all(trimmed, "$1");
+        let text = html.replace_all(&without_links, "");
+        let text = text.replace(['*', '`'], "");
+        if !text.trim().is_empty() {
+            current.push_str(text.trim());
+            current.push(' ');
+        }
+    }
+    if !current.trim().is_empty() {
+        paragraphs.push(current);
+    }
+
+    let text = paragraphs.iter().take(2).map(|p| p.trim()).collect::<Vec<_>>().join(" ");
+    let summary = sentences(&text).into_iter().take(3).collect::<Vec<_>>().join(" ");
+    truncate(&summary, 400)
+}
+
+/// Обзор репозитория для ответа
+pub fn format_insight(insight: &RepoInsight) -> String {
+    let repo = &insight.repository;
+    let mut lines = vec![format!(
+        "📦 **{}**{} ⭐ {} · 🍴 {} · 🐞 {} open issues",
+        repo.full_name,
+        if insight.archived { " (archived)" } else { "" },
+        compact_number(repo.stars),
+        compact_number(repo.forks),
+        compact_number(insight.open_issues)
+    )];
+    if let Some(description) = &repo.description {
+        lines.push(format!("💡 {}", description));
+    }
+
+    let mut meta = Vec::new();
+    if let Some(license) = &repo.license {
+        meta.push(format!("📜 {}", license));
+    }
+    if let Some(pushed) = repo.pushed_at {
+        meta.push(format!("🕒 last push {}", pushed.format("%Y-%m-%d")));
+    }
+    if !meta.is_empty() {
+        lines.push(meta.join(" · "));
+    }
+    if let Some(release) = &insight.release {
+        let mut line = format!("🏷️ Latest release: {}", release.tag);
+        if let Some(name) = release.name.as_ref().filter(|name| **name != release.tag) {
+            line.push_str(&format!(" — {}", name));
+        }
+        if let Some(published) = release.published_at {
+            line.push_str(&format!(" ({})", published.format("%Y-%m-%d")));
+        }
+        lines.push(line);
+    }
+    if !insight.languages.is_empty() {
+        let languages = insight
+            .languages
+            .iter()
+            .take(5)
+            .map(|(language, share)| format!("{} {:.1}%", language, share))
+            .collect::<Vec<_>>()
+            .join(" · ");
+        lines.push(format!("🔤 {}", languages));
+    }
+    if !repo.topics.is_empty() {
+        lines.push(format!("🏷️ {}", repo.topics.iter().take(8).cloned().collect::<Vec<_>>().join(", ")));
+    }
+    if let Some(summary) = &insight.readme_summary {
+        lines.push(format!("\n📘 README: {}", summary));
+    }
+    lines.push(String::new());
+    lines.push(format!("🔗 {}", repo.url));
+    if let Some(homepage) = &insight.homepage {
+        lines.push(format!("🌐 {}", homepage));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reference(input: &str) -> Option<String> {
+        parse_repo_reference(input).map(|r| r.full_name())
+    }
+
+    #[test]
+    fn test_parse_repo_reference() {
+        assert_eq!(reference("https://github.com/tokio-rs/axum/tree/main/examples").as_deref(), Some("tokio-rs/axum"));
+        assert_eq!(reference("look at github.com/serde-rs/serde.git").as_deref(), Some("serde-rs/serde"));
+        assert_eq!(reference("tell me about tokio-rs/axum").as_deref(), Some("tokio-rs/axum"));
+        assert_eq!(reference("rust-lang/rust").as_deref(), Some("rust-lang/rust"));
+        assert_eq!(reference("what about and/or logic"), None);
+        assert_eq!(reference("speed is 5 km/h"), None);
+        assert_eq!(reference("2/3 of the people"), None);
+        assert_eq!(reference("tell me about CI/CD"), None);
+        assert_eq!(reference("tell me about GNU/Linux"), None);
+        assert_eq!(reference("tell me about AC/DC"), None);
+        assert_eq!(reference("github repo torvalds/linux").as_deref(), Some("torvalds/linux"));
+    }
+
+    #[test]
+    fn test_summarize_readme() {
+        let readme = "# axum\n\n[![Build](https://img.shields.io/badge.svg)](https://ci)\n\n\
+            `axum` is a web application framework that focuses on **ergonomics** and modularity. \
+            It is built with [Tokio](https://tokio.rs).\n\nMore info below. Ignored sentence.\n\n\
+            ```rust\nfn main() {}\n```\n\n## High level features\n- Route requests";
+        assert_eq!(
+            summarize_readme(readme),
+            "axum is a web application framework that focuses on ergonomics and modularity. It is built with Tokio. More info below."
+        );
+    }
+
+    #[test]
+    fn test_language_shares_and_decode() {
+        let shares = language_shares(HashMap::from([("Rust".to_string(), 900), ("Shell".to_string(), 100)]));
+        assert_eq!(shares[0].0, "Rust");
+        assert!((shares[0].1 - 90.0).abs() < 1e-9);
+        assert_eq!(decode_content("SGVsbG8s\nIHdvcmxk").as_deref(), Some("Hello, world"));
+    }
+}
@@ -0,0 +1,481 @@
+pub mod client;
+pub mod code_search;
+pub mod insight;
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use regex::Regex;
+use serde::Deserialize;
+use std::sync::OnceLock;
+
+use crate::language::{
+    fuzzy::default_matcher,
+    normalize::{is_filler_word, KeywordSet, SEARCH_PAGE_SIZE},
+    segment::truncate,
+};
+use client::{github_client, GithubError};
+
+#[derive(Debug, Deserialize)]
+struct SearchResponse {
+    #[serde(default)]
+    total_count: u64,
+    #[serde(default)]
+    items: Vec<RepoItem>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RepoItem {
+    name: String,
+    full_name: String,
+    owner: Owner,
+    description: Option<String>,
+    html_url: String,
+    #[serde(default)]
+    stargazers_count: u64,
+    #[serde(default)]
+    forks_count: u64,
+    license: Option<License>,
+    language: Option<String>,
+    #[serde(default)]
+    topics: Vec<String>,
+    pushed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Owner {
+    login: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct License {
+    spdx_id: Option<String>,
+    name: String,
+}
+
+/// Репозиторий GitHub
+#[derive(Debug, Clone, PartialEq)]
+pub struct Repository {
+    pub name: String,
+    /// `owner/name`
+    pub full_name: String,
+    pub owner: String,
+    pub description: Option<String>,
+    pub url: String,
+    pub stars: u64,
+    pub forks: u64,
+    /// SPDX-идентификатор (`MIT`, `Apache-2.0`) или название лицензии
+    pub license: Option<String>,
+    pub language: Option<String>,
+    pub topics: Vec<String>,
+    pub pushed_at: Option<DateTime<Utc>>,
+}
+
+impl From<RepoItem> for Repository {
+    fn from(item: RepoItem) -> Self {
+        let license = item.license.map(|license| match license.spdx_id {
+            // У нестандартных лицензий GitHub возвращает `NOASSERTION`
+            Some(id) if id != "NOASSERTION" => id,
+            _ => license.name,
+        });
+        Self {
+            name: item.name,
+            full_name: item.full_name,
+            owner: item.owner.login,
+            description: item.description.filter(|d| !d.trim().is_empty()),
+            url: item.html_url,
+            stars: item.stargazers_count,
+            forks: item.forks_count,
+            license,
+            language: item.language,
+            topics: item.topics,
+            pushed_at: item.pushed_at,
+        }
+    }
+}
+
+/// Страница результатов поиска
+#[derive(Debug, Clone, PartialEq)]
+pub struct RepoSearch {
+    pub total_count: u64,
+    pub repositories: Vec<Repository>,
+}
+
+/// Порядок выдачи
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RepoSort {
+    /// Сортировка GitHub по релевантности
+    #[default]
+    BestMatch,
+    Stars,
+    Forks,
+    Updated,
+}
+
+impl RepoSort {
+    fn as_param(&self) -> Option<&'static str> {
+        match self {
+            RepoSort::BestMatch => None,
+            RepoSort::Stars => Some("stars"),
+            RepoSort::Forks => Some("forks"),
+            RepoSort::Updated => Some("updated"),
+        }
+    }
+}
+
+/// Языки, которые распознаются в тексте запроса («web frameworks in rust»).
+/// Короткие и многозначные (`go`, `c`, `r`) — только после предлога: «written in go».
+const LANGUAGES: &[&str] = &[
+    "rust", "python", "javascript", "typescript", "java", "kotlin", "swift", "ruby", "php", "scala",
+    "haskell", "elixir", "erlang", "dart", "lua", "perl", "julia", "clojure", "ocaml", "zig", "nim",
+    "c++", "cpp", "c#", "csharp", "go", "golang", "c", "r", "shell", "bash", "html", "css",
+];
+const AMBIGUOUS_LANGUAGES: &[&str] = &["go", "c", "r"];
+
+/// Слова запроса к GitHub, которые не относятся к теме (кроме общих служебных слов)
+const FILLER_WORDS: &[&str] = &[
+    "github", "repo", "repos", "repository", "repositories", "project", "projects", "written", "using", "that",
+    "are", "top", "best", "popular", "most", "starred", "forked", "recently", "updated", "latest", "sorted", "by",
+    "example", "examples", "good", "cool",
+];
+
+/// Поисковый запрос к GitHub: ключевые слова, квалификаторы, сортировка и страница
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct RepoQuery {
+    pub keywords: String,
+    pub language: Option<String>,
+    pub topic: Option<String>,
+    pub min_stars: Option<u64>,
+    pub pushed_after: Option<NaiveDate>,
+    /// Квалификаторы, которые пользователь написал сам (`user:rust-lang`, `license:mit`)
+    pub extra: Vec<String>,
+    pub sort: RepoSort,
+    pub page: u32,
+    pub per_page: u32,
+}
+
+impl RepoQuery {
+    /// Запрос по ключевым словам с настройками по умолчанию
+    pub fn new(keywords: &str) -> Self {
+        Self { keywords: keywords.to_string(), page: 1, per_page: SEARCH_PAGE_SIZE as u32, ..Default::default() }
+    }
+
+    /// Разбирает запрос на английском: «popular web frameworks in rust with more than 1000 stars»,
+    /// «repos tagged cli updated in the last 6 months», «language:go stars:>500 topic:kubernetes»
+    pub fn parse(input: &str) -> Self {
+        Self::parse_at(input, Utc::now().date_naive())
+    }
+
+    /// То же, что [`RepoQuery::parse`], с явной текущей датой (для «за последние N месяцев»)
+    pub fn parse_at(input: &str, today: NaiveDate) -> Self {
+        let mut query = Self::new("");
+        let mut text = input.to_lowercase();
+
+        // Квалификаторы, написанные явно
+        for qualifier in qualifier_regex().find_iter(&input.to_lowercase()) {
+            let (key, value) = qualifier.as_str().split_once(':').unwrap_or_default();
+            match key {
+                "language" => query.language = Some(value.to_string()),
+                "topic" => query.topic = Some(value.to_string()),
+                "stars" => query.min_stars = value.trim_start_matches(['>', '=']).parse().ok(),
+                "pushed" => query.pushed_after = NaiveDate::parse_from_str(value.trim_start_matches(['>', '=']), "%Y-%m-%d").ok(),
+                _ => query.extra.push(qualifier.as_str().to_string()),
+            }
+        }
+        text = qualifier_regex().replace_all(&text, " ").into_owned();
+
+        if let Some(caps) = stars_regex().captures(&text) {
+            let number: f64 = caps[1].parse().unwrap_or(0.0);
+            let multiplier = if caps.get(2).is_some() { 1000.0 } else { 1.0 };
+            query.min_stars = Some((number * multiplier) as u64);
+        }
+        text = stars_regex().replace_all(&text, " ").into_owned();
+
+        if let Some(caps) = since_regex().captures(&text) {
+            let date = &caps[1];
+            query.pushed_after = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .ok()
+                .or_else(|| date.parse().ok().and_then(|year| NaiveDate::from_ymd_opt(year, 1, 1)));
+        }
+        text = since_regex().replace_all(&text, " ").into_owned();
+
+        if let Some(caps) = last_period_regex().captures(&text) {
+            let count: i64 = caps.get(1).map_or(1, |m| m.as_str().parse().unwrap_or(1));
+            let days = match &caps[2] {
+                "day" => 1,
+                "week" => 7,
+                "month" => 30,
+                _ => 365,
+            };
+            query.pushed_after = Some(today - Duration::days(count * days));
+        }
+        text = last_period_regex().replace_all(&text, " ").into_owned();
+
+        if let Some(caps) = topic_regex().captures(&text) {
+            query.topic = Some(caps[1].to_string());
+        }
+        text = topic_regex().replace_all(&text, " ").into_owned();
+
+        let words: Vec<&str> = text
+            .split(|c: char| c.is_whitespace() || matches!(c, ',' | '?' | '!' | '.' | '"'))
+            .filter(|w| !w.is_empty())
+            .collect();
+        // Целыми словами: «top» не должно находиться в «desktop»
+        let has_any = |phrases: &[&str]| phrases.iter().any(|phrase| contains_phrase(&words, phrase));
+        query.sort = if has_any(&["most forked", "by forks"]) {
+            RepoSort::Forks
+        } else if has_any(&["recently updated", "latest", "by update"]) {
+            RepoSort::Updated
+        } else if has_any(&["popular", "top", "most starred", "best", "by stars"]) {
+            RepoSort::Stars
+        } else {
+            RepoSort::BestMatch
+        };
+
+        let mut keywords = Vec::new();
+        for (i, word) in words.iter().enumerate() {
+            let after_preposition = i > 0 && matches!(words[i - 1], "in" | "written" | "using" | "for");
+            let is_language = LANGUAGES.contains(word) && (after_preposition || !AMBIGUOUS_LANGUAGES.contains(word));
+            if is_language && query.language.is_none() {
+                query.language = Some(word.to_string());
+            } else if !is_filler_word(word, FILLER_WORDS) && !is_language {
+                keywords.push(*word);
+            }
+        }
+        query.keywords = keywords.join(" ");
+        query
+    }
+
+    /// Есть ли в запросе что искать
+    pub fn is_empty(&self) -> bool {
+        self.to_query_string().is_empty()
+    }
+
+    /// Запрос следующей страницы
+    pub fn next_page(&self) -> Self {
+        Self { page: self.page + 1, ..self.clone() }
+    }
+
+    /// Параметр `q` поиска: ключевые слова и квалификаторы
+    pub fn to_query_string(&self) -> String {
+        let mut parts = Vec::new();
+        if !self.keywords.is_empty() {
+            parts.push(self.keywords.clone());
+        }
+        if let Some(language) = &self.language {
+            parts.push(format!("language:{}", github_language(language)));
+        }
+        if let Some(topic) = &self.topic {
+            parts.push(format!("topic:{}", topic));
+        }
+        if let Some(stars) = self.min_stars {
+            parts.push(format!("stars:>{}", stars));
+        }
+        if let Some(date) = self.pushed_after {
+            parts.push(format!("pushed:>{}", date.format("%Y-%m-%d")));
+        }
+        parts.extend(self.extra.iter().cloned());
+        parts.join(" ")
+    }
+
+    /// Путь запроса к API
+    fn path(&self) -> String {
+        let mut url = format!(
+            "/search/repositories?q={}&per_page={}&page={}",
+            urlencoding::encode(&self.to_query_string()),
+            self.per_page,
+            self.page
+        );
+        if let Some(sort) = self.sort.as_param() {
+            url.push_str(&format!("&sort={}&order=desc", sort));
+        }
+        url
+    }
+}
+
+/// Есть ли в словах запроса фраза из одного или нескольких слов
+fn contains_phrase(words: &[&str], phrase: &str) -> bool {
+    let parts: Vec<&str> = phrase.split(' ').collect();
+    words.windows(parts.len()).any(|window| window == parts.as_slice())
+}
+
+/// Похоже ли сообщение на поиск репозиториев: упоминает GitHub или репозитории,
+/// либо ставит условие на число звёзд («more than 1000 stars»)
+pub fn is_repo_search(input: &str) -> bool {
+    static KEYWORDS: OnceLock<KeywordSet> = OnceLock::new();
+    KEYWORDS
+        .get_or_init(|| KeywordSet::new(&["github", "repo", "repos", "repository", "repositories", "репозиторий"]))
+        .matches_fuzzy(input, default_matcher())
+        || stars_regex().is_match(&input.to_lowercase())
+}
+
+/// Название языка для квалификатора `language:`
+fn github_language(language: &str) -> &str {
+    match language {
+        "c++" => "cpp",
+        "c#" => "csharp",
+        "golang" => "go",
+        other => other,
+    }
+}
+
+fn qualifier_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"\b(?:language|topic|stars|forks|pushed|created|user|org|license|archived|in|size):[^\s]+").unwrap()
+    })
+}
+
+/// «more than 1000 stars», «over 1.5k stars», «1k+ stars»
+fn stars_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"(?:(?:with )?(?:more than|over|at least|above|>=?)\s*)?(\d+(?:\.\d+)?)\s*(k)?\+?\s*stars?\b").unwrap()
+    })
+}
+
+/// «updated since 2024», «pushed after 2024-03-01»
+fn since_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?:(?:updated|pushed|active)\s+)?(?:since|after)\s+(\d{4}(?:-\d{2}-\d{2})?)").unwrap())
+}
+
+/// «updated in the last 6 months», «active this year»
+fn last_period_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"(?:(?:updated|pushed|active)\s+)?(?:in\s+the\s+(?:last|past)\s+(\d+)?\s*|this\s+)(day|week|month|year)s?").unwrap()
+    })
+}
+
+/// «tagged cli», «topic machine-learning»
+fn topic_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?:tagged|topic|with topic)\s+([a-z0-9][a-z0-9-]*)").unwrap())
+}
+
+/// Ищет репозитории на GitHub
+pub async fn search_github_repos(query: &RepoQuery) -> Result<RepoSearch, GithubError> {
+    let path = query.path();
+    println!("🔍 GitHub: {}", path);
+
+    let search: SearchResponse = github_client().get_json(&path).await?;
+    Ok(RepoSearch {
+        total_count: search.total_count,
+        repositories: search.items.into_iter().map(Repository::from).collect(),
+    })
+}
+
+/// Количество в коротком виде: `950`, `12.3k`, `1.2M`
+pub fn compact_number(n: u64) -> String {
+    match n {
+        0..=999 => n.to_string(),
+        1_000..=999_999 => format!("{:.1}k", n as f64 / 1_000.0).replace(".0k", "k"),
+        _ => format!("{:.1}M", n as f64 / 1_000_000.0).replace(".0M", "M"),
+    }
+}
+
+/// Карточка репозитория: звёзды, форки, описание, язык, лицензия, последний push и темы
+pub fn format_repository(repo: &Repository) -> String {
+    let mut lines = vec![format!("📂 **{}** ⭐ {} · 🍴 {}", repo.full_name, compact_number(repo.stars), compact_number(repo.forks))];
+    if let Some(description) = &repo.description {
+        lines.push(format!("💡 {}", truncate(description, 200)));
+    }
+    let mut meta = Vec::new();
+    if let Some(language) = &repo.language {
+        meta.push(format!("🔤 {}", language));
+    }
+    if let Some(license) = &repo.license {
+        meta.push(format!("📜 {}", license));
+    }
+    if let Some(pushed) = repo.pushed_at {
+        meta.push(format!("🕒 {}", pushed.format("%Y-%m-%d")));
+    }
+    if !meta.is_empty() {
+        lines.push(meta.join(" · "));
+    }
+    if !repo.topics.is_empty() {
+        lines.push(format!("🏷️ {}", repo.topics.iter().take(5).cloned().collect::<Vec<_>>().join(", ")));
+    }
+    lines.push(format!("🔗 {}", repo.url));
+    lines.join("\n")
+}
+
+/// Список найденных репозиториев с нумерацией от начала выдачи
+pub fn format_repositories(search: &RepoSearch, query: &RepoQuery) -> String {
+    let offset = (query.page.saturating_sub(1) * query.per_page) as usize;
+    let list = search
+        .repositories
+        .iter()
+        .enumerate()
+        .map(|(i, repo)| format!("{}. {}", offset + i + 1, format_repository(repo)))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    format!("🔍 `{}` — {} repositories\n\n{}", query.to_query_string(), search.total_count, list)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, 19).unwrap()
+    }
+
+    #[test]
+    fn test_parse_natural_query() {
+        let query = RepoQuery::parse_at("popular web frameworks in rust with more than 1000 stars", today());
+        assert_eq!(query.keywords, "web frameworks");
+        assert_eq!(query.language.as_deref(), Some("rust"));
+        assert_eq!(query.min_stars, Some(1000));
+        assert_eq!(query.sort, RepoSort::Stars);
+        assert_eq!(query.to_query_string(), "web frameworks language:rust stars:>1000");
+
+        let query = RepoQuery::parse_at("cli tools written in go tagged terminal updated in the last 6 months", today());
+        assert_eq!(query.to_query_string(), "cli tools language:go topic:terminal pushed:>2026-04-22");
+
+        let query = RepoQuery::parse_at("most forked go to definition plugins over 1.5k stars", today());
+        assert_eq!(query.language, None);
+        assert_eq!(query.min_stars, Some(1500));
+        assert_eq!(query.sort, RepoSort::Forks);
+
+        // «top» — только целым словом
+        assert_eq!(RepoQuery::parse_at("desktop apps in rust", today()).sort, RepoSort::BestMatch);
+        assert_eq!(RepoQuery::parse_at("laptop tools", today()).sort, RepoSort::BestMatch);
+        assert_eq!(RepoQuery::parse_at("top laptop tools", today()).sort, RepoSort::Stars);
+    }
+
+    #[test]
+    fn test_is_repo_search() {
+        assert!(is_repo_search("find github repos for web frameworks in rust"));
+        assert!(is_repo_search("popular web frameworks in rust with more than 1000 stars"));
+        assert!(!is_repo_search("how to read a file in rust"));
+    }
+
+    #[test]
+    fn test_parse_explicit_qualifiers() {
+        let query = RepoQuery::parse_at("github parser language:c++ stars:>500 user:rust-lang", today()).next_page();
+        assert_eq!(query.to_query_string(), "parser language:cpp stars:>500 user:rust-lang");
+        assert_eq!(query.page, 2);
+        assert!(query.path().contains("&per_page=5&page=2"));
+        assert!(RepoQuery::parse_at("show me github repos", today()).is_empty());
+    }
+
+    #[test]
+    fn test_format_repository() {
+        let json = r#"{"total_count": 1, "items": [{
+            "name": "tokio", "full_name": "tokio-rs/tokio", "owner": {"login": "tokio-rs"},
+            "description": "A runtime for writing reliable asynchronous applications with Rust.",
+            "html_url": "https://github.com/tokio-rs/tokio", "stargazers_count": 28450, "forks_count": 2600,
+            "license": {"spdx_id": "MIT", "name": "MIT License"}, "language": "Rust",
+            "topics": ["async", "networking"], "pushed_at": "2026-10-18T09:00:00Z"
+        }]}"#;
+        let response: SearchResponse = serde_json::from_str(json).unwrap();
+        let repo = Repository::from(response.items.into_iter().next().unwrap());
+        assert_eq!(repo.owner, "tokio-rs");
+        assert_eq!(
+            format_repository(&repo),
+            "📂 **tokio-rs/tokio** ⭐ 28.4k · 🍴 2.6k\n💡 A runtime for writing reliable asynchronous applications with Rust.\n🔤 Rust · 📜 MIT · 🕒 2026-10-18\n🏷️ async, networking\n🔗 https://github.com/tokio-rs/tokio"
+        );
+        assert_eq!(compact_number(950), "950");
+        assert_eq!(compact_number(1_000), "1k");
+    }
+}
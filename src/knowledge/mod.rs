@@ -1,4 +1,5 @@
 pub mod wikipedia;
 pub mod github;
 pub mod arxiv;
+pub mod citation;
 pub mod web;
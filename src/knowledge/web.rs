@@ -1,36 +1,100 @@
+use chrono::NaiveDate;
+use regex::Regex;
 use reqwest;
-use scraper::{Html, Selector};
+use scraper::{ElementRef, Html, Node, Selector};
+use std::collections::HashMap;
+use std::sync::OnceLock;
 
-/// Загружает HTML-страницу и возвращает её заголовок `<title>`
-pub async fn fetch_page_title(url: &str) -> Result<String, Box<dyn std::error::Error>> {
-    let client = reqwest::Client::new();
-    let response = client
-        .get(url)
-        .header("User-Agent", "Bot-Auraya/1.0")
-        .send()
-        .await?;
+use crate::language::segment::{grapheme_count, truncate};
 
-    if !response.status().is_success() {
-        return Err(format!("HTTP status: {}", response.status()).into());
+/// Теги, которые никогда не относятся к основному тексту
+const SKIPPED_TAGS: &[&str] = &[
+    "script", "style", "noscript", "template", "iframe", "svg", "canvas", "form", "button", "input", "select",
+    "nav", "aside", "footer", "header", "menu", "dialog",
+];
+
+/// Блочные теги: встретив такой, текст разбивается на отдельные блоки
+const BLOCK_TAGS: &[&str] = &[
+    "p", "div", "section", "article", "main", "h1", "h2", "h3", "h4", "h5", "h6", "ul", "ol", "li", "pre",
+    "blockquote", "table", "tr", "figure", "figcaption", "dl", "dt", "dd", "hr", "br",
+];
+
+/// Абзац короче этого не учитывается при поиске основного текста
+const MIN_PARAGRAPH_LEN: usize = 25;
+
+/// Классы и id служебных блоков: меню, баннеры cookie, комментарии, реклама
+fn unlikely_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(
+            r"(?i)banner|breadcrumb|combx|comment|community|consent|cookie|disqus|extra|foot|gdpr|header|legends|menu|modal|related|remark|replies|rss|share|shoutbox|sidebar|skyscraper|social|sponsor|subscribe|newsletter|popup|promo|nav|ad-break|advert|agegate|pagination|pager",
+        )
+        .unwrap()
+    })
+}
+
+/// Классы и id, которые обычно носит основной текст
+fn likely_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?i)article|body|content|entry|hentry|h-entry|main|page|post|text|blog|story").unwrap())
+}
+
+fn selector(css: &str) -> Selector {
+    Selector::parse(css).expect("корректный CSS-селектор")
+}
+
+/// Основное содержимое веб-страницы
+#[derive(Debug, Clone, PartialEq)]
+pub struct WebPage {
+    pub url: String,
+    pub title: String,
+    pub byline: Option<String>,
+    pub published: Option<NaiveDate>,
+    /// Текст статьи без меню и баннеров: блоки через пустую строку,
+    /// заголовки выделены `**`, пункты списков начинаются с `•` или номера
+    pub text: String,
+}
+
+impl WebPage {
+    /// Блоки текста (абзацы, заголовки, списки)
+    pub fn blocks(&self) -> Vec<&str> {
+        self.text.split("\n\n").filter(|block| !block.trim().is_empty()).collect()
     }
 
-    let html = response.text().await?;
-    let document = Html::parse_document(&html);
-    let selector = Selector::parse("title").map_err(|e| e.to_string())?;
+    /// Краткое содержание: заголовок, автор и дата, первые блоки текста (не больше `max_len` символов)
+    pub fn summary(&self, max_len: usize) -> String {
+        let mut result = format!("**{}**", self.title);
+        let meta: Vec<String> = self
+            .byline
+            .iter()
+            .map(|author| format!("✍️ {}", author))
+            .chain(self.published.map(|date| format!("📅 {}", date.format("%Y-%m-%d"))))
+            .collect();
+        if !meta.is_empty() {
+            result.push_str(&format!("\n{}", meta.join(" · ")));
+        }
 
-    if let Some(element) = document.select(&selector).next() {
-        let title = element.text().collect::<Vec<_>>().join(" ").trim().to_string();
-        if title.is_empty() {
-            return Err("Заголовок пустой".into());
+        let mut body = String::new();
+        for block in self.blocks() {
+            if !body.is_empty() && grapheme_count(&body) + grapheme_count(block) > max_len {
+                break;
+            }
+            if !body.is_empty() {
+                body.push_str("\n\n");
+            }
+            body.push_str(block);
+        }
+        if body.is_empty() {
+            result.push_str("\n\nИнформация о содержимом недоступна.");
+        } else {
+            result.push_str(&format!("\n\n{}", truncate(&body, max_len)));
         }
-        Ok(title)
-    } else {
-        Err("Заголовок не найден".into())
+        result
     }
 }
 
-/// Извлекает все абзацы (`<p>`) как список строк
-pub async fn extract_paragraphs(url: &str, max: usize) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+/// Загружает HTML-страницу
+async fn fetch_html(url: &str) -> Result<String, Box<dyn std::error::Error>> {
     let client = reqwest::Client::new();
     let response = client
         .get(url)
@@ -42,17 +106,39 @@ pub async fn extract_paragraphs(url: &str, max: usize) -> Result<Vec<String>, Bo
         return Err(format!("HTTP status: {}", response.status()).into());
     }
 
-    let html = response.text().await?;
-    let document = Html::parse_document(&html);
-    let selector = Selector::parse("p").map_err(|e| e.to_string())?;
+    Ok(response.text().await?)
+}
 
-    let mut paragraphs = vec![];
-    for el in document.select(&selector).take(max) {
-        let text = el.text().collect::<Vec<_>>().join(" ").trim().to_string();
-        if !text.is_empty() && text.len() > 10 {  // Фильтруем слишком короткие абзацы
-            paragraphs.push(text);
-        }
+/// Загружает страницу (один запрос) и извлекает основное содержимое
+pub async fn fetch_page(url: &str) -> Result<WebPage, Box<dyn std::error::Error>> {
+    println!("🌐 Загрузка страницы: {}", url);
+    let html = fetch_html(url).await?;
+    let page = extract_readable(&html, url);
+    if page.title.is_empty() && page.text.is_empty() {
+        return Err("Содержимое страницы не найдено".into());
     }
+    Ok(page)
+}
+
+/// Загружает HTML-страницу и возвращает её заголовок
+pub async fn fetch_page_title(url: &str) -> Result<String, Box<dyn std::error::Error>> {
+    let page = fetch_page(url).await?;
+    if page.title.is_empty() {
+        return Err("Заголовок не найден".into());
+    }
+    Ok(page.title)
+}
+
+/// Извлекает абзацы основного текста страницы как список строк
+pub async fn extract_paragraphs(url: &str, max: usize) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let page = fetch_page(url).await?;
+    let paragraphs: Vec<String> = page
+        .blocks()
+        .into_iter()
+        .filter(|block| grapheme_count(block) > 10) // Фильтруем слишком короткие абзацы
+        .take(max)
+        .map(str::to_string)
+        .collect();
 
     if paragraphs.is_empty() {
         return Err("Абзацы не найдены".into());
@@ -61,18 +147,387 @@ pub async fn extract_paragraphs(url: &str, max: usize) -> Result<Vec<String>, Bo
     Ok(paragraphs)
 }
 
-/// Безопасно извлекает краткое содержимое страницы (заголовок + первые абзацы)
+/// Безопасно извлекает краткое содержимое страницы (заголовок, автор, дата и начало статьи)
 pub async fn extract_page_summary(url: &str) -> Result<String, Box<dyn std::error::Error>> {
-    let title = fetch_page_title(url).await?;
-    
-    match extract_paragraphs(url, 3).await {
-        Ok(paragraphs) => {
-            let summary = paragraphs.join("\n\n");
-            Ok(format!("**{}**\n\n{}", title, summary))
+    let page = fetch_page(url).await?;
+    if page.title.is_empty() {
+        return Err("Заголовок не найден".into());
+    }
+    Ok(page.summary(1000))
+}
+
+/// Извлекает из HTML заголовок, автора, дату публикации и основной текст (в духе Readability):
+/// абзацы начисляют баллы родительским блокам, лучший блок с подходящими соседями — статья
+pub fn extract_readable(html: &str, url: &str) -> WebPage {
+    let document = Html::parse_document(html);
+    let text = main_content(&document).map(render_blocks).unwrap_or_default();
+    WebPage {
+        url: url.to_string(),
+        title: extract_title(&document),
+        byline: extract_byline(&document),
+        published: extract_published(&document),
+        text,
+    }
+}
+
+/// Значение первого найденного `<meta>` из списка селекторов
+fn meta_content(document: &Html, selectors: &[&str]) -> Option<String> {
+    selectors.iter().find_map(|css| {
+        document
+            .select(&selector(css))
+            .find_map(|el| el.value().attr("content").or_else(|| el.value().attr("datetime")))
+            .map(collapse_whitespace)
+            .filter(|value| !value.is_empty())
+    })
+}
+
+fn extract_title(document: &Html) -> String {
+    meta_content(document, &["meta[property='og:title']", "meta[name='twitter:title']"])
+        .or_else(|| document.select(&selector("title")).next().map(element_text))
+        .or_else(|| document.select(&selector("h1")).next().map(element_text))
+        .filter(|title| !title.is_empty())
+        .unwrap_or_default()
+}
+
+fn extract_byline(document: &Html) -> Option<String> {
+    let byline = meta_content(document, &["meta[name='author']", "meta[property='article:author']"])
+        .filter(|author| !author.starts_with("http"))
+        .or_else(|| {
+            document
+                .select(&selector("[rel='author'], [itemprop='author'], .byline, .author"))
+                .map(element_text)
+                .find(|text| !text.is_empty())
+        })?;
+    static BY: OnceLock<Regex> = OnceLock::new();
+    let by = BY.get_or_init(|| Regex::new(r"(?i)^(?:by|автор:?)\s+").unwrap());
+    let byline = by.replace(&byline, "").trim().to_string();
+    // Длинный текст — скорее блок «об авторе», чем подпись
+    (!byline.is_empty() && grapheme_count(&byline) <= 100).then_some(byline)
+}
+
+fn extract_published(document: &Html) -> Option<NaiveDate> {
+    let value = meta_content(
+        document,
+        &[
+            "meta[property='article:published_time']",
+            "meta[itemprop='datePublished']",
+            "meta[name='date']",
+            "meta[name='pubdate']",
+            "meta[name='publish-date']",
+            "[itemprop='datePublished'][datetime]",
+            "time[datetime]",
+        ],
+    )?;
+    NaiveDate::parse_from_str(value.get(..10)?, "%Y-%m-%d").ok()
+}
+
+/// Класс и id элемента одной строкой
+fn class_and_id(el: ElementRef) -> String {
+    format!("{} {}", el.value().attr("class").unwrap_or(""), el.value().attr("id").unwrap_or(""))
+}
+
+/// Служебный блок: меню, подвал, баннер cookie, скрытый элемент
+fn is_boilerplate(el: ElementRef) -> bool {
+    let element = el.value();
+    if SKIPPED_TAGS.contains(&element.name()) || element.attr("hidden").is_some() {
+        return true;
+    }
+    if element.attr("aria-hidden") == Some("true") || element.attr("role").is_some_and(|r| ["navigation", "banner", "dialog", "complementary"].contains(&r)) {
+        return true;
+    }
+    if element.attr("style").is_some_and(|s| s.replace(' ', "").contains("display:none")) {
+        return true;
+    }
+    let names = class_and_id(el);
+    !names.trim().is_empty() && unlikely_regex().is_match(&names) && !likely_regex().is_match(&names) && element.name() != "body"
+}
+
+/// Вес блока по тегу, классу и id
+fn initial_score(el: ElementRef) -> f64 {
+    let tag = match el.value().name() {
+        "article" | "main" => 10.0,
+        "div" | "section" => 5.0,
+        "pre" | "td" | "blockquote" => 3.0,
+        "address" | "ol" | "ul" | "dl" | "dd" | "dt" | "li" | "form" => -3.0,
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "th" => -5.0,
+        _ => 0.0,
+    };
+    let names = class_and_id(el);
+    let mut weight = 0.0;
+    if unlikely_regex().is_match(&names) {
+        weight -= 25.0;
+    }
+    if likely_regex().is_match(&names) {
+        weight += 25.0;
+    }
+    tag + weight
+}
+
+/// Доля текста внутри ссылок: у меню и списков ссылок она близка к единице
+fn link_density(el: ElementRef) -> f64 {
+    let total = grapheme_count(&element_text(el));
+    if total == 0 {
+        return 0.0;
+    }
+    let links: usize = el.select(&selector("a")).map(|a| grapheme_count(&element_text(a))).sum();
+    links as f64 / total as f64
+}
+
+/// Лежит ли элемент внутри служебного блока
+fn inside_boilerplate(el: ElementRef) -> bool {
+    el.ancestors().filter_map(ElementRef::wrap).any(is_boilerplate) || is_boilerplate(el)
+}
+
+/// Блоки, составляющие статью: лучший по баллам и его соседи с текстом
+fn main_content(document: &Html) -> Option<Vec<ElementRef<'_>>> {
+    let mut scores: HashMap<_, (ElementRef, f64)> = HashMap::new();
+    for paragraph in document.select(&selector("p, pre, td")) {
+        let text = element_text(paragraph);
+        let len = grapheme_count(&text);
+        if len < MIN_PARAGRAPH_LEN || inside_boilerplate(paragraph) {
+            continue;
+        }
+        // Балл абзаца: запятые и длина. Родитель получает его целиком, дальние предки — часть.
+        let commas = text.matches([',', '，', '、']).count() as f64;
+        let score = 1.0 + commas + (len as f64 / 100.0).min(3.0);
+        for (level, ancestor) in paragraph.ancestors().filter_map(ElementRef::wrap).take(3).enumerate() {
+            let divider = match level {
+                0 => 1.0,
+                1 => 2.0,
+                _ => level as f64 * 3.0,
+            };
+            let entry = scores.entry(ancestor.id()).or_insert_with(|| (ancestor, initial_score(ancestor)));
+            entry.1 += score / divider;
+        }
+    }
+
+    let final_score = |el: ElementRef, score: f64| score * (1.0 - link_density(el));
+    let (top, top_score) = scores
+        .values()
+        .map(|&(el, score)| (el, final_score(el, score)))
+        .max_by(|a, b| a.1.total_cmp(&b.1))?;
+
+    // Статья бывает разбита на несколько соседних блоков
+    let Some(parent) = top.parent().and_then(ElementRef::wrap) else {
+        return Some(vec![top]);
+    };
+    let threshold = (top_score * 0.2).max(10.0);
+    let blocks = parent
+        .children()
+        .filter_map(ElementRef::wrap)
+        .filter(|&sibling| {
+            if sibling.id() == top.id() {
+                return true;
+            }
+            if is_boilerplate(sibling) {
+                return false;
+            }
+            if scores.get(&sibling.id()).is_some_and(|&(el, score)| final_score(el, score) >= threshold) {
+                return true;
+            }
+            let len = grapheme_count(&element_text(sibling));
+            sibling.value().name() == "p" && len > 80 && link_density(sibling) < 0.25
+        })
+        .collect();
+    Some(blocks)
+}
+
+/// Текст блоков статьи с сохранением заголовков, списков и кода
+fn render_blocks(elements: Vec<ElementRef>) -> String {
+    let mut blocks = Vec::new();
+    for el in elements {
+        render_element(el, &mut blocks);
+    }
+    blocks.retain(|block| !block.trim().is_empty());
+    blocks.join("\n\n")
+}
+
+fn render_element(el: ElementRef, blocks: &mut Vec<String>) {
+    if is_boilerplate(el) {
+        return;
+    }
+    match el.value().name() {
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+            let text = element_text(el);
+            if !text.is_empty() {
+                blocks.push(format!("**{}**", text));
+            }
+        }
+        "ul" | "ol" => {
+            let ordered = el.value().name() == "ol";
+            // Списки ссылок — навигация внутри статьи
+            if link_density(el) > 0.5 {
+                return;
+            }
+            let items: Vec<String> = el
+                .children()
+                .filter_map(ElementRef::wrap)
+                .filter(|li| li.value().name() == "li" && !is_boilerplate(*li))
+                .map(element_text)
+                .filter(|text| !text.is_empty())
+                .enumerate()
+                .map(|(i, text)| if ordered { format!("{}. {}", i + 1, text) } else { format!("• {}", text) })
+                .collect();
+            if !items.is_empty() {
+                blocks.push(items.join("\n"));
+            }
         }
-        Err(_) => {
-            // Если абзацы не найдены, возвращаем хотя бы заголовок
-            Ok(format!("**{}**\n\nИнформация о содержимом недоступна.", title))
+        "pre" => {
+            let code = el.text().collect::<String>();
+            if !code.trim().is_empty() {
+                blocks.push(format!("```\n{}\n```", code.trim_matches('\n')));
+            }
         }
+        "blockquote" => {
+            let text = element_text(el);
+            if !text.is_empty() {
+                blocks.push(format!("> {}", text));
+            }
+        }
+        "table" => {
+            let rows: Vec<String> = el
+                .select(&selector("tr"))
+                .map(|row| row.children().filter_map(ElementRef::wrap).map(element_text).collect::<Vec<_>>().join(" | "))
+                .filter(|row| !row.trim_matches([' ', '|']).is_empty())
+                .collect();
+            if !rows.is_empty() {
+                blocks.push(rows.join("\n"));
+            }
+        }
+        "p" => {
+            let text = element_text(el);
+            if !text.is_empty() {
+                blocks.push(text);
+            }
+        }
+        // Контейнеры: вложенные блоки отдельно, текст между ними — отдельными абзацами
+        _ => {
+            let mut inline = String::new();
+            for child in el.children() {
+                match child.value() {
+                    Node::Text(text) => inline.push_str(text),
+                    Node::Element(element) if BLOCK_TAGS.contains(&element.name()) => {
+                        flush_inline(&mut inline, blocks);
+                        if let Some(child) = ElementRef::wrap(child) {
+                            render_element(child, blocks);
+                        }
+                    }
+                    Node::Element(_) => {
+                        if let Some(child) = ElementRef::wrap(child).filter(|child| !is_boilerplate(*child)) {
+                            inline.push(' ');
+                            inline.push_str(&child.text().collect::<String>());
+                            inline.push(' ');
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            flush_inline(&mut inline, blocks);
+        }
+    }
+}
+
+fn flush_inline(inline: &mut String, blocks: &mut Vec<String>) {
+    let text = collapse_whitespace(inline);
+    if !text.is_empty() {
+        blocks.push(text);
+    }
+    inline.clear();
+}
+
+/// Текст элемента одной строкой (без скриптов и стилей)
+fn element_text(el: ElementRef) -> String {
+    let mut text = String::new();
+    for node in el.descendants() {
+        if let Node::Text(fragment) = node.value() {
+            let hidden = node
+                .ancestors()
+                .filter_map(ElementRef::wrap)
+                .take_while(|ancestor| ancestor.id() != el.id())
+                .any(|ancestor| ["script", "style", "noscript", "template"].contains(&ancestor.value().name()));
+            if !hidden {
+                text.push_str(fragment);
+                text.push(' ');
+            }
+        }
+    }
+    collapse_whitespace(&text)
+}
+
+/// Схлопывает пробелы и переносы строк, убирает пробелы перед знаками препинания
+fn collapse_whitespace(text: &str) -> String {
+    static PUNCT: OnceLock<Regex> = OnceLock::new();
+    let punct = PUNCT.get_or_init(|| Regex::new(r"\s+([,.;:!?)»])").unwrap());
+    let collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    punct.replace_all(&collapsed, "$1").into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ARTICLE: &str = r#"<!DOCTYPE html>
+<html><head>
+  <title>Ferris explores the sea | Crab News</title>
+  <meta property="og:title" content="Ferris explores the sea">
+  <meta name="author" content="By Jane Doe">
+  <meta property="article:published_time" content="2024-05-17T09:30:00Z">
+  <script>var tracking = "do not show";</script>
+</head><body>
+  <div id="cookie-banner"><p>We use cookies to improve your experience, please accept all cookies.</p></div>
+  <nav class="menu"><ul><li><a href="/">Home</a></li><li><a href="/news">News</a></li></ul></nav>
+  <div class="layout">
+    <div class="article-body">
+      <p>Ferris the crab set out on a long journey across the sea, carrying nothing but a map.</p>
+      <h2>The first day</h2>
+      <p>On the first day, the waves were calm, the sky was clear, and the wind was gentle.</p>
+      <ul><li>A compass</li><li>Some <b>dried</b> seaweed</li></ul>
+      <p>By evening, Ferris reached a small island, tired but happy, and decided to rest.</p>
+    </div>
+    <aside class="sidebar"><p>Related: ten facts about crabs you did not know, with pictures.</p></aside>
+  </div>
+  <div class="comments"><p>Great story, I loved it, thanks for writing this article about crabs!</p></div>
+  <footer><p>Copyright Crab News, all rights reserved, since the beginning of time.</p></footer>
+</body></html>"#;
+
+    #[test]
+    fn test_extract_readable_metadata() {
+        let page = extract_readable(ARTICLE, "https://example.com/ferris");
+        assert_eq!(page.title, "Ferris explores the sea");
+        assert_eq!(page.byline.as_deref(), Some("Jane Doe"));
+        assert_eq!(page.published, NaiveDate::from_ymd_opt(2024, 5, 17));
+    }
+
+    #[test]
+    fn test_extract_readable_main_text() {
+        let page = extract_readable(ARTICLE, "https://example.com/ferris");
+        assert_eq!(
+            page.blocks(),
+            vec![
+                "Ferris the crab set out on a long journey across the sea, carrying nothing but a map.",
+                "**The first day**",
+                "On the first day, the waves were calm, the sky was clear, and the wind was gentle.",
+                "• A compass\n• Some dried seaweed",
+                "By evening, Ferris reached a small island, tired but happy, and decided to rest.",
+            ]
+        );
+        for boilerplate in ["cookies", "Home", "Related", "Great story", "Copyright", "tracking"] {
+            assert!(!page.text.contains(boilerplate), "{}", boilerplate);
+        }
+    }
+
+    #[test]
+    fn test_summary_and_fallbacks() {
+        let page = extract_readable(ARTICLE, "https://example.com/ferris");
+        let summary = page.summary(200);
+        assert!(summary.starts_with("**Ferris explores the sea**\n✍️ Jane Doe · 📅 2024-05-17\n\nFerris the crab"));
+        assert!(!summary.contains("By evening"));
+
+        // Без метаданных: заголовок из <title>, текст из единственного блока
+        let page = extract_readable("<html><head><title>Plain</title></head><body><div><p>Just one paragraph of text, long enough to count.</p></div></body></html>", "u");
+        assert_eq!(page.title, "Plain");
+        assert_eq!((page.byline.as_deref(), page.published), (None, None));
+        assert_eq!(page.text, "Just one paragraph of text, long enough to count.");
+        assert!(page.summary(100).ends_with("long enough to count."));
     }
 }
@@ -1,84 +1,418 @@
 use reqwest::Client;
 use serde::Deserialize;
 
+use crate::core::dialog::is_more_request;
+use crate::language::{
+    code::Language,
+    normalize::{contains_cyrillic, normalize_reply},
+    segment::truncate,
+    translit::{capitalize_words, to_latin, to_nominative, Scheme},
+};
+
 #[derive(Debug, Deserialize)]
 struct WikipediaSummary {
     title: String,
     extract: String,
     #[serde(default)]
     pageid: Option<u64>,
-    #[serde(default)] 
+    #[serde(default, rename = "type")]
     type_field: Option<String>,
 }
 
-/// Получает краткое описание из Wikipedia
-pub async fn fetch_wikipedia_summary(query: &str) -> Result<String, String> {
-    // Очищаем запрос от лишних слов
-    let clean_query = clean_wikipedia_query(query);
-    
-    // Пробуем сначала REST API
-    match fetch_from_rest_api(&clean_query).await {
-        Ok(result) => Ok(result),
-        Err(e) => {
+/// Статья Википедии: заголовок, вступление и язык раздела, из которого она взята
+#[derive(Debug, Clone, PartialEq)]
+pub struct WikiArticle {
+    pub title: String,
+    pub extract: String,
+    pub language: Language,
+}
+
+impl WikiArticle {
+    /// Ответ пользователю: заголовок и вступление
+    pub fn format(&self) -> String {
+        format!("📖 **{}**\n\n{}", self.title, self.extract)
+    }
+
+    /// Заголовок статьи из ответа, собранного [`WikiArticle::format`] (например, взятого из кэша)
+    pub fn title_of(formatted: &str) -> Option<&str> {
+        formatted.strip_prefix("📖 **")?.split_once("**").map(|(title, _)| title)
+    }
+}
+
+/// Раздел статьи (вступление в разделы не входит — оно показывается в первом ответе)
+#[derive(Debug, Clone, PartialEq)]
+pub struct WikiSection {
+    pub title: String,
+    /// Уровень вложенности: 1 для `== История ==`, 2 для `=== Ранние годы ===`
+    pub level: usize,
+    pub text: String,
+}
+
+/// Максимальная длина раздела в одном ответе (лимит сообщения Telegram — 4096 символов)
+const MAX_SECTION_GRAPHEMES: usize = 3000;
+
+/// Просьбы показать оглавление
+const CONTENTS_REQUESTS: &[&str] = &["contents", "table of contents", "toc", "содержание", "оглавление"];
+
+/// Что пользователь хочет прочитать в текущей статье
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReadingRequest {
+    Next,
+    Contents,
+    Section(usize),
+}
+
+/// Статья, которую пользователь читает по разделам. Разделы загружаются при первом
+/// обращении: большинству хватает вступления.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WikiReading {
+    pub title: String,
+    pub language: Language,
+    pub sections: Option<Vec<WikiSection>>,
+    /// Индекс следующего раздела для «дальше»
+    pub position: usize,
+}
+
+impl WikiReading {
+    pub fn new(article: &WikiArticle) -> Self {
+        Self { title: article.title.clone(), language: article.language, sections: None, position: 0 }
+    }
+
+    /// Распознаёт просьбу о продолжении: «дальше», оглавление или (когда разделы уже
+    /// загружены) название или номер раздела
+    pub fn parse_request(&self, input: &str) -> Option<ReadingRequest> {
+        if is_more_request(input) {
+            return Some(ReadingRequest::Next);
+        }
+        let input = normalize_reply(input);
+        if CONTENTS_REQUESTS.contains(&input.as_str()) {
+            return Some(ReadingRequest::Contents);
+        }
+
+        let sections = self.sections.as_ref()?;
+        if let Ok(n) = input.parse::<usize>() {
+            return (1..=sections.len()).contains(&n).then(|| ReadingRequest::Section(n - 1));
+        }
+        sections
+            .iter()
+            .position(|section| section.title.to_lowercase() == input)
+            .map(ReadingRequest::Section)
+    }
+
+    /// Следующий непрочитанный раздел
+    pub fn next_section(&mut self) -> Option<&WikiSection> {
+        let index = self.position;
+        self.select(index)
+    }
+
+    /// Раздел `index`; «дальше» после него продолжит со следующего
+    pub fn select(&mut self, index: usize) -> Option<&WikiSection> {
+        let section = self.sections.as_ref()?.get(index)?;
+        self.position = index + 1;
+        Some(section)
+    }
+
+    /// Ответ с текстом раздела
+    pub fn format_section(&self, section: &WikiSection) -> String {
+        format!("📖 **{} — {}**\n\n{}", self.title, section.title, truncate(&section.text, MAX_SECTION_GRAPHEMES))
+    }
+
+    /// Оглавление: пронумерованные разделы с отступами по уровню вложенности
+    pub fn table_of_contents(&self) -> String {
+        let items = self
+            .sections
+            .iter()
+            .flatten()
+            .enumerate()
+            .map(|(i, section)| format!("{}{}. {}", "   ".repeat(section.level.saturating_sub(1)), i + 1, section.title))
+            .collect::<Vec<_>>();
+        format!("📑 **{}**\n\n{}", self.title, items.join("\n"))
+    }
+}
+
+/// Делит полный текст статьи (`explaintext` с `exsectionformat=wiki`) на разделы.
+/// Вступление и разделы без собственного текста («См. также», «Примечания») пропускаются.
+fn parse_sections(extract: &str) -> Vec<WikiSection> {
+    let mut sections = Vec::new();
+    let mut current: Option<WikiSection> = None;
+
+    for line in extract.lines() {
+        let trimmed = line.trim();
+        let level = trimmed.chars().take_while(|&c| c == '=').count();
+        let is_heading = level >= 2 && trimmed.len() > 2 * level && trimmed.ends_with(&"=".repeat(level));
+        if is_heading {
+            sections.extend(current.take());
+            current = Some(WikiSection {
+                title: trimmed[level..trimmed.len() - level].trim().to_string(),
+                level: level - 1,
+                text: String::new(),
+            });
+        } else if let Some(section) = current.as_mut() {
+            section.text.push_str(line);
+            section.text.push('\n');
+        }
+    }
+    sections.extend(current);
+
+    for section in &mut sections {
+        section.text = section.text.trim().to_string();
+    }
+    sections.retain(|section| !section.text.is_empty());
+    sections
+}
+
+/// Сколько вариантов предлагать со страницы значений
+const DISAMBIGUATION_OPTIONS: usize = 8;
+
+/// Пометки в названиях страниц значений разных разделов
+const DISAMBIGUATION_MARKERS: &[&str] = &["(disambiguation)", "(значения)", "(begriffsklärung)", "(homonymie)", "(desambiguación)"];
+
+/// Страница значений: запросу соответствует несколько статей, пользователь выбирает одну
+#[derive(Debug, Clone, PartialEq)]
+pub struct Disambiguation {
+    pub title: String,
+    pub options: Vec<String>,
+    pub language: Language,
+}
+
+impl Disambiguation {
+    /// Нумерованный список вариантов
+    pub fn format(&self) -> String {
+        self.options
+            .iter()
+            .enumerate()
+            .map(|(i, option)| format!("{}. {}", i + 1, option))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Индекс варианта, выбранного ответом пользователя: номер («2», «2.», «2)»)
+    /// или название варианта целиком
+    pub fn choice_index(&self, reply: &str) -> Option<usize> {
+        let reply = reply.trim();
+        let number = reply.trim_end_matches(['.', ')']).trim();
+        if let Ok(n) = number.parse::<usize>() {
+            return (1..=self.options.len()).contains(&n).then(|| n - 1);
+        }
+        let reply = reply.to_lowercase();
+        self.options.iter().position(|option| option.to_lowercase() == reply)
+    }
+}
+
+/// Результат поиска справки: статья или страница значений
+#[derive(Debug, Clone, PartialEq)]
+pub enum WikiLookup {
+    Article(WikiArticle),
+    Ambiguous(Disambiguation),
+}
+
+/// Адрес статьи `title` в разделе `lang`
+pub fn article_url(lang: Language, title: &str) -> String {
+    format!("https://{}.wikipedia.org/wiki/{}", edition(lang), title.replace(' ', "_"))
+}
+
+/// Поддомен языкового раздела Википедии (`ru`, `de`; норвежский букмол — `no`)
+fn edition(lang: Language) -> &'static str {
+    match lang.iso639_1() {
+        "nb" => "no",
+        code => code,
+    }
+}
+
+/// Получает краткое описание из Википедии на языке `lang`: сначала ищет `query` в разделе
+/// этого языка, затем переходит по межъязыковой ссылке из английской статьи для `query_en`,
+/// и только в крайнем случае возвращает английскую статью (её язык — в [`WikiArticle::language`]).
+/// Если запрос ведёт на страницу значений, возвращает варианты для выбора.
+pub async fn fetch_wikipedia_summary(query: &str, lang: Language, query_en: &str) -> Result<WikiLookup, String> {
+    let english_title = clean_wikipedia_query(query_en, Language::ENGLISH);
+    if lang == Language::ENGLISH {
+        return fetch_summary(Language::ENGLISH, &english_title).await;
+    }
+
+    let local_title = clean_wikipedia_query(query, lang);
+    match fetch_summary(lang, &local_title).await {
+        Ok(lookup) => return Ok(lookup),
+        Err(e) => println!("⚠️ Статья «{}» не найдена в разделе {}: {}", local_title, edition(lang), e),
+    }
+
+    // Статьи под таким названием нет — ищем её через межъязыковую ссылку английской статьи
+    match find_interlanguage_title(&english_title, lang).await {
+        Ok(Some(title)) => match fetch_summary(lang, &title).await {
+            Ok(lookup) => return Ok(lookup),
+            Err(e) => println!("⚠️ Статья по межъязыковой ссылке «{}» недоступна: {}", title, e),
+        },
+        Ok(None) => println!("⚠️ У статьи «{}» нет версии для раздела {}", english_title, edition(lang)),
+        Err(e) => println!("⚠️ Не удалось получить межъязыковые ссылки: {}", e),
+    }
+
+    fetch_summary(Language::ENGLISH, &english_title).await
+}
+
+/// Статья с точным названием `title` из раздела `lang` (например, вариант со страницы значений)
+pub async fn fetch_wikipedia_page(lang: Language, title: &str) -> Result<WikiLookup, String> {
+    fetch_summary(lang, title).await
+}
+
+/// Почему REST API не вернул справку
+enum RestFailure {
+    /// Запрос не удался — можно попробовать Action API
+    Unavailable(String),
+    /// Это страница значений, но варианты прочитать не удалось. Action API отдал бы
+    /// её текст как статью, поэтому к нему не обращаемся.
+    Disambiguation(String),
+}
+
+/// Статья из раздела `lang`: сначала REST API, затем Action API
+async fn fetch_summary(lang: Language, title: &str) -> Result<WikiLookup, String> {
+    match fetch_from_rest_api(lang, title).await {
+        Ok(lookup) => Ok(lookup),
+        Err(RestFailure::Disambiguation(e)) => Err(e),
+        Err(RestFailure::Unavailable(e)) => {
             println!("⚠️ REST API не сработал: {}, пробуем Action API", e);
-            fetch_from_action_api(&clean_query).await
+            fetch_from_action_api(lang, title).await.map(WikiLookup::Article)
         }
     }
 }
 
+fn wikipedia_client() -> Result<Client, String> {
+    Client::builder()
+        .timeout(std::time::Duration::from_secs(10))
+        .user_agent("Auraya-Bot/1.0 (https://github.com/auraya-bot)")
+        .build()
+        .map_err(|e| format!("HTTP client error: {}", e))
+}
+
 /// Получает данные через REST API Wikipedia
-async fn fetch_from_rest_api(query: &str) -> Result<String, String> {
+async fn fetch_from_rest_api(lang: Language, query: &str) -> Result<WikiLookup, RestFailure> {
     let url = format!(
-        "https://en.wikipedia.org/api/rest_v1/page/summary/{}",
+        "https://{}.wikipedia.org/api/rest_v1/page/summary/{}",
+        edition(lang),
         urlencoding::encode(query)
     );
-    
+
     println!("🔍 Wikipedia REST URL: {}", url);
-    
-    let client = Client::builder()
-        .timeout(std::time::Duration::from_secs(10))
-        .user_agent("Auraya-Bot/1.0 (https://github.com/auraya-bot)")
-        .build()
-        .map_err(|e| format!("HTTP client error: {}", e))?;
+
+    let client = wikipedia_client().map_err(RestFailure::Unavailable)?;
 
     let response = client
         .get(&url)
         .send()
         .await
-        .map_err(|e| format!("Wikipedia request error: {}", e))?;
+        .map_err(|e| RestFailure::Unavailable(format!("Wikipedia request error: {}", e)))?;
 
     if !response.status().is_success() {
         let status = response.status();
         let error_text = response.text().await.unwrap_or_default();
-        return Err(format!("Wikipedia REST API status {}: {}", status, error_text));
+        return Err(RestFailure::Unavailable(format!("Wikipedia REST API status {}: {}", status, error_text)));
     }
 
     let summary: WikipediaSummary = response
         .json()
         .await
-        .map_err(|e| format!("JSON parsing error: {}", e))?;
+        .map_err(|e| RestFailure::Unavailable(format!("JSON parsing error: {}", e)))?;
+
+    if summary.type_field.as_deref() == Some("disambiguation") || summary.extract.contains("may refer to:") {
+        println!("🔀 «{}» — страница значений, читаем варианты", summary.title);
+        let entries = fetch_disambiguation_entries(lang, &summary.title)
+            .await
+            .map_err(RestFailure::Disambiguation)?;
+        let options = disambiguation_options(&summary.title, entries);
+        if options.is_empty() {
+            return Err(RestFailure::Disambiguation("Disambiguation page without options".to_string()));
+        }
+        return Ok(WikiLookup::Ambiguous(Disambiguation { title: summary.title, options, language: lang }));
+    }
+    if summary.extract.is_empty() {
+        return Err(RestFailure::Unavailable("Empty summary".to_string()));
+    }
+
+    Ok(WikiLookup::Article(WikiArticle { title: summary.title, extract: summary.extract, language: lang }))
+}
+
+/// Варианты для страницы значений `title`: без самой страницы, повторов
+/// и других страниц значений
+fn disambiguation_options(title: &str, entries: Vec<String>) -> Vec<String> {
+    let title = title.to_lowercase();
+    let mut options: Vec<String> = Vec::new();
+    for entry in entries {
+        let lower = entry.to_lowercase();
+        let duplicate = options.iter().any(|option| option.to_lowercase() == lower);
+        if lower != title && !duplicate && !DISAMBIGUATION_MARKERS.iter().any(|marker| lower.contains(marker)) {
+            options.push(entry);
+        }
+    }
+    options.truncate(DISAMBIGUATION_OPTIONS);
+    options
+}
+
+#[derive(Debug, Deserialize)]
+struct ParseResponse {
+    parse: Option<ParsedPage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ParsedPage {
+    #[serde(default)]
+    wikitext: String,
+}
+
+/// Статьи, перечисленные на странице значений `title`, в порядке страницы
+async fn fetch_disambiguation_entries(lang: Language, title: &str) -> Result<Vec<String>, String> {
+    let url = format!(
+        "https://{}.wikipedia.org/w/api.php?action=parse&format=json&formatversion=2&redirects=1&prop=wikitext&page={}",
+        edition(lang),
+        urlencoding::encode(title)
+    );
+
+    println!("🔍 Wikipedia disambiguation URL: {}", url);
+
+    let response = wikipedia_client()?
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Wikipedia request error: {}", e))?;
 
-    if summary.extract.is_empty() || summary.extract.contains("may refer to:") {
-        return Err("Disambiguation page or empty summary".to_string());
+    if !response.status().is_success() {
+        return Err(format!("Wikipedia Action API returned status: {}", response.status()));
     }
 
-    Ok(format!("📖 **{}**\n\n{}", summary.title, summary.extract))
+    let parsed: ParseResponse = response
+        .json()
+        .await
+        .map_err(|e| format!("JSON parsing error: {}", e))?;
+
+    let page = parsed.parse.ok_or("No disambiguation page text available")?;
+    Ok(disambiguation_entries(&page.wikitext))
+}
+
+/// Первая ссылка каждого пункта списка на странице значений
+/// (`* [[Freddie Mercury]], British singer` → `Freddie Mercury`). Ссылки на другие
+/// пространства имён и проекты (`[[wikt:mercury]]`, `[[File:…]]`) пропускаются.
+fn disambiguation_entries(wikitext: &str) -> Vec<String> {
+    wikitext
+        .lines()
+        .filter(|line| line.trim_start().starts_with('*'))
+        .filter_map(|line| {
+            let start = line.find("[[")? + 2;
+            let end = start + line[start..].find("]]")?;
+            let target = line[start..end].split('|').next()?.split('#').next()?.trim();
+            let namespaced = target.split_once(':').is_some_and(|(prefix, _)| !prefix.contains(' '));
+            (!target.is_empty() && !namespaced).then(|| target.replace('_', " "))
+        })
+        .collect()
 }
 
 /// Получает данные через Action API Wikipedia (запасной вариант)
-async fn fetch_from_action_api(query: &str) -> Result<String, String> {
+async fn fetch_from_action_api(lang: Language, query: &str) -> Result<WikiArticle, String> {
     let url = format!(
-        "https://en.wikipedia.org/w/api.php?action=query&format=json&titles={}&prop=extracts&exintro&explaintext&exsectionformat=plain",
+        "https://{}.wikipedia.org/w/api.php?action=query&format=json&redirects=1&titles={}&prop=extracts%7Cpageprops&ppprop=disambiguation&exintro&explaintext&exsectionformat=plain",
+        edition(lang),
         urlencoding::encode(query)
     );
     
     println!("🔍 Wikipedia Action URL: {}", url);
     
-    let client = Client::builder()
-        .timeout(std::time::Duration::from_secs(10))
-        .user_agent("Auraya-Bot/1.0 (https://github.com/auraya-bot)")
-        .build()
-        .map_err(|e| format!("HTTP client error: {}", e))?;
+    let client = wikipedia_client()?;
 
     let response = client
         .get(&url)
@@ -97,10 +431,13 @@ async fn fetch_from_action_api(query: &str) -> Result<String, String> {
 
     if let Some(query) = wiki_response.query {
         for (_, page) in query.pages {
+            if page.pageprops.disambiguation.is_some() {
+                continue;
+            }
             if let Some(extract) = page.extract {
                 if !extract.is_empty() && !extract.contains("may refer to:") {
                     let title = page.title.unwrap_or_else(|| "Wikipedia".to_string());
-                    return Ok(format!("📖 **{}**\n\n{}", title, extract));
+                    return Ok(WikiArticle { title, extract, language: lang });
                 }
             }
         }
@@ -109,6 +446,108 @@ async fn fetch_from_action_api(query: &str) -> Result<String, String> {
     Err("No summary available".to_string())
 }
 
+#[derive(Debug, Deserialize)]
+struct FullTextResponse {
+    query: Option<FullTextQuery>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FullTextQuery {
+    #[serde(default)]
+    pages: Vec<FullTextPage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FullTextPage {
+    #[serde(default)]
+    extract: Option<String>,
+}
+
+/// Разделы статьи `title` из раздела Википедии `lang`
+pub async fn fetch_wikipedia_sections(lang: Language, title: &str) -> Result<Vec<WikiSection>, String> {
+    let url = format!(
+        "https://{}.wikipedia.org/w/api.php?action=query&format=json&formatversion=2&redirects=1&prop=extracts&explaintext=1&exsectionformat=wiki&titles={}",
+        edition(lang),
+        urlencoding::encode(title)
+    );
+
+    println!("🔍 Wikipedia sections URL: {}", url);
+
+    let response = wikipedia_client()?
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Wikipedia request error: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Wikipedia Action API returned status: {}", response.status()));
+    }
+
+    let full_text: FullTextResponse = response
+        .json()
+        .await
+        .map_err(|e| format!("JSON parsing error: {}", e))?;
+
+    let extract = full_text
+        .query
+        .and_then(|query| query.pages.into_iter().find_map(|page| page.extract))
+        .ok_or("No article text available")?;
+    Ok(parse_sections(&extract))
+}
+
+#[derive(Debug, Deserialize)]
+struct LangLinksResponse {
+    query: Option<LangLinksQuery>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LangLinksQuery {
+    #[serde(default)]
+    pages: Vec<LangLinksPage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LangLinksPage {
+    #[serde(default)]
+    langlinks: Vec<LangLink>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LangLink {
+    title: String,
+}
+
+/// Название статьи в разделе `lang` по межъязыковой ссылке английской статьи
+async fn find_interlanguage_title(english_title: &str, lang: Language) -> Result<Option<String>, String> {
+    let url = format!(
+        "https://en.wikipedia.org/w/api.php?action=query&format=json&formatversion=2&redirects=1&prop=langlinks&lllang={}&titles={}",
+        edition(lang),
+        urlencoding::encode(english_title)
+    );
+
+    println!("🔍 Wikipedia langlinks URL: {}", url);
+
+    let response = wikipedia_client()?
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Wikipedia request error: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Wikipedia Action API returned status: {}", response.status()));
+    }
+
+    let links: LangLinksResponse = response
+        .json()
+        .await
+        .map_err(|e| format!("JSON parsing error: {}", e))?;
+
+    Ok(links
+        .query
+        .and_then(|query| query.pages.into_iter().flat_map(|page| page.langlinks).next())
+        .map(|link| link.title))
+}
+
 #[derive(Debug, Deserialize)]
 struct WikipediaResponse {
     query: Option<WikipediaQuery>,
@@ -123,33 +562,49 @@ struct WikipediaQuery {
 struct WikipediaPage {
     extract: Option<String>,
     title: Option<String>,
+    #[serde(default)]
+    pageprops: PageProps,
 }
 
-/// Очищает запрос для Wikipedia
-fn clean_wikipedia_query(query: &str) -> String {
-    let query_lower = query.to_lowercase();
+/// Свойства страницы; `disambiguation` есть только у страниц значений
+#[derive(Debug, Default, Deserialize)]
+struct PageProps {
+    disambiguation: Option<serde_json::Value>,
+}
+
+/// Вопросительные обороты, которые не входят в название статьи
+const QUERY_PREFIXES: &[&str] = &[
+    "what is ", "who is ", "tell me about ", "explain ",
+    "что такое ", "кто такой ", "кто такая ", "расскажи о ", "расскажи про ", "объясни ",
+    "was ist ", "wer ist ", "qu'est-ce que ", "qui est ", "qué es ", "quién es ",
+];
+
+/// Очищает запрос для раздела Википедии на языке `lang`
+fn clean_wikipedia_query(query: &str, lang: Language) -> String {
+    let mut cleaned = query.to_lowercase();
     
     // Убираем лишние слова
-    let cleaned = query_lower
-        .replace("what is ", "")
-        .replace("что такое ", "") 
-        .replace("tell me about ", "")
-        .replace("расскажи о ", "")
-        .replace("explain ", "")
-        .replace("объясни ", "")
-        .replace("?", "")
-        .replace("!", "")
-        .trim()
-        .to_string();
+    for prefix in QUERY_PREFIXES {
+        cleaned = cleaned.replace(prefix, "");
+    }
+    let cleaned = cleaned.replace(['?', '!'], "").trim().to_string();
     
     // Специальные случаи для улучшения поиска
-    let result = match cleaned.as_str() {
-        "ai" | "ии" => "artificial_intelligence".to_string(),
-        "ml" => "machine_learning".to_string(),
-        "js" => "javascript".to_string(),
-        "css" => "cascading_style_sheets".to_string(),
-        "html" => "html".to_string(),
-        "cpp" | "c++" => "c++".to_string(),
+    let result = match (cleaned.as_str(), lang.iso639_1()) {
+        ("ai" | "ии", "en") => "artificial_intelligence".to_string(),
+        ("ии", "ru") => "искусственный_интеллект".to_string(),
+        ("ml", "en") => "machine_learning".to_string(),
+        ("js", _) => "javascript".to_string(),
+        ("css", "en") => "cascading_style_sheets".to_string(),
+        ("cpp" | "c++", _) => "c++".to_string(),
+        // Непереведённое русское имя в английском разделе: «расскажи о москве» → Moskva
+        // (английская Википедия знает транслитерации как перенаправления)
+        _ if lang == Language::ENGLISH && contains_cyrillic(&cleaned) => {
+            let nominative = capitalize_words(&to_nominative(&cleaned, &[]));
+            to_latin(&nominative, Scheme::Bgn).replace(" ", "_")
+        }
+        // В русском разделе название нужно в именительном падеже: «о москве» → «москва»
+        _ if lang == Language::RUSSIAN => to_nominative(&cleaned, &[]).replace(" ", "_"),
         _ => cleaned.replace(" ", "_")
     };
     
@@ -157,23 +612,18 @@ fn clean_wikipedia_query(query: &str) -> String {
     result
 }
 
-/// Пытается найти похожие статьи, если точного совпадения нет
-pub async fn search_wikipedia_articles(query: &str, limit: usize) -> Result<Vec<String>, String> {
+/// Ищет статьи раздела `lang`, названия которых начинаются с `query`
+pub async fn search_wikipedia_articles(query: &str, lang: Language, limit: usize) -> Result<Vec<String>, String> {
     let url = format!(
-        "https://en.wikipedia.org/w/api.php?action=opensearch&search={}&limit={}&format=json",
+        "https://{}.wikipedia.org/w/api.php?action=opensearch&search={}&limit={}&namespace=0&format=json",
+        edition(lang),
         urlencoding::encode(query),
         limit
     );
     
     println!("🔍 Wikipedia Search URL: {}", url);
     
-    let client = Client::builder()
-        .timeout(std::time::Duration::from_secs(10))
-        .user_agent("Auraya-Bot/1.0")
-        .build()
-        .map_err(|e| format!("HTTP client error: {}", e))?;
-
-    let response = client
+    let response = wikipedia_client()?
         .get(&url)
         .send()
         .await
@@ -207,16 +657,113 @@ mod tests {
 
     #[test]
     fn test_clean_query() {
-        assert_eq!(clean_wikipedia_query("What is Rust?"), "rust");
-        assert_eq!(clean_wikipedia_query("что такое Python"), "python");
-        assert_eq!(clean_wikipedia_query("artificial intelligence"), "artificial_intelligence");
-        assert_eq!(clean_wikipedia_query("What is AI?"), "artificial_intelligence");
-        assert_eq!(clean_wikipedia_query("C++"), "c++");
+        let en = Language::ENGLISH;
+        assert_eq!(clean_wikipedia_query("What is Rust?", en), "rust");
+        assert_eq!(clean_wikipedia_query("что такое Python", en), "python");
+        assert_eq!(clean_wikipedia_query("artificial intelligence", en), "artificial_intelligence");
+        assert_eq!(clean_wikipedia_query("What is AI?", en), "artificial_intelligence");
+        assert_eq!(clean_wikipedia_query("C++", en), "c++");
+        assert_eq!(clean_wikipedia_query("расскажи о москве", en), "Moskva");
+        assert_eq!(clean_wikipedia_query("Лев Толстой", en), "Lev_Tolstoy");
+    }
+
+    #[test]
+    fn test_clean_query_native() {
+        let ru = Language::RUSSIAN;
+        assert_eq!(clean_wikipedia_query("Расскажи о Москве", ru), "москва");
+        assert_eq!(clean_wikipedia_query("Что такое ИИ?", ru), "искусственный_интеллект");
+        assert_eq!(clean_wikipedia_query("Was ist Quantenmechanik?", Language::parse("de").unwrap()), "quantenmechanik");
+    }
+
+    #[test]
+    fn test_edition() {
+        assert_eq!(edition(Language::RUSSIAN), "ru");
+        assert_eq!(edition(Language::parse("nb").unwrap()), "no");
+    }
+
+    #[test]
+    fn test_langlinks_parsing() {
+        let json = r#"{"query": {"pages": [{"pageid": 1, "title": "Moscow", "langlinks": [{"lang": "ru", "title": "Москва"}]}]}}"#;
+        let links: LangLinksResponse = serde_json::from_str(json).unwrap();
+        let title = links.query.unwrap().pages.into_iter().flat_map(|p| p.langlinks).next().unwrap().title;
+        assert_eq!(title, "Москва");
+    }
+
+    #[test]
+    fn test_disambiguation_options() {
+        let entries = vec!["Mercury", "Mercury (planet)", "Mercury (element)", "Mercury (disambiguation)", "Mercury (planet)"];
+        let options = disambiguation_options("Mercury", entries.into_iter().map(String::from).collect());
+        assert_eq!(options, vec!["Mercury (planet)", "Mercury (element)"]);
+    }
+
+    #[test]
+    fn test_disambiguation_entries() {
+        let wikitext = "'''Mercury''' may refer to:\n\
+            == Astronomy ==\n\
+            * [[Mercury (planet)]], the closest planet to the Sun\n\
+            * [[Mercury (element)|mercury]], a chemical element\n\
+            == People ==\n\
+            * [[Freddie Mercury]] (1946–1991), British singer\n\
+            ** [[Queen (band)#Members|Queen]]\n\
+            * Roman god, see [[Mercury (mythology)]]\n\
+            == See also ==\n\
+            * [[wikt:mercury|mercury]] in Wiktionary\n\
+            * [[File:Mercury.png]]\n\
+            Compare [[Hermes]].";
+        assert_eq!(
+            disambiguation_entries(wikitext),
+            vec!["Mercury (planet)", "Mercury (element)", "Freddie Mercury", "Queen (band)", "Mercury (mythology)"]
+        );
+    }
+
+    #[test]
+    fn test_choice_index() {
+        let disambiguation = Disambiguation {
+            title: "Меркурий".to_string(),
+            options: vec!["Меркурий (планета)".to_string(), "Меркурий (мифология)".to_string()],
+            language: Language::RUSSIAN,
+        };
+        assert_eq!(disambiguation.format(), "1. Меркурий (планета)\n2. Меркурий (мифология)");
+        assert_eq!(disambiguation.choice_index("2"), Some(1));
+        assert_eq!(disambiguation.choice_index(" 1. "), Some(0));
+        assert_eq!(disambiguation.choice_index("3"), None);
+        assert_eq!(disambiguation.choice_index("меркурий (мифология)"), Some(1));
+        assert_eq!(disambiguation.choice_index("что такое Марс"), None);
+    }
+
+    #[test]
+    fn test_parse_sections() {
+        let extract = "Rust is a language.\n\n== History ==\nStarted in 2006.\n\n=== Early years ===\nGraydon Hoare.\n\n== See also ==\n\n== Syntax ==\nC-like.";
+        let sections = parse_sections(extract);
+        let titles: Vec<_> = sections.iter().map(|s| (s.title.as_str(), s.level)).collect();
+        assert_eq!(titles, vec![("History", 1), ("Early years", 2), ("Syntax", 1)]);
+        assert_eq!(sections[0].text, "Started in 2006.");
+    }
+
+    #[test]
+    fn test_reading_requests() {
+        let article = WikiArticle { title: "Rust".to_string(), extract: String::new(), language: Language::ENGLISH };
+        let mut reading = WikiReading::new(&article);
+        assert_eq!(reading.parse_request("Дальше!"), Some(ReadingRequest::Next));
+        assert_eq!(reading.parse_request("оглавление"), Some(ReadingRequest::Contents));
+        // Пока разделы не загружены, названия и номера не распознаются
+        assert_eq!(reading.parse_request("history"), None);
+
+        reading.sections = Some(parse_sections("== History ==\nA.\n=== Early years ===\nB.\n== Syntax ==\nC."));
+        assert_eq!(reading.parse_request("history"), Some(ReadingRequest::Section(0)));
+        assert_eq!(reading.parse_request("3"), Some(ReadingRequest::Section(2)));
+        assert_eq!(reading.parse_request("7"), None);
+        assert_eq!(reading.table_of_contents(), "📑 **Rust**\n\n1. History\n   2. Early years\n3. Syntax");
+
+        assert_eq!(reading.next_section().map(|s| s.title.clone()).as_deref(), Some("History"));
+        reading.select(2);
+        assert_eq!(reading.next_section(), None);
+        assert_eq!(WikiArticle::title_of(&article.format()), Some("Rust"));
     }
 
     #[tokio::test]
     async fn test_wikipedia_search() {
-        let results = search_wikipedia_articles("rust programming", 3).await;
+        let results = search_wikipedia_articles("rust programming", Language::ENGLISH, 3).await;
         assert!(results.is_ok());
     }
 }
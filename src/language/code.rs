@@ -0,0 +1,293 @@
+use std::fmt;
+use whatlang::Lang;
+
+/// Описание языка: коды ISO 639-1 / 639-3, названия и поддержка в DeepL
+#[derive(Debug, PartialEq, Eq)]
+pub struct LanguageInfo {
+    pub iso639_1: &'static str,
+    pub iso639_3: &'static str,
+    pub name: &'static str,
+    pub native_name: &'static str,
+    /// Поддерживается ли язык в DeepL (как исходный и как целевой)
+    pub deepl: bool,
+    /// Другие коды того же языка (whatlang `cmn`, Telegram `no`, ISO 639-2/B и т.п.)
+    aliases: &'static [&'static str],
+}
+
+macro_rules! lang {
+    ($iso1:literal, $iso3:literal, $name:literal, $native:literal, $deepl:literal $(, $alias:literal)*) => {
+        LanguageInfo {
+            iso639_1: $iso1,
+            iso639_3: $iso3,
+            name: $name,
+            native_name: $native,
+            deepl: $deepl,
+            aliases: &[$($alias),*],
+        }
+    };
+}
+
+/// Все языки, которые умеет определять whatlang
+const LANGUAGES: &[LanguageInfo] = &[
+    lang!("en", "eng", "English", "English", true),
+    lang!("ru", "rus", "Russian", "Русский", true),
+    lang!("uk", "ukr", "Ukrainian", "Українська", true),
+    lang!("be", "bel", "Belarusian", "Беларуская", false),
+    lang!("de", "deu", "German", "Deutsch", true, "ger"),
+    lang!("fr", "fra", "French", "Français", true, "fre"),
+    lang!("es", "spa", "Spanish", "Español", true),
+    lang!("it", "ita", "Italian", "Italiano", true),
+    lang!("pt", "por", "Portuguese", "Português", true),
+    lang!("nl", "nld", "Dutch", "Nederlands", true, "dut"),
+    lang!("pl", "pol", "Polish", "Polski", true),
+    lang!("cs", "ces", "Czech", "Čeština", true, "cze"),
+    lang!("sk", "slk", "Slovak", "Slovenčina", true, "slo"),
+    lang!("sl", "slv", "Slovenian", "Slovenščina", true),
+    lang!("hr", "hrv", "Croatian", "Hrvatski", false),
+    lang!("sr", "srp", "Serbian", "Српски", false),
+    lang!("mk", "mkd", "Macedonian", "Македонски", false, "mac"),
+    lang!("bg", "bul", "Bulgarian", "Български", true),
+    lang!("ro", "ron", "Romanian", "Română", true, "rum"),
+    lang!("hu", "hun", "Hungarian", "Magyar", true),
+    lang!("el", "ell", "Greek", "Ελληνικά", true, "gre"),
+    lang!("fi", "fin", "Finnish", "Suomi", true),
+    lang!("et", "est", "Estonian", "Eesti", true),
+    lang!("lv", "lav", "Latvian", "Latviešu", true),
+    lang!("lt", "lit", "Lithuanian", "Lietuvių", true),
+    lang!("sv", "swe", "Swedish", "Svenska", true),
+    lang!("da", "dan", "Danish", "Dansk", true),
+    lang!("nb", "nob", "Norwegian", "Norsk bokmål", true, "no", "nor"),
+    lang!("tr", "tur", "Turkish", "Türkçe", true),
+    lang!("az", "aze", "Azerbaijani", "Azərbaycan", false),
+    lang!("uz", "uzb", "Uzbek", "Oʻzbek", false),
+    lang!("tk", "tuk", "Turkmen", "Türkmen", false),
+    lang!("ka", "kat", "Georgian", "ქართული", false, "geo"),
+    lang!("hy", "hye", "Armenian", "Հայերեն", false, "arm"),
+    lang!("ar", "ara", "Arabic", "العربية", true),
+    lang!("he", "heb", "Hebrew", "עברית", false, "iw"),
+    lang!("yi", "yid", "Yiddish", "ייִדיש", false),
+    lang!("fa", "fas", "Persian", "فارسی", false, "pes", "per"),
+    lang!("ur", "urd", "Urdu", "اردو", false),
+    lang!("hi", "hin", "Hindi", "हिन्दी", false),
+    lang!("bn", "ben", "Bengali", "বাংলা", false),
+    lang!("mr", "mar", "Marathi", "मराठी", false),
+    lang!("ne", "nep", "Nepali", "नेपाली", false),
+    lang!("gu", "guj", "Gujarati", "ગુજરાતી", false),
+    lang!("pa", "pan", "Punjabi", "ਪੰਜਾਬੀ", false),
+    lang!("or", "ori", "Odia", "ଓଡ଼ିଆ", false),
+    lang!("ta", "tam", "Tamil", "தமிழ்", false),
+    lang!("te", "tel", "Telugu", "తెలుగు", false),
+    lang!("kn", "kan", "Kannada", "ಕನ್ನಡ", false),
+    lang!("ml", "mal", "Malayalam", "മലയാളം", false),
+    lang!("si", "sin", "Sinhala", "සිංහල", false),
+    lang!("zh", "zho", "Chinese", "中文", true, "cmn", "chi"),
+    lang!("ja", "jpn", "Japanese", "日本語", true),
+    lang!("ko", "kor", "Korean", "한국어", true),
+    lang!("vi", "vie", "Vietnamese", "Tiếng Việt", false),
+    lang!("th", "tha", "Thai", "ไทย", false),
+    lang!("km", "khm", "Khmer", "ខ្មែរ", false),
+    lang!("my", "mya", "Burmese", "မြန်မာ", false, "bur"),
+    lang!("id", "ind", "Indonesian", "Bahasa Indonesia", true, "in"),
+    lang!("jv", "jav", "Javanese", "Basa Jawa", false),
+    lang!("tl", "tgl", "Tagalog", "Tagalog", false, "fil"),
+    lang!("am", "amh", "Amharic", "አማርኛ", false),
+    lang!("ak", "aka", "Akan", "Akan", false),
+    lang!("zu", "zul", "Zulu", "isiZulu", false),
+    lang!("sn", "sna", "Shona", "chiShona", false),
+    lang!("af", "afr", "Afrikaans", "Afrikaans", false),
+    lang!("ca", "cat", "Catalan", "Català", false),
+    lang!("la", "lat", "Latin", "Latina", false),
+    lang!("eo", "epo", "Esperanto", "Esperanto", false),
+];
+
+/// Языки, на которых бот умеет отвечать (выбор в /settings и т.п.)
+const SUPPORTED: &[&str] = &["en", "ru", "de", "fr", "es", "it", "ja", "zh"];
+
+/// Язык. Внутри бота язык обозначается двухбуквенным кодом в верхнем регистре
+/// (`EN`, `RU`) — см. [`Language::code`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Language(&'static LanguageInfo);
+
+impl Language {
+    pub const ENGLISH: Language = Language(&LANGUAGES[0]);
+    pub const RUSSIAN: Language = Language(&LANGUAGES[1]);
+
+    /// Внутренний код бота: ISO 639-1 в верхнем регистре (`EN`, `RU`)
+    pub fn code(&self) -> String {
+        self.0.iso639_1.to_uppercase()
+    }
+
+    pub fn iso639_1(&self) -> &'static str {
+        self.0.iso639_1
+    }
+
+    pub fn iso639_3(&self) -> &'static str {
+        self.0.iso639_3
+    }
+
+    /// Английское название языка
+    pub fn name(&self) -> &'static str {
+        self.0.name
+    }
+
+    /// Самоназвание языка («Русский», «Deutsch»)
+    pub fn native_name(&self) -> &'static str {
+        self.0.native_name
+    }
+
+    /// Отвечает ли бот на этом языке
+    pub fn is_supported(&self) -> bool {
+        SUPPORTED.contains(&self.0.iso639_1)
+    }
+
+    /// Язык по результату whatlang
+    pub fn from_whatlang(lang: Lang) -> Option<Self> {
+        Self::from_iso639_3(lang.code())
+    }
+
+    pub fn from_iso639_1(code: &str) -> Option<Self> {
+        let code = code.trim().to_lowercase();
+        LANGUAGES
+            .iter()
+            .find(|info| info.iso639_1 == code || (code.len() == 2 && info.aliases.contains(&code.as_str())))
+            .map(Language)
+    }
+
+    pub fn from_iso639_3(code: &str) -> Option<Self> {
+        let code = code.trim().to_lowercase();
+        LANGUAGES
+            .iter()
+            .find(|info| info.iso639_3 == code || (code.len() == 3 && info.aliases.contains(&code.as_str())))
+            .map(Language)
+    }
+
+    /// Разбирает код в любом распространённом виде: `en`, `EN`, `eng`, `en-GB`, `pt_BR`, `zh-Hans`
+    pub fn parse(code: &str) -> Option<Self> {
+        LanguageTag::parse(code).map(|tag| tag.language)
+    }
+
+    /// Код исходного языка для DeepL (`EN`, `PT`) — без региона
+    pub fn deepl_source(&self) -> Option<String> {
+        self.0.deepl.then(|| self.code())
+    }
+
+    /// Код целевого языка для DeepL без учёта региона (`EN-US`, `PT-BR`, `ZH-HANS`, `DE`)
+    pub fn deepl_target(&self) -> Option<String> {
+        LanguageTag { language: *self, region: None }.deepl_target()
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+/// Язык с необязательным регионом или письменностью: `EN-GB`, `PT-BR`, `ZH-HANT`.
+/// В таком виде приходит `language_code` из Telegram и целевые языки DeepL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LanguageTag {
+    pub language: Language,
+    pub region: Option<String>, // в верхнем регистре
+}
+
+impl LanguageTag {
+    /// Разбирает тег BCP 47 / DeepL / Telegram (`en`, `en-US`, `pt_br`, `ENG`)
+    pub fn parse(code: &str) -> Option<Self> {
+        let mut parts = code.trim().split(['-', '_']);
+        let primary = parts.next()?;
+        let language = match primary.len() {
+            2 => Language::from_iso639_1(primary),
+            3 => Language::from_iso639_3(primary),
+            _ => None,
+        }?;
+        let region = parts
+            .next()
+            .filter(|r| !r.is_empty())
+            .map(|r| r.to_uppercase());
+        Some(Self { language, region })
+    }
+
+    /// Язык из поля `language_code` пользователя Telegram (`en`, `pt-br`, `zh-hans`)
+    pub fn from_telegram(language_code: &str) -> Option<Self> {
+        Self::parse(language_code)
+    }
+
+    /// Код целевого языка DeepL. Английский и португальский требуют вариант,
+    /// китайский — письменность; по умолчанию `EN-US`, `PT-BR`, `ZH-HANS`.
+    pub fn deepl_target(&self) -> Option<String> {
+        if !self.language.0.deepl {
+            return None;
+        }
+        let region = self.region.as_deref();
+        let code = match self.language.iso639_1() {
+            "en" => match region {
+                Some("GB" | "UK" | "IE" | "AU" | "NZ" | "IN" | "ZA") => "EN-GB",
+                _ => "EN-US",
+            },
+            "pt" => match region {
+                Some("PT" | "AO" | "MZ") => "PT-PT",
+                _ => "PT-BR",
+            },
+            "zh" => match region {
+                Some("HANT" | "TW" | "HK" | "MO") => "ZH-HANT",
+                _ => "ZH-HANS",
+            },
+            _ => return Some(self.language.code()),
+        };
+        Some(code.to_string())
+    }
+}
+
+impl fmt::Display for LanguageTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.region {
+            Some(region) => write!(f, "{}-{}", self.language.code(), region),
+            None => write!(f, "{}", self.language.code()),
+        }
+    }
+}
+
+/// Нормализует код языка к внутреннему виду бота (`eng` → `EN`, `pt-br` → `PT-BR`)
+pub fn normalize_code(code: &str) -> Option<String> {
+    LanguageTag::parse(code).map(|tag| tag.to_string())
+}
+
+/// Языки, на которых бот отвечает
+pub fn supported_languages() -> Vec<Language> {
+    SUPPORTED.iter().filter_map(|code| Language::from_iso639_1(code)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_whatlang_language_is_mapped() {
+        for lang in Lang::all() {
+            assert!(Language::from_whatlang(*lang).is_some(), "нет соответствия для {:?}", lang);
+        }
+        assert_eq!(Language::from_whatlang(Lang::Eng).unwrap().code(), "EN");
+        assert_eq!(Language::from_whatlang(Lang::Cmn).unwrap().code(), "ZH");
+        assert_eq!(Language::from_whatlang(Lang::Pes).unwrap().code(), "FA");
+    }
+
+    #[test]
+    fn test_parse_codes() {
+        assert_eq!(Language::parse("eng"), Some(Language::ENGLISH));
+        assert_eq!(Language::parse("RU"), Some(Language::RUSSIAN));
+        assert_eq!(Language::parse("no").unwrap().code(), "NB");
+        assert_eq!(Language::parse("xx"), None);
+        assert_eq!(normalize_code("pt_br").as_deref(), Some("PT-BR"));
+    }
+
+    #[test]
+    fn test_deepl_codes() {
+        assert_eq!(LanguageTag::parse("en").unwrap().deepl_target().as_deref(), Some("EN-US"));
+        assert_eq!(LanguageTag::from_telegram("en-GB").unwrap().deepl_target().as_deref(), Some("EN-GB"));
+        assert_eq!(LanguageTag::parse("pt-PT").unwrap().deepl_target().as_deref(), Some("PT-PT"));
+        assert_eq!(LanguageTag::parse("zh-hant").unwrap().deepl_target().as_deref(), Some("ZH-HANT"));
+        assert_eq!(LanguageTag::parse("EN-GB").unwrap().language.deepl_source().as_deref(), Some("EN"));
+        assert_eq!(Language::parse("hi").unwrap().deepl_target(), None);
+    }
+}
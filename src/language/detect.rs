@@ -1,24 +1,266 @@
-use whatlang::{detect, Lang};
+use whatlang::detect;
 
-/// Определяет язык текста и возвращает его ISO-код (например, "EN", "RU", "JA")
+use crate::language::{
+    code::Language,
+    segment::{grapheme_count, sentences},
+};
+
+/// Сколько слов считается «коротким» сообщением, на котором whatlang ненадёжен
+const SHORT_TEXT_WORDS: usize = 3;
+
+/// Короткие слова, по которым язык узнаётся однозначно (в нижнем регистре). Слова, общие
+/// для нескольких языков («так» — русский и украинский, «no» — английский, испанский
+/// и итальянский, «ja» — немецкий и скандинавские), сюда не входят: для них язык подскажет
+/// история диалога.
+const LEXICON: &[(&str, &str)] = &[
+    ("hi", "en"), ("hello", "en"), ("hey", "en"), ("thanks", "en"), ("thx", "en"), ("thank", "en"),
+    ("yes", "en"), ("bye", "en"), ("please", "en"), ("pls", "en"), ("what", "en"),
+    ("how", "en"), ("why", "en"), ("who", "en"), ("where", "en"), ("good", "en"), ("morning", "en"),
+    ("help", "en"), ("the", "en"), ("weather", "en"),
+    ("привет", "ru"), ("спс", "ru"), ("спасибо", "ru"), ("да", "ru"), ("нет", "ru"), ("пока", "ru"),
+    ("здравствуйте", "ru"), ("хай", "ru"), ("норм", "ru"), ("ага", "ru"), ("что", "ru"), ("как", "ru"),
+    ("почему", "ru"), ("кто", "ru"), ("где", "ru"), ("помощь", "ru"), ("погода", "ru"),
+    ("есть", "ru"),
+    ("привіт", "uk"), ("дякую", "uk"), ("ні", "uk"), ("що", "uk"),
+    ("hallo", "de"), ("danke", "de"), ("nein", "de"), ("tschüss", "de"),
+    ("bonjour", "fr"), ("merci", "fr"), ("salut", "fr"), ("oui", "fr"),
+    ("hola", "es"), ("gracias", "es"), ("sí", "es"), ("adiós", "es"), ("qué", "es"),
+    ("ciao", "it"), ("grazie", "it"), ("buongiorno", "it"),
+];
+
+/// Слова, которые одинаково пишут на любом языке — по ним язык не определить
+const NEUTRAL_WORDS: &[&str] = &["ok", "okay", "ок", "lol", "хм", "hmm", "wow", "ха", "haha", "хаха"];
+
+/// Письменность (по преобладающим буквам текста)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Script {
+    Latin,
+    Cyrillic,
+    Greek,
+    Arabic,
+    Hebrew,
+    Han,
+    Kana,
+    Hangul,
+    Devanagari,
+    Georgian,
+    Armenian,
+    Thai,
+    Other,
+}
+
+fn script_of_char(c: char) -> Script {
+    match c {
+        'a'..='z' | 'A'..='Z' | '\u{00C0}'..='\u{024F}' => Script::Latin,
+        '\u{0400}'..='\u{04FF}' => Script::Cyrillic,
+        '\u{0370}'..='\u{03FF}' => Script::Greek,
+        '\u{0600}'..='\u{06FF}' => Script::Arabic,
+        '\u{0590}'..='\u{05FF}' => Script::Hebrew,
+        '\u{4E00}'..='\u{9FFF}' | '\u{3400}'..='\u{4DBF}' => Script::Han,
+        '\u{3040}'..='\u{30FF}' => Script::Kana,
+        '\u{AC00}'..='\u{D7AF}' | '\u{1100}'..='\u{11FF}' => Script::Hangul,
+        '\u{0900}'..='\u{097F}' => Script::Devanagari,
+        '\u{10A0}'..='\u{10FF}' => Script::Georgian,
+        '\u{0530}'..='\u{058F}' => Script::Armenian,
+        '\u{0E00}'..='\u{0E7F}' => Script::Thai,
+        _ => Script::Other,
+    }
+}
+
+/// Основная письменность языка
+fn script_of_language(lang: Language) -> Script {
+    match lang.iso639_1() {
+        "ru" | "uk" | "be" | "bg" | "sr" | "mk" => Script::Cyrillic,
+        "el" => Script::Greek,
+        "ar" | "fa" | "ur" => Script::Arabic,
+        "he" | "yi" => Script::Hebrew,
+        "zh" => Script::Han,
+        "ja" => Script::Kana,
+        "ko" => Script::Hangul,
+        "hi" | "mr" | "ne" => Script::Devanagari,
+        "ka" => Script::Georgian,
+        "hy" => Script::Armenian,
+        "th" => Script::Thai,
+        _ => Script::Latin,
+    }
+}
+
+/// Преобладающая письменность букв текста; `None`, если букв нет
+fn dominant_script(text: &str) -> Option<Script> {
+    let mut counts: Vec<(Script, usize)> = Vec::new();
+    for c in text.chars().filter(|c| c.is_alphabetic()) {
+        let script = script_of_char(c);
+        match counts.iter_mut().find(|(s, _)| *s == script) {
+            Some((_, n)) => *n += 1,
+            None => counts.push((script, 1)),
+        }
+    }
+    // Японский текст — смесь каны и иероглифов: любая кана означает японский
+    if counts.iter().any(|(s, _)| *s == Script::Kana) {
+        return Some(Script::Kana);
+    }
+    counts.into_iter().max_by_key(|(_, n)| *n).map(|(s, _)| s)
+}
+
+/// Подсказки для определения языка коротких сообщений
+#[derive(Debug, Clone, Default)]
+pub struct LanguageHints {
+    /// Язык интерфейса клиента (например, `language_code` пользователя Telegram)
+    pub client_language: Option<Language>,
+    /// Языки последних сообщений пользователя (от старых к новым)
+    pub recent: Vec<Language>,
+}
+
+impl LanguageHints {
+    /// Самый частый язык последних сообщений (при равенстве — более свежий)
+    fn recent_majority(&self) -> Option<Language> {
+        let mut best: Option<(Language, usize)> = None;
+        for lang in self.recent.iter().rev() {
+            let count = self.recent.iter().filter(|l| *l == lang).count();
+            if best.is_none_or(|(_, n)| count > n) {
+                best = Some((*lang, count));
+            }
+        }
+        best.map(|(lang, _)| lang)
+    }
+
+    /// Подходящий по письменности язык из подсказок: сначала история, затем клиент
+    fn preferred(&self, script: Script) -> Option<Language> {
+        self.recent_majority()
+            .filter(|lang| script_of_language(*lang) == script)
+            .or_else(|| self.client_language.filter(|lang| script_of_language(*lang) == script))
+    }
+}
+
+/// Результат определения языка
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Detection {
+    /// Язык определён уверенно
+    Detected(Language),
+    /// Определить нельзя (числа, эмодзи, «ok») — оставить прежний язык диалога
+    Unknown,
+}
+
+impl Detection {
+    pub fn language(self) -> Option<Language> {
+        match self {
+            Detection::Detected(lang) => Some(lang),
+            Detection::Unknown => None,
+        }
+    }
+}
+
+/// Определяет язык сообщения по письменности, словарю коротких слов, whatlang
+/// и подсказкам (язык клиента, языки последних сообщений)
+pub fn detect_with_hints(text: &str, hints: &LanguageHints) -> Detection {
+    let Some(script) = dominant_script(text) else {
+        return Detection::Unknown;
+    };
+
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphabetic())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    if words.iter().all(|w| NEUTRAL_WORDS.contains(&w.as_str())) {
+        return Detection::Unknown;
+    }
+
+    let by_iso = |code: &str| Language::from_iso639_1(code).map_or(Detection::Unknown, Detection::Detected);
+
+    // Письменности, однозначно задающие язык
+    match script {
+        Script::Greek => return by_iso("el"),
+        Script::Kana => return by_iso("ja"),
+        Script::Hangul => return by_iso("ko"),
+        Script::Georgian => return by_iso("ka"),
+        Script::Armenian => return by_iso("hy"),
+        Script::Thai => return by_iso("th"),
+        // Одни иероглифы: японский, только если на него указывают подсказки
+        Script::Han => return by_iso(if hints.preferred(Script::Kana).is_some() { "ja" } else { "zh" }),
+        _ => {}
+    }
+
+    // Буквы, которые есть только в одном кириллическом алфавите
+    if script == Script::Cyrillic {
+        if text.contains(['ў', 'Ў']) {
+            return by_iso("be");
+        }
+        if text.contains(['і', 'ї', 'є', 'ґ', 'І', 'Ї', 'Є', 'Ґ']) {
+            return by_iso("uk");
+        }
+    }
+
+    // Длинный текст: whatlang достаточно надёжен, если согласен с письменностью
+    if words.len() > SHORT_TEXT_WORDS {
+        if let Some(info) = detect(text).filter(|info| info.is_reliable()) {
+            if let Some(lang) = Language::from_whatlang(info.lang()).filter(|l| script_of_language(*l) == script) {
+                return Detection::Detected(lang);
+            }
+        }
+    }
+
+    // Словарь коротких слов: голосование
+    let mut votes: Vec<(&str, usize)> = Vec::new();
+    for word in &words {
+        if let Some((_, code)) = LEXICON.iter().find(|(w, _)| w == word) {
+            match votes.iter_mut().find(|(c, _)| c == code) {
+                Some((_, n)) => *n += 1,
+                None => votes.push((code, 1)),
+            }
+        }
+    }
+    votes.sort_by_key(|(_, n)| std::cmp::Reverse(*n));
+    match votes.as_slice() {
+        [(code, _)] => return by_iso(code),
+        [(code, first), (_, second), ..] if first > second => return by_iso(code),
+        _ => {}
+    }
+
+    // Подсказки: язык последних сообщений или клиента с той же письменностью
+    if let Some(lang) = hints.preferred(script) {
+        return Detection::Detected(lang);
+    }
+
+    match script {
+        // Среди кириллических языков без особых букв по умолчанию — русский
+        Script::Cyrillic => by_iso("ru"),
+        Script::Arabic => by_iso("ar"),
+        Script::Hebrew => by_iso("he"),
+        Script::Devanagari => by_iso("hi"),
+        // Короткий латинский текст без подсказок: доверяем только уверенному whatlang
+        _ => detect(text)
+            .filter(|info| info.is_reliable())
+            .and_then(|info| Language::from_whatlang(info.lang()))
+            .filter(|lang| script_of_language(*lang) == script)
+            .map_or(Detection::Unknown, Detection::Detected),
+    }
+}
+
+/// Определяет язык текста без подсказок
+pub fn detect_lang(text: &str) -> Option<Language> {
+    detect_with_hints(text, &LanguageHints::default()).language()
+}
+
+/// Определяет язык текста и возвращает его код ISO 639-1 (например, "EN", "RU", "JA")
 pub fn detect_language(text: &str) -> Option<String> {
-    detect(text).map(|info| info.lang().code().to_uppercase())
+    detect_lang(text).map(|lang| lang.code())
 }
 
 /// Проверка: является ли язык английским
 pub fn is_english(text: &str) -> bool {
-    detect(text).map_or(false, |info| info.lang() == Lang::Eng)
+    detect_lang(text) == Some(Language::ENGLISH)
 }
 
 /// Проверка: является ли язык русским
 pub fn is_russian(text: &str) -> bool {
-    detect(text).map_or(false, |info| info.lang() == Lang::Rus)
+    detect_lang(text) == Some(Language::RUSSIAN)
 }
 
 /// Получает детальную информацию о языке (код + уверенность)
 pub fn detect_language_with_confidence(text: &str) -> Option<(String, f64)> {
-    detect(text).map(|info| {
-        (info.lang().code().to_uppercase(), info.confidence())
+    detect(text).and_then(|info| {
+        Language::from_whatlang(info.lang()).map(|lang| (lang.code(), info.confidence()))
     })
 }
 
@@ -34,38 +276,23 @@ pub fn detect_language_or_default(text: &str) -> String {
     })
 }
 
-/// Проверяет, поддерживается ли язык ботом
+/// Проверяет, поддерживается ли язык ботом (принимает любой вид кода: `EN`, `eng`, `en-GB`)
 pub fn is_supported_language(lang_code: &str) -> bool {
-    matches!(lang_code, "EN" | "RU" | "DE" | "FR" | "ES" | "IT" | "JA" | "ZH")
+    Language::parse(lang_code).is_some_and(|lang| lang.is_supported())
 }
 
 /// Возвращает человекочитаемое название языка
-pub fn get_language_name(lang_code: &str) -> &str {
-    match lang_code {
-        "EN" => "English",
-        "RU" => "Русский",
-        "DE" => "Deutsch", 
-        "FR" => "Français",
-        "ES" => "Español",
-        "IT" => "Italiano",
-        "JA" => "日本語",
-        "ZH" => "中文",
-        "PT" => "Português",
-        "KO" => "한국어",
-        "AR" => "العربية",
-        "HI" => "हिन्दी",
-        _ => "Unknown"
-    }
+pub fn get_language_name(lang_code: &str) -> &'static str {
+    Language::parse(lang_code).map_or("Unknown", |lang| lang.native_name())
 }
 
 /// Определяет основные языки в многоязычном тексте
 pub fn detect_mixed_languages(text: &str) -> Vec<String> {
-    let sentences: Vec<&str> = text.split(&['.', '!', '?'][..]).collect();
     let mut languages = Vec::new();
     
-    for sentence in sentences {
-        if sentence.trim().len() > 10 {
-            if let Some(lang) = detect_language(sentence.trim()) {
+    for sentence in sentences(text) {
+        if grapheme_count(&sentence) > 10 {
+            if let Some(lang) = detect_language(&sentence) {
                 if !languages.contains(&lang) {
                     languages.push(lang);
                 }
@@ -87,6 +314,61 @@ mod tests {
         assert_eq!(detect_language("Bonjour le monde"), Some("FR".to_string()));
     }
 
+    #[test]
+    fn test_detect_returns_iso639_1() {
+        assert_eq!(
+            detect_language("The quick brown fox jumps over the lazy dog near the river bank"),
+            Some("EN".to_string())
+        );
+        assert_eq!(
+            detect_language("Съешь же ещё этих мягких французских булок, да выпей чаю"),
+            Some("RU".to_string())
+        );
+        let (code, _) = detect_language_with_confidence("Der schnelle braune Fuchs springt über den faulen Hund").unwrap();
+        assert_eq!(code, "DE");
+    }
+
+    #[test]
+    fn test_short_messages() {
+        let none = LanguageHints::default();
+        assert_eq!(detect_with_hints("hi", &none).language(), Some(Language::ENGLISH));
+        assert_eq!(detect_with_hints("спс", &none).language(), Some(Language::RUSSIAN));
+        assert_eq!(detect_with_hints("2+2", &none), Detection::Unknown);
+        assert_eq!(detect_with_hints("ok", &none), Detection::Unknown);
+        assert_eq!(detect_with_hints("дякую", &none).language().map(|l| l.code()), Some("UK".to_string()));
+        assert_eq!(detect_with_hints("こんにちは", &none).language().map(|l| l.code()), Some("JA".to_string()));
+        // «так» — частое русское слово, не признак украинского
+        for text in ["так", "ну так", "так и есть"] {
+            assert_eq!(detect_with_hints(text, &none).language(), Some(Language::RUSSIAN), "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_shared_words_follow_hints() {
+        let spanish = Language::parse("es").unwrap();
+        let hints = LanguageHints { client_language: None, recent: vec![spanish] };
+        assert_eq!(detect_with_hints("no", &hints).language(), Some(spanish));
+        let german = Language::parse("de").unwrap();
+        let hints = LanguageHints { client_language: Some(german), recent: Vec::new() };
+        assert_eq!(detect_with_hints("ja", &hints).language(), Some(german));
+    }
+
+    #[test]
+    fn test_hints_break_ties() {
+        let german = Language::parse("de").unwrap();
+        let hints = LanguageHints {
+            client_language: Some(Language::ENGLISH),
+            recent: vec![german, german, Language::ENGLISH],
+        };
+        // Незнакомое короткое слово латиницей — берём преобладающий язык диалога
+        assert_eq!(detect_with_hints("Zugspitze", &hints).language(), Some(german));
+        // Подсказка не противоречит письменности
+        assert_eq!(detect_with_hints("мир", &hints).language(), Some(Language::RUSSIAN));
+
+        let client_only = LanguageHints { client_language: Some(german), recent: Vec::new() };
+        assert_eq!(detect_with_hints("Zugspitze", &client_only).language(), Some(german));
+    }
+
     #[test]
     fn test_language_checks() {
         assert!(is_english("Hello world"));
@@ -104,6 +386,8 @@ mod tests {
     fn test_supported_languages() {
         assert!(is_supported_language("EN"));
         assert!(is_supported_language("RU"));
+        assert!(is_supported_language("eng"));
         assert!(!is_supported_language("XX"));
+        assert_eq!(get_language_name("ru"), "Русский");
     }
 }
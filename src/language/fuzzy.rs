@@ -0,0 +1,301 @@
+use std::env;
+use std::sync::OnceLock;
+
+/// Ряды клавиатуры QWERTY и ЙЦУКЕН: одинаковые позиции — одна и та же физическая клавиша
+const LATIN_ROWS: [&str; 4] = ["`1234567890-=", "qwertyuiop[]", "asdfghjkl;'", "zxcvbnm,./"];
+const CYRILLIC_ROWS: [&str; 4] = ["ё1234567890-=", "йцукенгшщзхъ", "фывапролджэ", "ячсмитьбю."];
+
+/// Позиция символа на клавиатуре (ряд, колонка) в любой из раскладок
+fn key_position(c: char) -> Option<(usize, usize)> {
+    let c = c.to_lowercase().next().unwrap_or(c);
+    for rows in [&LATIN_ROWS, &CYRILLIC_ROWS] {
+        for (row, keys) in rows.iter().enumerate() {
+            if let Some(col) = keys.chars().position(|k| k == c) {
+                return Some((row, col));
+            }
+        }
+    }
+    None
+}
+
+/// Соседние ли клавиши (опечатка «промахнулся пальцем» дешевле обычной замены)
+fn adjacent_keys(a: char, b: char) -> bool {
+    match (key_position(a), key_position(b)) {
+        (Some((ra, ca)), Some((rb, cb))) => ra.abs_diff(rb) <= 1 && ca.abs_diff(cb) <= 1,
+        _ => false,
+    }
+}
+
+/// Символ той же клавиши в другой раскладке
+fn counterpart(c: char) -> Option<char> {
+    LATIN_ROWS.iter().zip(CYRILLIC_ROWS.iter()).find_map(|(latin, cyrillic)| {
+        match (latin.chars().position(|k| k == c), cyrillic.chars().position(|k| k == c)) {
+            (Some(i), _) => cyrillic.chars().nth(i),
+            (_, Some(i)) => latin.chars().nth(i),
+            _ => None,
+        }
+    })
+}
+
+/// Переключает раскладку: текст, набранный в английской раскладке вместо русской, и наоборот
+/// (`ghbdtn` → `привет`, `руддщ` → `hello`). Знаки препинания меняются, только если они
+/// стоят внутри слова (`k.,k.` → `люблю`); в `Ghbdtn,` запятая остаётся запятой.
+pub fn swap_layout(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut token = String::new();
+
+    for c in text.chars() {
+        if c.is_whitespace() {
+            result.push_str(&swap_token(&token));
+            token.clear();
+            result.push(c);
+        } else {
+            token.push(c);
+        }
+    }
+    result.push_str(&swap_token(&token));
+    result
+}
+
+fn swap_token(token: &str) -> String {
+    let chars: Vec<char> = token.chars().collect();
+    // Есть ли знак препинания между буквами — тогда это буквы другой раскладки
+    let punctuation_inside = chars.windows(3).any(|w| {
+        w[0].is_alphabetic() && !w[1].is_alphabetic() && counterpart(w[1]).is_some_and(char::is_alphabetic)
+    });
+
+    chars
+        .iter()
+        .map(|&c| {
+            let lower = c.to_lowercase().next().unwrap_or(c);
+            if !(c.is_alphabetic() || punctuation_inside) {
+                return c;
+            }
+            match counterpart(lower).filter(|swapped| c.is_alphabetic() || swapped.is_alphabetic()) {
+                Some(swapped) if c.is_uppercase() => swapped.to_uppercase().next().unwrap_or(swapped),
+                Some(swapped) => swapped,
+                None => c,
+            }
+        })
+        .collect()
+}
+
+/// Расстояние Дамерау–Левенштейна (с перестановкой соседних букв), где замена на
+/// соседнюю клавишу стоит 0.5
+pub fn edit_distance(a: &str, b: &str) -> f32 {
+    let a: Vec<char> = a.to_lowercase().chars().collect();
+    let b: Vec<char> = b.to_lowercase().chars().collect();
+    let (n, m) = (a.len(), b.len());
+    let mut d = vec![vec![0.0f32; m + 1]; n + 1];
+
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i as f32;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j as f32;
+    }
+
+    for i in 1..=n {
+        for j in 1..=m {
+            let substitution = if a[i - 1] == b[j - 1] {
+                0.0
+            } else if adjacent_keys(a[i - 1], b[j - 1]) {
+                0.5
+            } else {
+                1.0
+            };
+            let mut best = (d[i - 1][j] + 1.0)
+                .min(d[i][j - 1] + 1.0)
+                .min(d[i - 1][j - 1] + substitution);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                best = best.min(d[i - 2][j - 2] + 1.0);
+            }
+            d[i][j] = best;
+        }
+    }
+    d[n][m]
+}
+
+/// Частые слова, которые сами по себе правильные: их не «исправляем» в похожее ключевое
+/// слово («think» — не опечатка в «thank», «thing» — не опечатка в «thin»)
+const COMMON_WORDS: &[&str] = &[
+    "think", "thing", "three", "there", "their", "these", "those", "where", "which", "while",
+    "whole", "world", "would", "could", "should", "about", "after", "again", "other", "right",
+    "still", "small", "stuff", "thick", "thief", "shank", "shell", "happy",
+    "когда", "потом", "после", "можно", "нужно", "очень", "почему", "сейчас", "теперь", "только",
+    "просто", "ничего", "всегда", "хорошо", "вообще", "сегодня",
+];
+
+/// Частое правильное слово, которое не считается опечаткой
+pub fn is_common_word(word: &str) -> bool {
+    let word = word.to_lowercase();
+    COMMON_WORDS.contains(&word.as_str())
+}
+
+/// Результат нечёткого сравнения
+#[derive(Debug, Clone, PartialEq)]
+pub struct FuzzyMatch<'a> {
+    pub candidate: &'a str,
+    pub distance: f32,
+    /// Совпало после переключения раскладки
+    pub layout_swapped: bool,
+}
+
+impl FuzzyMatch<'_> {
+    pub fn is_exact(&self) -> bool {
+        self.distance == 0.0 && !self.layout_swapped
+    }
+}
+
+/// Настройки допустимых опечаток
+#[derive(Debug, Clone)]
+pub struct FuzzyMatcher {
+    /// С какой длины слова допускается одна опечатка (короче — только точное совпадение)
+    pub one_typo_min_len: usize,
+    /// С какой длины слова допускаются две опечатки
+    pub two_typos_min_len: usize,
+    /// Требовать совпадения первой буквы (в первой букве ошибаются редко, а ложных совпадений
+    /// вроде «funny» → «sunny» так меньше)
+    pub same_first_letter: bool,
+    /// Пробовать вариант в другой раскладке
+    pub layout_aware: bool,
+}
+
+impl Default for FuzzyMatcher {
+    fn default() -> Self {
+        Self {
+            one_typo_min_len: 5,
+            two_typos_min_len: 9,
+            same_first_letter: true,
+            layout_aware: true,
+        }
+    }
+}
+
+impl FuzzyMatcher {
+    /// Настройки из окружения: `FUZZY_ONE_TYPO_MIN_LEN`, `FUZZY_TWO_TYPOS_MIN_LEN`
+    /// (`FUZZY_ONE_TYPO_MIN_LEN=0` отключает исправление опечаток)
+    pub fn from_env() -> Self {
+        let read = |name: &str| env::var(name).ok().and_then(|v| v.trim().parse::<usize>().ok());
+        let mut matcher = Self::default();
+        if let Some(len) = read("FUZZY_ONE_TYPO_MIN_LEN") {
+            matcher.one_typo_min_len = if len == 0 { usize::MAX } else { len };
+        }
+        if let Some(len) = read("FUZZY_TWO_TYPOS_MIN_LEN") {
+            matcher.two_typos_min_len = len;
+        }
+        matcher
+    }
+
+    /// Сколько правок допустимо для слова такой длины
+    pub fn max_distance(&self, len: usize) -> f32 {
+        if len >= self.two_typos_min_len {
+            2.0
+        } else if len >= self.one_typo_min_len {
+            1.0
+        } else {
+            0.0
+        }
+    }
+
+    /// Сравнивает слово с одним кандидатом
+    pub fn compare<'a>(&self, word: &str, candidate: &'a str) -> Option<FuzzyMatch<'a>> {
+        let word = word.to_lowercase();
+        let candidate_lower = candidate.to_lowercase();
+
+        if word == candidate_lower {
+            return Some(FuzzyMatch { candidate, distance: 0.0, layout_swapped: false });
+        }
+        if self.layout_aware && swap_layout(&word) == candidate_lower {
+            return Some(FuzzyMatch { candidate, distance: 0.0, layout_swapped: true });
+        }
+
+        // Настоящее слово — не опечатка, даже если похоже на кандидата
+        if is_common_word(&word) {
+            return None;
+        }
+        if self.same_first_letter && word.chars().next() != candidate_lower.chars().next() {
+            return None;
+        }
+        // Допуск определяется длиной правильного слова
+        let allowed = self.max_distance(candidate_lower.chars().count());
+        if allowed == 0.0 {
+            return None;
+        }
+        let distance = edit_distance(&word, &candidate_lower);
+        (distance <= allowed).then_some(FuzzyMatch { candidate, distance, layout_swapped: false })
+    }
+
+    /// Ближайший кандидат в пределах допустимого числа опечаток
+    pub fn best_match<'a>(&self, word: &str, candidates: &[&'a str]) -> Option<FuzzyMatch<'a>> {
+        candidates
+            .iter()
+            .filter_map(|candidate| self.compare(word, candidate))
+            .min_by(|a, b| a.distance.total_cmp(&b.distance))
+    }
+
+    /// Подсказка «возможно, вы имели в виду …?»: ближайший кандидат, если слово не совпало точно
+    pub fn suggest<'a>(&self, word: &str, candidates: &[&'a str]) -> Option<&'a str> {
+        self.best_match(word, candidates)
+            .filter(|m| !m.is_exact())
+            .map(|m| m.candidate)
+    }
+}
+
+/// Глобальные настройки нечёткого сравнения (из окружения, один раз)
+pub fn default_matcher() -> &'static FuzzyMatcher {
+    static MATCHER: OnceLock<FuzzyMatcher> = OnceLock::new();
+    MATCHER.get_or_init(FuzzyMatcher::from_env)
+}
+
+/// Текст подсказки «Возможно, вы имели в виду …?»
+pub fn did_you_mean(suggestion: &str) -> String {
+    format!("Возможно, вы имели в виду «{}»?", suggestion)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_swap_layout() {
+        assert_eq!(swap_layout("ghbdtn"), "привет");
+        assert_eq!(swap_layout("руддщ"), "hello");
+        assert_eq!(swap_layout("Ghbdtn, 123"), "Привет, 123");
+        assert_eq!(swap_layout("k.,k."), "люблю");
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("pyhton", "python"), 1.0);
+        assert_eq!(edit_distance("wether", "weather"), 1.0);
+        // r и t — соседние клавиши
+        assert_eq!(edit_distance("tust", "rust"), 0.5);
+        assert_eq!(edit_distance("Rust", "rust"), 0.0);
+    }
+
+    #[test]
+    fn test_matcher_thresholds() {
+        let matcher = FuzzyMatcher::default();
+        assert!(matcher.compare("londn", "London").is_some());
+        assert!(matcher.compare("snow", "show").is_none()); // короткие слова — только точно
+        assert!(matcher.compare("funny", "sunny").is_none());
+        assert!(matcher.compare("whether", "weather").is_none());
+        // Частые слова не исправляются в похожие ключевые слова
+        assert!(matcher.compare("think", "thank").is_none());
+        assert!(matcher.compare("thank", "thank").is_some());
+
+        let swapped = matcher.compare("ghbdtn", "привет").unwrap();
+        assert!(swapped.layout_swapped);
+    }
+
+    #[test]
+    fn test_suggest() {
+        let matcher = FuzzyMatcher::default();
+        let commands = ["settings", "start", "stats", "forget"];
+        assert_eq!(matcher.suggest("setings", &commands), Some("settings"));
+        assert_eq!(matcher.suggest("ыефке", &commands), Some("start"));
+        assert_eq!(matcher.suggest("start", &commands), None);
+        assert_eq!(matcher.suggest("xyz", &commands), None);
+    }
+}
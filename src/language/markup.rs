@@ -0,0 +1,159 @@
+use regex::Regex;
+use std::sync::OnceLock;
+
+static PROTECTED_REGEX: OnceLock<Regex> = OnceLock::new();
+
+/// Фрагмент ответа: текст для перевода или защищённая разметка, которую нужно сохранить как есть
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Segment {
+    Text(String),
+    Protected(String),
+}
+
+/// Всё, что нельзя отдавать переводчику: блоки и строки кода, ссылки, имена репозиториев,
+/// числа с единицами, маркеры markdown и списков, эмодзи, переводы строк
+fn protected_regex() -> &'static Regex {
+    PROTECTED_REGEX.get_or_init(|| {
+        Regex::new(concat!(
+            r"(?s:```.*?```)",                                       // блок кода
+            r"|`[^`\n]+`",                                           // инлайн-код
+            r"|\]\([^)\s]+\)|\[",                                    // хвост markdown-ссылки [текст](url)
+            r"|https?://[^\s<>()\[\]]*[^\s<>()\[\].,;:!?'\x22]",     // URL без завершающей пунктуации
+            r"|(?m:^[ \t]*(?:[-•*]|\d+[.)])[ \t]+)",                  // маркеры списков
+            r"|\b\d+(?:[.,]\d+)?\s?(?:°[CF]|°|%|km/h|m/s|mph|hPa|mm|km|kg|cm|м/с|км/ч|гПа|мм|км|кг|см)",
+            r"|\b[A-Za-z0-9][\w.-]*/[\w.-]*[A-Za-z0-9]\b",             // owner/repo, пути
+            r"|\*\*|__|~~|\*",                                        // выделение markdown
+            r"|[\u{1F000}-\u{1FAFF}\u{2600}-\u{27BF}\u{2B00}-\u{2BFF}\u{FE0F}\u{200D}]+", // эмодзи
+            r"|\n",
+        ))
+        .unwrap()
+    })
+}
+
+/// Разбивает ответ на переводимые и защищённые фрагменты.
+/// Пробелы по краям текста и фрагменты без букв тоже считаются защищёнными.
+pub fn split_segments(text: &str) -> Vec<Segment> {
+    let mut segments = Vec::new();
+    let mut last = 0;
+
+    for m in protected_regex().find_iter(text) {
+        push_text(&mut segments, &text[last..m.start()]);
+        push_protected(&mut segments, m.as_str());
+        last = m.end();
+    }
+    push_text(&mut segments, &text[last..]);
+
+    segments
+}
+
+fn push_text(segments: &mut Vec<Segment>, text: &str) {
+    if text.is_empty() {
+        return;
+    }
+    if !text.chars().any(char::is_alphabetic) {
+        push_protected(segments, text);
+        return;
+    }
+
+    let trimmed_start = text.trim_start();
+    let leading = &text[..text.len() - trimmed_start.len()];
+    let core = trimmed_start.trim_end();
+    let trailing = &trimmed_start[core.len()..];
+
+    push_protected(segments, leading);
+    segments.push(Segment::Text(core.to_string()));
+    push_protected(segments, trailing);
+}
+
+fn push_protected(segments: &mut Vec<Segment>, text: &str) {
+    if text.is_empty() {
+        return;
+    }
+    // Соседние защищённые фрагменты склеиваем
+    if let Some(Segment::Protected(prev)) = segments.last_mut() {
+        prev.push_str(text);
+    } else {
+        segments.push(Segment::Protected(text.to_string()));
+    }
+}
+
+/// Тексты фрагментов, которые нужно перевести (в порядке следования)
+pub fn translatable_texts(segments: &[Segment]) -> Vec<String> {
+    segments
+        .iter()
+        .filter_map(|segment| match segment {
+            Segment::Text(text) => Some(text.clone()),
+            Segment::Protected(_) => None,
+        })
+        .collect()
+}
+
+/// Собирает ответ обратно, подставляя переводы вместо переводимых фрагментов
+pub fn reassemble(segments: &[Segment], translations: &[String]) -> Result<String, String> {
+    let expected = segments.iter().filter(|s| matches!(s, Segment::Text(_))).count();
+    if translations.len() != expected {
+        return Err(format!(
+            "Переводчик вернул {} фрагментов вместо {}",
+            translations.len(),
+            expected
+        ));
+    }
+
+    let mut translations = translations.iter();
+    let mut result = String::new();
+    for segment in segments {
+        match segment {
+            Segment::Protected(text) => result.push_str(text),
+            Segment::Text(_) => result.push_str(translations.next().map(String::as_str).unwrap_or_default()),
+        }
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_code_and_urls_are_protected() {
+        let text = "💻 **Example:**\n```rust\nfn main() {}\n```\nSee https://doc.rust-lang.org/book/. Repo: rust-lang/rust";
+        let segments = split_segments(text);
+        let texts = translatable_texts(&segments);
+
+        assert_eq!(texts, vec!["Example:", "See", ". Repo:"]);
+        assert!(segments.contains(&Segment::Protected("**\n```rust\nfn main() {}\n```\n".to_string())));
+        assert_eq!(reassemble(&segments, &texts).unwrap(), text);
+    }
+
+    #[test]
+    fn test_lists_and_units() {
+        let text = "• Temperature: 21.5°C\n• Wind: 3 m/s\n1. Humidity 40%";
+        let segments = split_segments(text);
+        assert_eq!(translatable_texts(&segments), vec!["Temperature:", "Wind:", "Humidity"]);
+
+        let translated = vec!["Температура:".to_string(), "Ветер:".to_string(), "Влажность".to_string()];
+        assert_eq!(
+            reassemble(&segments, &translated).unwrap(),
+            "• Температура: 21.5°C\n• Ветер: 3 m/s\n1. Влажность 40%"
+        );
+    }
+
+    #[test]
+    fn test_numbers_stay_in_prose() {
+        let text = "In 2015, Rust 1.0 was released.\n• Wind: 3 m/s";
+        let segments = split_segments(text);
+        assert_eq!(translatable_texts(&segments), vec!["In 2015, Rust 1.0 was released.", "Wind:"]);
+    }
+
+    #[test]
+    fn test_markdown_link_text_is_translated() {
+        let segments = split_segments("Read [the docs](https://example.com) first");
+        assert_eq!(translatable_texts(&segments), vec!["Read", "the docs", "first"]);
+    }
+
+    #[test]
+    fn test_reassemble_count_mismatch() {
+        let segments = split_segments("Hello **world**");
+        assert!(reassemble(&segments, &["Привет".to_string()]).is_err());
+    }
+}
@@ -1,3 +1,8 @@
+pub mod code;
 pub mod detect;
+pub mod fuzzy;
+pub mod markup;
+pub mod segment;
 pub mod translator;
+pub mod translit;
 pub mod normalize;
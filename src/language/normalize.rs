@@ -1,6 +1,13 @@
 use regex::Regex;
+use rust_stemmers::{Algorithm, Stemmer};
 use std::sync::OnceLock;
 
+use crate::language::{
+    code::Language,
+    fuzzy::{is_common_word, swap_layout, FuzzyMatcher},
+    segment,
+};
+
 // Ленивая инициализация регулярных выражений для лучшей производительности
 static SPACE_REGEX: OnceLock<Regex> = OnceLock::new();
 static PUNCT_REGEX: OnceLock<Regex> = OnceLock::new();
@@ -53,14 +60,9 @@ pub fn word_count(text: &str) -> usize {
     extract_words(text).len()
 }
 
-/// Обрезает текст до определенного количества слов
+/// Обрезает текст до определенного количества слов, сохраняя пунктуацию
 pub fn truncate_words(text: &str, max_words: usize) -> String {
-    let words = extract_words(text);
-    if words.len() <= max_words {
-        text.to_string()
-    } else {
-        words[..max_words].join(" ") + "..."
-    }
+    segment::truncate_words(text, max_words)
 }
 
 /// Проверяет, содержит ли текст только латиницу
@@ -73,6 +75,162 @@ pub fn contains_cyrillic(text: &str) -> bool {
     text.chars().any(|c| ('\u{0400}'..='\u{04FF}').contains(&c))
 }
 
+/// Сколько результатов поиска (статей, репозиториев, файлов) показывать на одной странице
+pub const SEARCH_PAGE_SIZE: usize = 5;
+
+/// Служебные слова, которые не относятся к теме ни одного поискового запроса
+pub const QUERY_FILLER_WORDS: &[&str] = &[
+    "find", "show", "search", "me", "some", "any", "a", "an", "the", "for", "on", "in", "of", "with", "please",
+];
+
+/// Служебное ли слово запроса (`word` — в нижнем регистре): общее для всех поисков
+/// или из `extra` — слов конкретного поиска
+pub fn is_filler_word(word: &str, extra: &[&str]) -> bool {
+    QUERY_FILLER_WORDS.contains(&word) || extra.contains(&word)
+}
+
+/// Короткая реплика без регистра и завершающих знаков для сравнения со списком
+/// фраз: «Дальше!» → «дальше»
+pub fn normalize_reply(input: &str) -> String {
+    input.trim().trim_end_matches(['.', '!', '?']).trim().to_lowercase()
+}
+
+/// Разбивает текст на токены в нижнем регистре по границам слов.
+/// `+` и `#` внутри слова сохраняются, чтобы `c++` и `c#` оставались токенами.
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !(c.is_alphanumeric() || c == '+' || c == '#'))
+        .map(|token| token.trim_start_matches(['+', '#']))
+        .filter(|token| !token.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Алгоритм стемминга Snowball для языка (есть для EN/RU/DE/FR/ES)
+fn stemming_algorithm(lang: Language) -> Option<Algorithm> {
+    match lang.iso639_1() {
+        "en" => Some(Algorithm::English),
+        "ru" => Some(Algorithm::Russian),
+        "de" => Some(Algorithm::German),
+        "fr" => Some(Algorithm::French),
+        "es" => Some(Algorithm::Spanish),
+        _ => None,
+    }
+}
+
+/// Основа слова. Кириллица всегда стеммится как русский, латиница — как `lang`
+/// (если для него есть стеммер) или как английский.
+pub fn stem_word(word: &str, lang: Option<Language>) -> String {
+    if !word.chars().all(char::is_alphabetic) {
+        return word.to_string();
+    }
+    let algorithm = if contains_cyrillic(word) {
+        Algorithm::Russian
+    } else {
+        lang.filter(|lang| lang.iso639_1() != "ru")
+            .and_then(stemming_algorithm)
+            .unwrap_or(Algorithm::English)
+    };
+    Stemmer::create(algorithm).stem(word).into_owned()
+}
+
+/// Токены текста, приведённые к основам
+pub fn stemmed_tokens(text: &str, lang: Option<Language>) -> Vec<String> {
+    tokenize(text).iter().map(|token| stem_word(token, lang)).collect()
+}
+
+/// Набор ключевых слов и фраз навыка. Сравнивается по основам слов и только по границам
+/// токенов: «погоду» совпадает с «погода», а «hi» не находится внутри «this».
+#[derive(Debug, Clone, Default)]
+pub struct KeywordSet {
+    // исходная фраза, язык стемминга, основы её слов
+    phrases: Vec<(String, Option<Language>, Vec<String>)>,
+}
+
+impl KeywordSet {
+    /// Ключевые слова; язык стемминга выбирается по письменности (кириллица — RU, иначе EN)
+    pub fn new(keywords: &[&str]) -> Self {
+        Self::default().with_keywords(None, keywords)
+    }
+
+    /// Добавляет ключевые слова конкретного языка (например, немецкие — со стеммером DE)
+    pub fn with_language(self, lang: Language, keywords: &[&str]) -> Self {
+        self.with_keywords(Some(lang), keywords)
+    }
+
+    fn with_keywords(mut self, lang: Option<Language>, keywords: &[&str]) -> Self {
+        for keyword in keywords {
+            let stems = stemmed_tokens(keyword, lang);
+            if !stems.is_empty() {
+                self.phrases.push((keyword.to_string(), lang, stems));
+            }
+        }
+        self
+    }
+
+    /// Первое ключевое слово (в порядке объявления), встречающееся в тексте
+    pub fn find(&self, text: &str) -> Option<&str> {
+        let tokens = tokenize(text);
+        let mut stemmed: Vec<(Option<Language>, Vec<String>)> = Vec::new();
+
+        for (keyword, lang, stems) in &self.phrases {
+            let index = match stemmed.iter().position(|(l, _)| l == lang) {
+                Some(index) => index,
+                None => {
+                    stemmed.push((*lang, tokens.iter().map(|t| stem_word(t, *lang)).collect()));
+                    stemmed.len() - 1
+                }
+            };
+            if stemmed[index].1.windows(stems.len()).any(|window| window == stems.as_slice()) {
+                return Some(keyword);
+            }
+        }
+        None
+    }
+
+    /// Встречается ли в тексте хотя бы одно ключевое слово
+    pub fn matches(&self, text: &str) -> bool {
+        self.find(text).is_some()
+    }
+
+    /// Как [`Self::find`], но с допуском опечаток и неверной раскладки
+    /// («wether» → «weather», «ghbdtn» → «привет»). Точные совпадения имеют приоритет.
+    pub fn find_fuzzy(&self, text: &str, matcher: &FuzzyMatcher) -> Option<KeywordMatch<'_>> {
+        if let Some(keyword) = self.find(text) {
+            return Some(KeywordMatch { keyword, corrected: false });
+        }
+
+        let tokens = tokenize(text);
+        for (keyword, lang, stems) in &self.phrases {
+            let token_matches = |token: &String, stem: &String| {
+                let token_stem = stem_word(token, *lang);
+                (!is_common_word(token) && matcher.compare(&token_stem, stem).is_some())
+                    || (matcher.layout_aware && stem_word(&swap_layout(token), *lang) == *stem)
+            };
+            let found = tokens
+                .windows(stems.len())
+                .any(|window| window.iter().zip(stems).all(|(token, stem)| token_matches(token, stem)));
+            if found {
+                return Some(KeywordMatch { keyword, corrected: true });
+            }
+        }
+        None
+    }
+
+    /// Встречается ли ключевое слово с учётом опечаток
+    pub fn matches_fuzzy(&self, text: &str, matcher: &FuzzyMatcher) -> bool {
+        self.find_fuzzy(text, matcher).is_some()
+    }
+}
+
+/// Найденное ключевое слово
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeywordMatch<'a> {
+    pub keyword: &'a str,
+    /// Совпало только с исправлением опечатки или раскладки
+    pub corrected: bool,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -89,6 +247,59 @@ mod tests {
         assert_eq!(word_count("Привет мир"), 2);
     }
 
+    #[test]
+    fn test_query_helpers() {
+        assert_eq!(normalize_reply("  Дальше!! "), "дальше");
+        assert_eq!(normalize_reply("Cite that."), "cite that");
+        assert!(is_filler_word("the", &[]));
+        assert!(is_filler_word("arxiv", &["arxiv"]));
+        assert!(!is_filler_word("rust", &["arxiv"]));
+    }
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(tokenize("Пример на C++, пожалуйста!"), vec!["пример", "на", "c++", "пожалуйста"]);
+        assert_eq!(tokenize("c# vs. go"), vec!["c#", "vs", "go"]);
+    }
+
+    #[test]
+    fn test_stemming() {
+        assert_eq!(stem_word("погоду", None), stem_word("погода", None));
+        assert_eq!(stem_word("examples", None), stem_word("example", None));
+        let german = Language::parse("de");
+        assert_eq!(stem_word("Wetters", german), stem_word("Wetter", german));
+    }
+
+    #[test]
+    fn test_keyword_set() {
+        let keywords = KeywordSet::new(&["погода", "hi", "how to write"])
+            .with_language(Language::parse("es").unwrap(), &["tiempo"]);
+
+        assert_eq!(keywords.find("Какая погода в Москве?"), Some("погода"));
+        assert_eq!(keywords.find("расскажи о погоде"), Some("погода"));
+        assert_eq!(keywords.find("Hi there"), Some("hi"));
+        assert!(!keywords.matches("this is a thing"));
+        assert!(keywords.matches("How to WRITE a parser"));
+        assert!(!keywords.matches("how to read"));
+        assert!(keywords.matches("¿Qué tiempos?"));
+    }
+
+    #[test]
+    fn test_keyword_set_fuzzy() {
+        let matcher = FuzzyMatcher::default();
+        let keywords = KeywordSet::new(&["weather", "привет", "how to write"]);
+
+        let found = keywords.find_fuzzy("wether in londn", &matcher).unwrap();
+        assert_eq!((found.keyword, found.corrected), ("weather", true));
+        assert_eq!(keywords.find_fuzzy("ghbdtn!", &matcher).unwrap().keyword, "привет");
+        assert!(keywords.matches_fuzzy("how to wrte a parser", &matcher));
+        assert!(!keywords.matches_fuzzy("whether or not", &matcher));
+        let thanks = KeywordSet::new(&["thank"]);
+        assert!(!thanks.matches_fuzzy("I think so", &matcher));
+        assert!(!thanks.matches_fuzzy("thinking about it", &matcher));
+        assert!(thanks.matches_fuzzy("thnak you", &matcher));
+    }
+
     #[test]
     fn test_language_detection() {
         assert!(is_latin_only("Hello world"));
@@ -0,0 +1,164 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Многоточие, которое добавляется к обрезанному тексту
+pub const ELLIPSIS: &str = "...";
+
+/// Сокращения, после точки в которых предложение обычно не заканчивается (без точки в конце,
+/// в нижнем регистре). `etc.`, `т.д.`, `т.п.`, `др.` сюда не входят — ими предложения
+/// заканчиваются чаще, чем нет.
+const ABBREVIATIONS: &[&str] = &[
+    // EN
+    "mr", "mrs", "ms", "dr", "prof", "st", "vs", "e.g", "i.e", "cf", "inc", "ltd", "jr", "sr",
+    "fig", "approx", "dept", "est", "vol",
+    // RU
+    "г", "гг", "т.е", "т.к", "т.н", "им", "ул", "д", "стр", "рис", "см", "напр", "проф", "акад",
+    "тыс", "млн", "млрд", "руб", "коп", "в", "вв", "н.э", "ок", "ср",
+];
+
+/// Количество графем (видимых символов): `é`, собранная из двух кодовых точек, и эмодзи
+/// семьи из нескольких считаются одним символом
+pub fn grapheme_count(text: &str) -> usize {
+    text.graphemes(true).count()
+}
+
+/// Обрезает текст до `max_graphemes` видимых символов и добавляет многоточие.
+/// Не режет графемы пополам и по возможности не режет слово: если граница слова есть
+/// во второй половине оставшегося текста, обрезка идёт по ней.
+pub fn truncate(text: &str, max_graphemes: usize) -> String {
+    let Some((cut, _)) = text.grapheme_indices(true).nth(max_graphemes) else {
+        return text.to_string();
+    };
+    let head = &text[..cut];
+
+    let mid_word = head.chars().last().is_some_and(char::is_alphanumeric)
+        && text[cut..].chars().next().is_some_and(char::is_alphanumeric);
+    let head = match head.rfind(char::is_whitespace) {
+        Some(space) if mid_word && space >= head.len() / 2 => &head[..space],
+        _ => head,
+    };
+
+    let head = head.trim_end().trim_end_matches([',', ';', ':', '-', '—']);
+    format!("{}{}", head.trim_end(), ELLIPSIS)
+}
+
+/// Слова текста по правилам Unicode (UAX #29): числа `3.14` и слова с апострофом `don't`
+/// остаются целыми, пунктуация отбрасывается
+pub fn words(text: &str) -> Vec<&str> {
+    text.unicode_words().collect()
+}
+
+/// Оставляет первые `max_words` слов, сохраняя пунктуацию и пробелы исходного текста
+pub fn truncate_words(text: &str, max_words: usize) -> String {
+    let mut count = 0;
+    for (start, segment) in text.split_word_bound_indices() {
+        if !segment.chars().any(char::is_alphanumeric) {
+            continue;
+        }
+        count += 1;
+        if count == max_words {
+            let end = start + segment.len();
+            if text[end..].chars().any(char::is_alphanumeric) {
+                return format!("{}{}", text[..end].trim_end_matches([',', ';', ':']), ELLIPSIS);
+            }
+            break;
+        }
+    }
+    text.to_string()
+}
+
+/// Делит текст на предложения по правилам Unicode (UAX #29) с поправкой на сокращения
+/// (`e.g.`, `Dr.`, `т.е.`, `г. Москва`) и инициалы (`А. С. Пушкин`).
+/// Знаки препинания в конце предложений сохраняются.
+pub fn sentences(text: &str) -> Vec<String> {
+    let mut result: Vec<String> = Vec::new();
+    // Перевод строки — всегда граница, даже после сокращения
+    let mut after_newline = true;
+
+    for raw in text.split_sentence_bounds() {
+        let sentence = raw.trim();
+        if sentence.is_empty() {
+            after_newline |= raw.contains(['\n', '\r']);
+            continue;
+        }
+        match result.last_mut() {
+            Some(previous) if !after_newline && ends_with_abbreviation(previous) => {
+                previous.push(' ');
+                previous.push_str(sentence);
+            }
+            _ => result.push(sentence.to_string()),
+        }
+        after_newline = raw.trim_end_matches([' ', '\t']).ends_with(['\n', '\r']);
+    }
+    result
+}
+
+/// Оканчивается ли фрагмент сокращением или инициалом, после которого предложение продолжается
+fn ends_with_abbreviation(fragment: &str) -> bool {
+    let mut tokens = fragment.split_whitespace().rev();
+    let Some(last) = tokens.next() else {
+        return false;
+    };
+    let Some(word) = last.strip_suffix('.') else {
+        return false;
+    };
+
+    // Инициал: «А.», «J.»
+    let mut chars = word.chars();
+    if let (Some(letter), None) = (chars.next(), chars.next()) {
+        if letter.is_uppercase() {
+            return true;
+        }
+    }
+
+    let word = word.to_lowercase();
+    if !ABBREVIATIONS.contains(&word.as_str()) {
+        return false;
+    }
+    // «в 1799 г.» — год, которым предложение обычно заканчивается; «в г. Москва» — город
+    let after_number = tokens.next().is_some_and(|prev| prev.chars().any(|c| c.is_ascii_digit()));
+    !(after_number && matches!(word.as_str(), "г" | "гг" | "в" | "вв"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate_graphemes() {
+        assert_eq!(truncate("Привет, мир", 6), "Привет...");
+        assert_eq!(truncate("Привет, мир", 50), "Привет, мир");
+        // Эмодзи семьи — одна графема из нескольких кодовых точек
+        assert_eq!(truncate("👨‍👩‍👧 family", 1), "👨‍👩‍👧...");
+        assert_eq!(grapheme_count("e\u{301}té"), 3);
+        // Слово не режется, если есть граница поближе к концу
+        assert_eq!(truncate("Погода в Москве отличная", 18), "Погода в Москве...");
+    }
+
+    #[test]
+    fn test_words() {
+        assert_eq!(words("Pi is 3.14, don't panic!"), vec!["Pi", "is", "3.14", "don't", "panic"]);
+        assert_eq!(truncate_words("Hello, world! How are you?", 2), "Hello, world...");
+        assert_eq!(truncate_words("Привет, мир!", 5), "Привет, мир!");
+    }
+
+    #[test]
+    fn test_sentences_en() {
+        assert_eq!(
+            sentences("Rust is fast, e.g. in benchmarks. Pi is 3.14! Really? Ask Dr. Smith."),
+            vec!["Rust is fast, e.g. in benchmarks.", "Pi is 3.14!", "Really?", "Ask Dr. Smith."]
+        );
+    }
+
+    #[test]
+    fn test_sentences_ru() {
+        assert_eq!(
+            sentences("А. С. Пушкин родился в 1799 г. Он жил в г. Москва, т.е. в столице. Стихи и т.д.\nКонец"),
+            vec![
+                "А. С. Пушкин родился в 1799 г.",
+                "Он жил в г. Москва, т.е. в столице.",
+                "Стихи и т.д.",
+                "Конец",
+            ]
+        );
+    }
+}
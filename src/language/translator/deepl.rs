@@ -0,0 +1,234 @@
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::env;
+
+use super::glossary::{glossary_for, GlossaryFile};
+use super::{Formality, TranslateError, Translator};
+use crate::language::code::{Language, LanguageTag};
+
+const FREE_ENDPOINT: &str = "https://api-free.deepl.com";
+const PRO_ENDPOINT: &str = "https://api.deepl.com";
+
+#[derive(Debug, Deserialize)]
+struct DeepLTranslation {
+    translations: Vec<Translation>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Translation {
+    text: String,
+}
+
+/// Переводчик DeepL (бесплатный или Pro API — определяется по ключу)
+pub struct DeepLTranslator {
+    api_key: String,
+    base_url: String,
+    client: Client,
+    // (исходный язык, целевой язык) → ID глоссария
+    glossaries: HashMap<(String, String), String>,
+}
+
+impl DeepLTranslator {
+    pub fn new(api_key: &str) -> Self {
+        Self {
+            api_key: api_key.to_string(),
+            base_url: endpoint_for_key(api_key).to_string(),
+            client: Client::builder()
+                .timeout(std::time::Duration::from_secs(10)) // Тайм-аут 10 секунд
+                .build()
+                .unwrap_or_default(),
+            glossaries: HashMap::new(),
+        }
+    }
+
+    /// Подключает синхронизированные глоссарии из файла
+    pub fn with_glossaries(mut self, file: &GlossaryFile) -> Self {
+        self.glossaries = file.synced_ids();
+        self
+    }
+
+    /// Создаёт переводчик из `DEEPL_API_KEY`; `None`, если ключ не задан.
+    /// Глоссарии берутся из `DEEPL_GLOSSARY_PATH`, если он задан.
+    pub fn from_env() -> Option<Self> {
+        let translator = match env::var("DEEPL_API_KEY") {
+            Ok(key) if !key.trim().is_empty() => Self::new(key.trim()),
+            _ => return None,
+        };
+        match GlossaryFile::path_from_env().map(|path| GlossaryFile::load(&path)) {
+            Some(Ok(file)) => Some(translator.with_glossaries(&file)),
+            Some(Err(e)) => {
+                println!("⚠️ Глоссарии DeepL не загружены: {}", e);
+                Some(translator)
+            }
+            None => Some(translator),
+        }
+    }
+
+    /// Значение заголовка `Authorization`
+    pub fn auth_header(&self) -> String {
+        format!("DeepL-Auth-Key {}", self.api_key)
+    }
+
+    /// Базовый URL API (для запросов к другим эндпоинтам DeepL)
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+}
+
+/// Ключи бесплатного тарифа DeepL оканчиваются на `:fx`
+fn endpoint_for_key(api_key: &str) -> &'static str {
+    if api_key.ends_with(":fx") {
+        FREE_ENDPOINT
+    } else {
+        PRO_ENDPOINT
+    }
+}
+
+/// DeepL принимает не больше 50 текстов в одном запросе
+const MAX_TEXTS_PER_REQUEST: usize = 50;
+
+impl DeepLTranslator {
+    /// Параметры формы запроса `/v2/translate`: тексты, языки, глоссарий и формальность
+    fn form_params(
+        &self,
+        texts: &[String],
+        source_lang: Option<&str>,
+        target_lang: &str,
+        formality: Formality,
+    ) -> Result<Vec<(&'static str, String)>, TranslateError> {
+        let target = LanguageTag::parse(target_lang)
+            .and_then(|tag| tag.deepl_target())
+            .ok_or_else(|| TranslateError::Unsupported(format!("DeepL не поддерживает язык {}", target_lang)))?;
+        // Неподдерживаемый исходный язык не передаём — DeepL определит его сам
+        let source = source_lang.and_then(Language::parse).and_then(|lang| lang.deepl_source());
+
+        let mut params: Vec<(&str, String)> = texts.iter().map(|text| ("text", text.clone())).collect();
+        if let Some(glossary) = self.glossary_id(source_lang, target_lang) {
+            params.push(("glossary_id", glossary));
+        }
+        if let Some(source) = source {
+            params.push(("source_lang", source));
+        }
+        params.push(("target_lang", target));
+        if formality != Formality::Default {
+            params.push(("formality", formality.deepl_param().to_string()));
+        }
+        Ok(params)
+    }
+
+    /// Один запрос `/v2/translate` с несколькими параметрами `text`
+    async fn request(
+        &self,
+        texts: &[String],
+        source_lang: Option<&str>,
+        target_lang: &str,
+        formality: Formality,
+    ) -> Result<Vec<String>, TranslateError> {
+        let params = self.form_params(texts, source_lang, target_lang, formality)?;
+
+        let response = self
+            .client
+            .post(format!("{}/v2/translate", self.base_url))
+            .header("User-Agent", "Bot-Auraya/1.0")
+            .header("Authorization", self.auth_header())
+            .form(&params)
+            .send()
+            .await
+            .map_err(|e| format!("Запрос DeepL не удался: {}", e))?;
+
+        // Проверяем статус ответа
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(format!("DeepL API ошибка {}: {}", status, error_text).into());
+        }
+
+        let data: DeepLTranslation = response
+            .json()
+            .await
+            .map_err(|e| format!("Ошибка парсинга JSON DeepL: {}", e))?;
+
+        if data.translations.len() != texts.len() {
+            return Err(format!(
+                "DeepL вернул {} переводов вместо {}",
+                data.translations.len(),
+                texts.len()
+            )
+            .into());
+        }
+        Ok(data.translations.into_iter().map(|t| t.text).collect())
+    }
+}
+
+#[async_trait]
+impl Translator for DeepLTranslator {
+    fn name(&self) -> &str {
+        "deepl"
+    }
+
+    /// Глоссарий применяется только при явно заданном исходном языке
+    fn glossary_id(&self, source_lang: Option<&str>, target_lang: &str) -> Option<String> {
+        let source = source_lang.and_then(Language::parse).and_then(|lang| lang.deepl_source())?;
+        let target = LanguageTag::parse(target_lang).and_then(|tag| tag.deepl_target())?;
+        glossary_for(&self.glossaries, &source, &target)
+    }
+
+    async fn translate(&self, text: &str, source_lang: Option<&str>, target_lang: &str) -> Result<String, TranslateError> {
+        self.request(&[text.to_string()], source_lang, target_lang, Formality::Default)
+            .await?
+            .pop()
+            .ok_or_else(|| "DeepL вернул пустой список переводов".to_string().into())
+    }
+
+    async fn translate_batch(
+        &self,
+        texts: &[String],
+        source_lang: Option<&str>,
+        target_lang: &str,
+    ) -> Result<Vec<String>, TranslateError> {
+        self.translate_batch_with(texts, source_lang, target_lang, Formality::Default).await
+    }
+
+    async fn translate_batch_with(
+        &self,
+        texts: &[String],
+        source_lang: Option<&str>,
+        target_lang: &str,
+        formality: Formality,
+    ) -> Result<Vec<String>, TranslateError> {
+        let mut translated = Vec::with_capacity(texts.len());
+        for chunk in texts.chunks(MAX_TEXTS_PER_REQUEST) {
+            translated.extend(self.request(chunk, source_lang, target_lang, formality).await?);
+        }
+        Ok(translated)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_endpoint_for_key() {
+        assert_eq!(endpoint_for_key("abc-123:fx"), FREE_ENDPOINT);
+        assert_eq!(endpoint_for_key("abc-123"), PRO_ENDPOINT);
+    }
+
+    #[test]
+    fn test_glossary_param() {
+        let mut translator = DeepLTranslator::new("abc-123:fx");
+        translator.glossaries.insert(("EN".to_string(), "RU".to_string()), "g-ru".to_string());
+        let texts = vec!["Add the crate".to_string()];
+
+        let params = translator.form_params(&texts, Some("EN"), "RU", Formality::Less).unwrap();
+        assert!(params.contains(&("glossary_id", "g-ru".to_string())));
+        assert!(params.contains(&("source_lang", "EN".to_string())));
+        assert!(params.contains(&("formality", "prefer_less".to_string())));
+
+        // Без исходного языка DeepL не принимает глоссарий
+        let params = translator.form_params(&texts, None, "RU", Formality::Default).unwrap();
+        assert!(!params.iter().any(|(name, _)| *name == "glossary_id"));
+    }
+}
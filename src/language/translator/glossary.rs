@@ -0,0 +1,229 @@
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::env;
+
+use super::deepl::DeepLTranslator;
+use crate::language::code::Language;
+
+/// Глоссарий для одной языковой пары: термины, которые нельзя переводить дословно
+/// (названия крейтов, продукта, внутренний жаргон)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Glossary {
+    pub source_lang: String,
+    pub target_lang: String,
+    pub entries: BTreeMap<String, String>,
+    /// ID глоссария в DeepL (заполняется при синхронизации)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub glossary_id: Option<String>,
+    /// Хэш записей на момент синхронизации: если записи изменились, ID устарел
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entries_hash: Option<String>,
+}
+
+impl Glossary {
+    /// Записи в формате TSV для DeepL (табуляции и переводы строк в терминах недопустимы)
+    pub fn to_tsv(&self) -> Result<String, String> {
+        let mut lines = Vec::with_capacity(self.entries.len());
+        for (source, target) in &self.entries {
+            let (source, target) = (source.trim(), target.trim());
+            if source.is_empty() || target.is_empty() || format!("{}{}", source, target).contains(['\t', '\n', '\r']) {
+                return Err(format!("Некорректная запись глоссария «{}» → «{}»", source, target));
+            }
+            lines.push(format!("{}\t{}", source, target));
+        }
+        Ok(lines.join("\n"))
+    }
+
+    /// Хэш языковой пары и записей
+    pub fn digest(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.pair_key().0.as_bytes());
+        hasher.update([0]);
+        hasher.update(self.pair_key().1.as_bytes());
+        for (source, target) in &self.entries {
+            hasher.update([0]);
+            hasher.update(source.trim().as_bytes());
+            hasher.update([b'\t']);
+            hasher.update(target.trim().as_bytes());
+        }
+        hex::encode(hasher.finalize())
+    }
+
+    /// Синхронизирован ли глоссарий с DeepL (есть ID и записи с тех пор не менялись)
+    pub fn is_synced(&self) -> bool {
+        self.glossary_id.is_some() && self.entries_hash.as_deref() == Some(self.digest().as_str())
+    }
+
+    /// Языковая пара в виде кодов `EN`, `RU` (регион у глоссариев DeepL не указывается)
+    fn pair_key(&self) -> (String, String) {
+        (language_key(&self.source_lang), language_key(&self.target_lang))
+    }
+}
+
+fn language_key(code: &str) -> String {
+    Language::parse(code).map(|lang| lang.code()).unwrap_or_else(|| code.trim().to_uppercase())
+}
+
+/// Локальный файл глоссариев (`DEEPL_GLOSSARY_PATH`):
+/// `{"glossaries": [{"source_lang": "EN", "target_lang": "RU", "entries": {"crate": "крейт"}}]}`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GlossaryFile {
+    pub glossaries: Vec<Glossary>,
+}
+
+impl GlossaryFile {
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        serde_json::from_str(json).map_err(|e| format!("Ошибка разбора файла глоссариев: {}", e))
+    }
+
+    pub fn load(path: &str) -> Result<Self, String> {
+        let json = std::fs::read_to_string(path).map_err(|e| format!("Не удалось прочитать {}: {}", path, e))?;
+        Self::from_json(&json)
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(path, json + "\n").map_err(|e| format!("Не удалось записать {}: {}", path, e))
+    }
+
+    /// Путь к файлу глоссариев из окружения
+    pub fn path_from_env() -> Option<String> {
+        env::var("DEEPL_GLOSSARY_PATH").ok().map(|path| path.trim().to_string()).filter(|path| !path.is_empty())
+    }
+
+    /// ID синхронизированных глоссариев по языковым парам. Несинхронизированные пропускаются:
+    /// с устаревшим ID DeepL применил бы старые термины.
+    pub fn synced_ids(&self) -> HashMap<(String, String), String> {
+        let mut ids = HashMap::new();
+        for glossary in &self.glossaries {
+            match &glossary.glossary_id {
+                Some(id) if glossary.is_synced() => {
+                    ids.insert(glossary.pair_key(), id.clone());
+                }
+                _ => println!(
+                    "⚠️ Глоссарий {}→{} не синхронизирован с DeepL (запустите с --sync-glossaries)",
+                    glossary.source_lang, glossary.target_lang
+                ),
+            }
+        }
+        ids
+    }
+}
+
+/// ID глоссария для языковой пары перевода
+pub fn glossary_for(ids: &HashMap<(String, String), String>, source_lang: &str, target_lang: &str) -> Option<String> {
+    ids.get(&(language_key(source_lang), language_key(target_lang))).cloned()
+}
+
+#[derive(Debug, Deserialize)]
+struct CreatedGlossary {
+    glossary_id: String,
+}
+
+/// Создаёт в DeepL глоссарии, записи которых изменились, удаляет устаревшие и записывает
+/// новые ID обратно в файл. Глоссарии DeepL неизменяемы, поэтому обновление — это пересоздание.
+/// Возвращает количество обновлённых глоссариев.
+pub async fn sync_glossaries(path: &str) -> Result<usize, String> {
+    let deepl = DeepLTranslator::from_env().ok_or("DEEPL_API_KEY не задан")?;
+    let mut file = GlossaryFile::load(path)?;
+    let client = Client::builder()
+        .timeout(std::time::Duration::from_secs(30))
+        .build()
+        .map_err(|e| format!("Ошибка создания HTTP клиента: {}", e))?;
+
+    let mut updated = 0;
+    for glossary in file.glossaries.iter_mut().filter(|g| !g.is_synced()) {
+        let (source, target) = glossary.pair_key();
+        let entries = glossary.to_tsv()?;
+
+        if let Some(old_id) = glossary.glossary_id.take() {
+            let response = client
+                .delete(format!("{}/v2/glossaries/{}", deepl.base_url(), old_id))
+                .header("Authorization", deepl.auth_header())
+                .send()
+                .await
+                .map_err(|e| format!("Запрос к DeepL не удался: {}", e))?;
+            // 404 — глоссарий уже удалён, это не ошибка
+            if !response.status().is_success() && response.status().as_u16() != 404 {
+                println!("⚠️ Не удалось удалить глоссарий {}: {}", old_id, response.status());
+            }
+        }
+
+        let response = client
+            .post(format!("{}/v2/glossaries", deepl.base_url()))
+            .header("Authorization", deepl.auth_header())
+            .json(&serde_json::json!({
+                "name": format!("auraya-{}-{}", source, target),
+                "source_lang": source,
+                "target_lang": target,
+                "entries": entries,
+                "entries_format": "tsv",
+            }))
+            .send()
+            .await
+            .map_err(|e| format!("Запрос к DeepL не удался: {}", e))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            // Сохраняем то, что уже успели синхронизировать
+            file.save(path)?;
+            return Err(format!("DeepL не создал глоссарий {}→{}: {} {}", source, target, status, error_text));
+        }
+
+        let created: CreatedGlossary = response
+            .json()
+            .await
+            .map_err(|e| format!("Ошибка парсинга JSON DeepL: {}", e))?;
+        println!("📘 Глоссарий {}→{} синхронизирован: {}", source, target, created.glossary_id);
+        glossary.glossary_id = Some(created.glossary_id);
+        glossary.entries_hash = Some(glossary.digest());
+        updated += 1;
+    }
+
+    file.save(path)?;
+    Ok(updated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FILE: &str = r#"{"glossaries": [
+        {"source_lang": "en", "target_lang": "ru", "entries": {"crate": "крейт", "Auraya": "Auraya"}},
+        {"source_lang": "EN", "target_lang": "DE", "entries": {"crate": "Crate"}, "glossary_id": "g-de", "entries_hash": "stale"}
+    ]}"#;
+
+    #[test]
+    fn test_tsv_and_sync_state() {
+        let mut file = GlossaryFile::from_json(FILE).unwrap();
+        assert_eq!(file.glossaries[0].to_tsv().unwrap(), "Auraya\tAuraya\ncrate\tкрейт");
+        assert!(!file.glossaries[0].is_synced());
+        // Записи изменились после синхронизации — ID не используется
+        assert!(file.synced_ids().is_empty());
+
+        let glossary = &mut file.glossaries[0];
+        glossary.glossary_id = Some("g-ru".to_string());
+        glossary.entries_hash = Some(glossary.digest());
+        let ids = file.synced_ids();
+        assert_eq!(glossary_for(&ids, "EN", "RU").as_deref(), Some("g-ru"));
+        assert_eq!(glossary_for(&ids, "en", "ru").as_deref(), Some("g-ru"));
+        assert_eq!(glossary_for(&ids, "EN", "DE"), None);
+    }
+
+    #[test]
+    fn test_invalid_entry() {
+        let mut entries = BTreeMap::new();
+        entries.insert("a\tb".to_string(), "c".to_string());
+        let glossary = Glossary {
+            source_lang: "EN".to_string(),
+            target_lang: "RU".to_string(),
+            entries,
+            glossary_id: None,
+            entries_hash: None,
+        };
+        assert!(glossary.to_tsv().is_err());
+    }
+}
@@ -0,0 +1,109 @@
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::env;
+
+use super::{TranslateError, Translator};
+use crate::language::code::Language;
+
+#[derive(Debug, Serialize)]
+struct LibreRequest<'a> {
+    q: &'a str,
+    source: String,
+    target: String,
+    format: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    api_key: Option<&'a str>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LibreResponse {
+    #[serde(rename = "translatedText")]
+    translated_text: String,
+}
+
+/// Переводчик с API, совместимым с LibreTranslate (можно развернуть у себя)
+pub struct LibreTranslator {
+    base_url: String,
+    api_key: Option<String>,
+    client: Client,
+}
+
+impl LibreTranslator {
+    pub fn new(base_url: &str, api_key: Option<String>) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key,
+            client: Client::builder()
+                .timeout(std::time::Duration::from_secs(15))
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Создаёт переводчик из `LIBRETRANSLATE_URL` (и необязательного `LIBRETRANSLATE_API_KEY`)
+    pub fn from_env() -> Option<Self> {
+        let url = env::var("LIBRETRANSLATE_URL").ok().filter(|u| !u.trim().is_empty())?;
+        let api_key = env::var("LIBRETRANSLATE_API_KEY").ok().filter(|k| !k.trim().is_empty());
+        Some(Self::new(url.trim(), api_key))
+    }
+}
+
+/// LibreTranslate использует коды ISO 639-1 в нижнем регистре
+fn libre_code(lang: &str) -> String {
+    match Language::parse(lang) {
+        Some(language) => language.iso639_1().to_string(),
+        None => lang.split('-').next().unwrap_or(lang).to_lowercase(),
+    }
+}
+
+#[async_trait]
+impl Translator for LibreTranslator {
+    fn name(&self) -> &str {
+        "libretranslate"
+    }
+
+    async fn translate(&self, text: &str, source_lang: Option<&str>, target_lang: &str) -> Result<String, TranslateError> {
+        let request = LibreRequest {
+            q: text,
+            source: source_lang.map(libre_code).unwrap_or_else(|| "auto".to_string()),
+            target: libre_code(target_lang),
+            format: "text",
+            api_key: self.api_key.as_deref(),
+        };
+
+        let response = self
+            .client
+            .post(format!("{}/translate", self.base_url))
+            .header("User-Agent", "Bot-Auraya/1.0")
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| format!("Запрос LibreTranslate не удался: {}", e))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(format!("LibreTranslate ошибка {}: {}", status, error_text).into());
+        }
+
+        let data: LibreResponse = response
+            .json()
+            .await
+            .map_err(|e| format!("Ошибка парсинга JSON LibreTranslate: {}", e))?;
+
+        Ok(data.translated_text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_libre_code() {
+        assert_eq!(libre_code("EN-GB"), "en");
+        assert_eq!(libre_code("RU"), "ru");
+        assert_eq!(libre_code("rus"), "ru");
+    }
+}
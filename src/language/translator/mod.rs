@@ -0,0 +1,684 @@
+pub mod deepl;
+pub mod glossary;
+pub mod libre;
+pub mod phrasebook;
+
+use async_trait::async_trait;
+use std::env;
+use std::fmt;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use super::{
+    detect::detect_language,
+    markup::{reassemble, split_segments, translatable_texts},
+};
+use crate::storage::translation_memory::{translation_memory, TranslationKey, TranslationMemory};
+use deepl::DeepLTranslator;
+use libre::LibreTranslator;
+use phrasebook::PhrasebookTranslator;
+
+/// После стольких ошибок подряд бэкенд временно исключается из цепочки
+const FAILURE_THRESHOLD: u32 = 3;
+/// Базовая пауза для «сломанного» бэкенда (удваивается с каждой новой серией ошибок)
+const BASE_COOLDOWN: Duration = Duration::from_secs(30);
+/// Максимальная пауза
+const MAX_COOLDOWN: Duration = Duration::from_secs(15 * 60);
+
+static DEFAULT_CHAIN: OnceLock<TranslatorChain> = OnceLock::new();
+
+/// Формальность перевода: обращение на «вы» или на «ты»
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Formality {
+    #[default]
+    Default,
+    More,
+    Less,
+}
+
+impl Formality {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Formality::Default => "default",
+            Formality::More => "more",
+            Formality::Less => "less",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "default" | "auto" | "авто" => Some(Formality::Default),
+            "more" | "formal" | "prefer_more" | "вы" | "официально" => Some(Formality::More),
+            "less" | "informal" | "prefer_less" | "ты" | "неформально" => Some(Formality::Less),
+            _ => None,
+        }
+    }
+
+    /// Значение параметра `formality` для DeepL. Используются варианты `prefer_*`: для языков
+    /// без формальности (английский, китайский) DeepL их игнорирует, а `more`/`less` — ошибка.
+    pub fn deepl_param(&self) -> &'static str {
+        match self {
+            Formality::Default => "default",
+            Formality::More => "prefer_more",
+            Formality::Less => "prefer_less",
+        }
+    }
+
+    /// Следующее значение по кругу (для кнопки-переключателя)
+    pub fn next(&self) -> Self {
+        match self {
+            Formality::Default => Formality::More,
+            Formality::More => Formality::Less,
+            Formality::Less => Formality::Default,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Formality::Default => "по умолчанию",
+            Formality::More => "на «вы»",
+            Formality::Less => "на «ты»",
+        }
+    }
+
+    /// Формальность по умолчанию для развёртывания (`TRANSLATION_FORMALITY`)
+    pub fn from_env() -> Self {
+        match env::var("TRANSLATION_FORMALITY") {
+            Ok(value) if !value.trim().is_empty() => Formality::parse(&value).unwrap_or_else(|| {
+                println!("⚠️ Неизвестное значение TRANSLATION_FORMALITY «{}», используем default", value.trim());
+                Formality::Default
+            }),
+            _ => Formality::Default,
+        }
+    }
+
+    /// Значение для ключа памяти переводов (`None` — формальность по умолчанию)
+    fn cache_tag(&self) -> Option<&'static str> {
+        (*self != Formality::Default).then(|| self.as_str())
+    }
+}
+
+/// Ошибка бэкенда перевода
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TranslateError {
+    /// Бэкенд не переводит такой текст или язык (например, фразы нет в разговорнике).
+    /// Это не сбой: на здоровье бэкенда не влияет.
+    Unsupported(String),
+    /// Сбой бэкенда: сеть, ошибка API, неожиданный ответ
+    Failed(String),
+}
+
+impl fmt::Display for TranslateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TranslateError::Unsupported(message) | TranslateError::Failed(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl From<String> for TranslateError {
+    fn from(message: String) -> Self {
+        TranslateError::Failed(message)
+    }
+}
+
+/// Бэкенд перевода
+#[async_trait]
+pub trait Translator: Send + Sync {
+    /// Имя бэкенда для логов и статистики
+    fn name(&self) -> &str;
+
+    /// Переводит текст на `target_lang` (`EN`, `RU`, ...); `source_lang` — `None` для автоопределения
+    async fn translate(&self, text: &str, source_lang: Option<&str>, target_lang: &str) -> Result<String, TranslateError>;
+
+    /// Переводит несколько фрагментов; бэкенды с пакетным API переопределяют это одним запросом
+    async fn translate_batch(
+        &self,
+        texts: &[String],
+        source_lang: Option<&str>,
+        target_lang: &str,
+    ) -> Result<Vec<String>, TranslateError> {
+        let mut translated = Vec::with_capacity(texts.len());
+        for text in texts {
+            translated.push(self.translate(text, source_lang, target_lang).await?);
+        }
+        Ok(translated)
+    }
+
+    /// Пакетный перевод с заданной формальностью; бэкенды без её поддержки формальность игнорируют
+    async fn translate_batch_with(
+        &self,
+        texts: &[String],
+        source_lang: Option<&str>,
+        target_lang: &str,
+        _formality: Formality,
+    ) -> Result<Vec<String>, TranslateError> {
+        self.translate_batch(texts, source_lang, target_lang).await
+    }
+
+    /// ID глоссария, который бэкенд применит к этой языковой паре (`None` — без глоссария)
+    fn glossary_id(&self, _source_lang: Option<&str>, _target_lang: &str) -> Option<String> {
+        None
+    }
+}
+
+/// Состояние здоровья бэкенда
+#[derive(Debug, Clone, Default)]
+pub struct BackendHealth {
+    pub successes: u64,
+    pub failures: u64,
+    pub consecutive_failures: u32,
+    pub last_error: Option<String>,
+    pub disabled_until: Option<Instant>,
+    trips: u32, // сколько раз бэкенд уже отключался — для экспоненциальной паузы
+}
+
+impl BackendHealth {
+    /// Доступен ли бэкенд сейчас
+    pub fn is_available(&self, now: Instant) -> bool {
+        self.disabled_until.is_none_or(|until| now >= until)
+    }
+
+    fn record_success(&mut self) {
+        self.successes += 1;
+        self.consecutive_failures = 0;
+        self.disabled_until = None;
+        self.trips = 0;
+    }
+
+    fn record_failure(&mut self, error: &str, now: Instant) {
+        self.failures += 1;
+        self.consecutive_failures += 1;
+        self.last_error = Some(error.to_string());
+
+        if self.consecutive_failures >= FAILURE_THRESHOLD {
+            let cooldown = BASE_COOLDOWN
+                .saturating_mul(1 << self.trips.min(10))
+                .min(MAX_COOLDOWN);
+            self.disabled_until = Some(now + cooldown);
+            self.trips += 1;
+            self.consecutive_failures = 0;
+        }
+    }
+}
+
+/// Цепочка переводчиков с переключением на следующий при ошибке
+pub struct TranslatorChain {
+    backends: Vec<(Box<dyn Translator>, Mutex<BackendHealth>)>,
+    memory: Option<&'static TranslationMemory>,
+    formality: Formality,
+}
+
+impl TranslatorChain {
+    pub fn new(backends: Vec<Box<dyn Translator>>) -> Self {
+        Self {
+            backends: backends
+                .into_iter()
+                .map(|backend| (backend, Mutex::new(BackendHealth::default())))
+                .collect(),
+            memory: None,
+            formality: Formality::Default,
+        }
+    }
+
+    /// Формальность по умолчанию (пользователь может переопределить её в настройках)
+    pub fn with_formality(mut self, formality: Formality) -> Self {
+        self.formality = formality;
+        self
+    }
+
+    /// Подключает память переводов для [`Self::translate_markup`]
+    pub fn with_memory(mut self, memory: &'static TranslationMemory) -> Self {
+        self.memory = Some(memory);
+        self
+    }
+
+    /// Цепочка из переменных окружения: DeepL (если есть `DEEPL_API_KEY`),
+    /// LibreTranslate (если есть `LIBRETRANSLATE_URL`) и офлайн-разговорник
+    pub fn from_env() -> Self {
+        let mut backends: Vec<Box<dyn Translator>> = Vec::new();
+
+        match DeepLTranslator::from_env() {
+            Some(deepl) => backends.push(Box::new(deepl)),
+            None => println!("⚠️ DEEPL_API_KEY не найден или пуст - DeepL отключен"),
+        }
+        if let Some(libre) = LibreTranslator::from_env() {
+            backends.push(Box::new(libre));
+        }
+        match PhrasebookTranslator::from_env() {
+            Ok(phrasebook) => backends.push(Box::new(phrasebook)),
+            Err(e) => println!("⚠️ Разговорник не загружен: {}", e),
+        }
+
+        Self::new(backends)
+            .with_memory(translation_memory())
+            .with_formality(Formality::from_env())
+    }
+
+    /// Переводит текст первым доступным бэкендом; при ошибке пробует следующий
+    pub async fn translate(&self, text: &str, source_lang: Option<&str>, target_lang: &str) -> Result<String, String> {
+        self.translate_with(text, source_lang, target_lang, None).await
+    }
+
+    /// Как [`Self::translate`], но с формальностью пользователя (`None` — по умолчанию для развёртывания)
+    pub async fn translate_with(
+        &self,
+        text: &str,
+        source_lang: Option<&str>,
+        target_lang: &str,
+        formality: Option<Formality>,
+    ) -> Result<String, String> {
+        let mut translated = self
+            .translate_batch_with(&[text.to_string()], source_lang, target_lang, formality)
+            .await?;
+        translated.pop().ok_or_else(|| "Переводчик вернул пустой ответ".to_string())
+    }
+
+    /// Как [`Self::translate_with`], но исходный язык определяется по тексту
+    pub async fn translate_detected(&self, text: &str, target_lang: &str, formality: Option<Formality>) -> Result<String, String> {
+        let source_lang = detect_language(text);
+        self.translate_with(text, source_lang.as_deref(), target_lang, formality).await
+    }
+
+    /// Переводит пакет фрагментов одним бэкендом (с переключением на следующий при ошибке)
+    pub async fn translate_batch(
+        &self,
+        texts: &[String],
+        source_lang: Option<&str>,
+        target_lang: &str,
+    ) -> Result<Vec<String>, String> {
+        self.translate_batch_with(texts, source_lang, target_lang, None).await
+    }
+
+    /// Как [`Self::translate_batch`], но с формальностью пользователя
+    pub async fn translate_batch_with(
+        &self,
+        texts: &[String],
+        source_lang: Option<&str>,
+        target_lang: &str,
+        formality: Option<Formality>,
+    ) -> Result<Vec<String>, String> {
+        let formality = formality.unwrap_or(self.formality);
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut errors = Vec::new();
+
+        for (backend, health) in &self.backends {
+            if !health.lock().unwrap().is_available(Instant::now()) {
+                continue;
+            }
+
+            let result = backend
+                .translate_batch_with(texts, source_lang, target_lang, formality)
+                .await
+                .and_then(|translated| {
+                    if translated.len() == texts.len() {
+                        Ok(translated)
+                    } else {
+                        Err(format!("вернул {} фрагментов вместо {}", translated.len(), texts.len()).into())
+                    }
+                });
+
+            match result {
+                Ok(translated) => {
+                    health.lock().unwrap().record_success();
+                    return Ok(translated);
+                }
+                // Бэкенд исправен, просто не переводит этот текст — пробуем следующий
+                Err(TranslateError::Unsupported(e)) => {
+                    errors.push(format!("{}: {}", backend.name(), e));
+                }
+                Err(TranslateError::Failed(e)) => {
+                    println!("⚠️ Переводчик {} не сработал: {}", backend.name(), e);
+                    health.lock().unwrap().record_failure(&e, Instant::now());
+                    errors.push(format!("{}: {}", backend.name(), e));
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Err("Нет доступных переводчиков".to_string())
+        } else {
+            Err(errors.join("; "))
+        }
+    }
+
+    /// Переводит отформатированный ответ, не трогая код, ссылки, числа с единицами и разметку.
+    /// Фрагменты ищутся в памяти переводов, остальные уходят одним пакетом.
+    pub async fn translate_markup(&self, text: &str, source_lang: Option<&str>, target_lang: &str) -> Result<String, String> {
+        self.translate_markup_with(text, source_lang, target_lang, None).await
+    }
+
+    /// Как [`Self::translate_markup`], но с формальностью пользователя
+    pub async fn translate_markup_with(
+        &self,
+        text: &str,
+        source_lang: Option<&str>,
+        target_lang: &str,
+        formality: Option<Formality>,
+    ) -> Result<String, String> {
+        self.translate_markup_inner(text, source_lang, target_lang, formality, true).await
+    }
+
+    /// Как [`Self::translate_markup_with`], но мимо памяти переводов: для ответов, которые
+    /// цитируют сообщение пользователя — его текст не должен попасть в общую память
+    pub async fn translate_markup_private(
+        &self,
+        text: &str,
+        source_lang: Option<&str>,
+        target_lang: &str,
+        formality: Option<Formality>,
+    ) -> Result<String, String> {
+        self.translate_markup_inner(text, source_lang, target_lang, formality, false).await
+    }
+
+    async fn translate_markup_inner(
+        &self,
+        text: &str,
+        source_lang: Option<&str>,
+        target_lang: &str,
+        formality: Option<Formality>,
+        use_memory: bool,
+    ) -> Result<String, String> {
+        let segments = split_segments(text);
+        let texts = translatable_texts(&segments);
+        if texts.is_empty() {
+            return Ok(text.to_string());
+        }
+        let formality = formality.unwrap_or(self.formality);
+        let translated = if use_memory {
+            self.translate_batch_cached(&texts, source_lang, target_lang, formality).await?
+        } else {
+            self.translate_batch_with(&texts, source_lang, target_lang, Some(formality)).await?
+        };
+        reassemble(&segments, &translated)
+    }
+
+    /// Пакетный перевод через память переводов. Только для ответов бота, в которых нет
+    /// текста пользователя: такие ответы переводятся через [`Self::translate_markup_private`].
+    async fn translate_batch_cached(
+        &self,
+        texts: &[String],
+        source_lang: Option<&str>,
+        target_lang: &str,
+        formality: Formality,
+    ) -> Result<Vec<String>, String> {
+        let Some(memory) = self.memory else {
+            return self.translate_batch_with(texts, source_lang, target_lang, Some(formality)).await;
+        };
+
+        // ID глоссария меняется при каждой синхронизации изменённых терминов,
+        // так что переводы со старыми терминами из памяти больше не достаются
+        let glossary = self.glossary_id(source_lang, target_lang);
+        let keys: Vec<TranslationKey> = texts
+            .iter()
+            .map(|text| {
+                TranslationKey::new(text, source_lang, target_lang, formality.cache_tag()).with_glossary(glossary.as_deref())
+            })
+            .collect();
+        let mut results = Vec::with_capacity(texts.len());
+        for key in &keys {
+            results.push(memory.get(key).await);
+        }
+
+        let missing: Vec<usize> = (0..texts.len()).filter(|&i| results[i].is_none()).collect();
+        if !missing.is_empty() {
+            let pending: Vec<String> = missing.iter().map(|&i| texts[i].clone()).collect();
+            let translated = self
+                .translate_batch_with(&pending, source_lang, target_lang, Some(formality))
+                .await?;
+            for (i, translation) in missing.into_iter().zip(translated) {
+                memory.put(&keys[i], &translation).await;
+                results[i] = Some(translation);
+            }
+        }
+
+        Ok(results.into_iter().map(Option::unwrap_or_default).collect())
+    }
+
+    /// Глоссарий первого бэкенда, который его применяет к этой языковой паре
+    fn glossary_id(&self, source_lang: Option<&str>, target_lang: &str) -> Option<String> {
+        self.backends
+            .iter()
+            .find_map(|(backend, _)| backend.glossary_id(source_lang, target_lang))
+    }
+
+    /// Снимок состояния всех бэкендов: (имя, здоровье)
+    pub fn health(&self) -> Vec<(String, BackendHealth)> {
+        self.backends
+            .iter()
+            .map(|(backend, health)| (backend.name().to_string(), health.lock().unwrap().clone()))
+            .collect()
+    }
+}
+
+/// Глобальная цепочка переводчиков (настраивается из окружения один раз)
+pub fn default_chain() -> &'static TranslatorChain {
+    DEFAULT_CHAIN.get_or_init(TranslatorChain::from_env)
+}
+
+/// Перевод текста на указанный язык (`EN`, `RU`, `FR`, и т.д.); `formality` — `None` для
+/// формальности по умолчанию. Исходный язык определяется по тексту, чтобы DeepL применил
+/// глоссарий этой языковой пары; если язык не определился, перевод идёт без глоссария.
+pub async fn translate_text(text: &str, target_lang: &str, formality: Option<Formality>) -> Result<String, String> {
+    default_chain().translate_detected(text, target_lang, formality).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    struct Failing(Arc<AtomicUsize>);
+
+    #[async_trait]
+    impl Translator for Failing {
+        fn name(&self) -> &str {
+            "failing"
+        }
+
+        async fn translate(&self, _: &str, _: Option<&str>, _: &str) -> Result<String, TranslateError> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Err(TranslateError::Failed("недоступен".to_string()))
+        }
+    }
+
+    struct Echo;
+
+    #[async_trait]
+    impl Translator for Echo {
+        fn name(&self) -> &str {
+            "echo"
+        }
+
+        async fn translate(&self, text: &str, _: Option<&str>, target_lang: &str) -> Result<String, TranslateError> {
+            Ok(format!("[{}] {}", target_lang, text))
+        }
+    }
+
+    /// Как Echo, но считает переведённые фрагменты
+    struct Counting(Arc<AtomicUsize>);
+
+    #[async_trait]
+    impl Translator for Counting {
+        fn name(&self) -> &str {
+            "counting"
+        }
+
+        async fn translate(&self, text: &str, _: Option<&str>, target_lang: &str) -> Result<String, TranslateError> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Ok(format!("[{}] {}", target_lang, text))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failover_to_next_backend() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let chain = TranslatorChain::new(vec![Box::new(Failing(calls.clone())), Box::new(Echo)]);
+
+        assert_eq!(chain.translate("hi", None, "RU").await.unwrap(), "[RU] hi");
+        let health = chain.health();
+        assert_eq!(health[0].1.failures, 1);
+        assert_eq!(health[1].1.successes, 1);
+    }
+
+    #[tokio::test]
+    async fn test_unhealthy_backend_is_skipped() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let chain = TranslatorChain::new(vec![Box::new(Failing(calls.clone())), Box::new(Echo)]);
+
+        for _ in 0..5 {
+            chain.translate("hi", None, "RU").await.unwrap();
+        }
+        // После FAILURE_THRESHOLD ошибок подряд бэкенд на паузе
+        assert_eq!(calls.load(Ordering::SeqCst), FAILURE_THRESHOLD as usize);
+        assert!(!chain.health()[0].1.is_available(Instant::now()));
+    }
+
+    /// Не знает ни одной фразы, как разговорник для произвольного текста
+    struct Unsupported(Arc<AtomicUsize>);
+
+    #[async_trait]
+    impl Translator for Unsupported {
+        fn name(&self) -> &str {
+            "unsupported"
+        }
+
+        async fn translate(&self, _: &str, _: Option<&str>, _: &str) -> Result<String, TranslateError> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Err(TranslateError::Unsupported("фразы нет в разговорнике".to_string()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unsupported_input_keeps_backend_healthy() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let chain = TranslatorChain::new(vec![Box::new(Unsupported(calls.clone()))]);
+
+        for _ in 0..5 {
+            assert!(chain.translate("quantum chromodynamics", None, "RU").await.is_err());
+        }
+        // Непереводимые фразы — не сбой: бэкенд не уходит на паузу
+        assert_eq!(calls.load(Ordering::SeqCst), 5);
+        let health = &chain.health()[0].1;
+        assert_eq!(health.failures, 0);
+        assert!(health.is_available(Instant::now()));
+    }
+
+    #[tokio::test]
+    async fn test_translate_markup_keeps_code() {
+        let chain = TranslatorChain::new(vec![Box::new(Echo)]);
+        let translated = chain
+            .translate_markup("**Example:**\n```\nlet x = 1;\n```", Some("EN"), "RU")
+            .await
+            .unwrap();
+        assert_eq!(translated, "**[RU] Example:**\n```\nlet x = 1;\n```");
+    }
+
+    #[tokio::test]
+    async fn test_translate_markup_uses_memory() {
+        let memory: &'static TranslationMemory = Box::leak(Box::new(TranslationMemory::in_memory(chrono::Duration::days(1))));
+        let calls = Arc::new(AtomicUsize::new(0));
+        let chain = TranslatorChain::new(vec![Box::new(Counting(calls.clone()))]).with_memory(memory);
+
+        chain.translate_markup("Hello **world**", Some("EN"), "RU").await.unwrap();
+        let second = chain.translate_markup("**Hello** world", Some("EN"), "RU").await.unwrap();
+
+        assert_eq!(second, "**[RU] Hello** [RU] world");
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert_eq!(memory.stats().hits, 2);
+    }
+
+    #[tokio::test]
+    async fn test_private_markup_skips_memory() {
+        let memory: &'static TranslationMemory = Box::leak(Box::new(TranslationMemory::in_memory(chrono::Duration::days(1))));
+        let chain = TranslatorChain::new(vec![Box::new(Echo)]).with_memory(memory);
+
+        let translated = chain.translate_markup_private("Cannot evaluate: **my secret**", Some("EN"), "RU", None).await.unwrap();
+        assert_eq!(translated, "[RU] Cannot evaluate: **[RU] my secret**");
+        let stats = memory.stats();
+        assert_eq!((stats.hits, stats.misses, stats.stores), (0, 0, 0));
+    }
+
+    /// Помечает перевод формальностью, которую передала цепочка
+    struct FormalityAware;
+
+    #[async_trait]
+    impl Translator for FormalityAware {
+        fn name(&self) -> &str {
+            "formality"
+        }
+
+        async fn translate(&self, text: &str, _: Option<&str>, _: &str) -> Result<String, TranslateError> {
+            Ok(text.to_string())
+        }
+
+        async fn translate_batch_with(
+            &self,
+            texts: &[String],
+            _: Option<&str>,
+            _: &str,
+            formality: Formality,
+        ) -> Result<Vec<String>, TranslateError> {
+            Ok(texts.iter().map(|text| format!("[{}] {}", formality.as_str(), text)).collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_formality_default_and_override() {
+        let memory: &'static TranslationMemory = Box::leak(Box::new(TranslationMemory::in_memory(chrono::Duration::days(1))));
+        let chain = TranslatorChain::new(vec![Box::new(FormalityAware)])
+            .with_memory(memory)
+            .with_formality(Formality::More);
+
+        assert_eq!(chain.translate("Hello", Some("EN"), "RU").await.unwrap(), "[more] Hello");
+        assert_eq!(chain.translate_with("Hello", Some("EN"), "RU", Some(Formality::Less)).await.unwrap(), "[less] Hello");
+
+        // Переводы с разной формальностью кэшируются отдельно
+        chain.translate_markup("Hello", Some("EN"), "RU").await.unwrap();
+        let informal = chain.translate_markup_with("Hello", Some("EN"), "RU", Some(Formality::Less)).await.unwrap();
+        assert_eq!(informal, "[less] Hello");
+    }
+
+    #[test]
+    fn test_formality_parse() {
+        assert_eq!(Formality::parse("вы"), Some(Formality::More));
+        assert_eq!(Formality::parse("informal"), Some(Formality::Less));
+        assert_eq!(Formality::parse("sometimes"), None);
+        assert_eq!(Formality::Less.deepl_param(), "prefer_less");
+    }
+
+    /// Помечает перевод исходным языком, который передала цепочка
+    struct SourceAware;
+
+    #[async_trait]
+    impl Translator for SourceAware {
+        fn name(&self) -> &str {
+            "source"
+        }
+
+        async fn translate(&self, text: &str, source_lang: Option<&str>, _: &str) -> Result<String, TranslateError> {
+            Ok(format!("[{}] {}", source_lang.unwrap_or("auto"), text))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_translate_detected_passes_source() {
+        // Исходный язык нужен DeepL, чтобы применить глоссарий
+        let chain = TranslatorChain::new(vec![Box::new(SourceAware)]);
+        let translated = chain
+            .translate_detected("Add the crate to your dependencies and build the project", "RU", None)
+            .await
+            .unwrap();
+        assert!(translated.starts_with("[EN] "));
+    }
+
+    #[tokio::test]
+    async fn test_all_backends_fail() {
+        let chain = TranslatorChain::new(vec![Box::new(Failing(Arc::new(AtomicUsize::new(0))))]);
+        let err = chain.translate("hi", None, "RU").await.unwrap_err();
+        assert!(err.contains("failing"));
+    }
+}
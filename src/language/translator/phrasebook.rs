@@ -0,0 +1,137 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::env;
+
+use super::{TranslateError, Translator};
+use crate::language::code::Language;
+use crate::language::normalize::normalize;
+
+/// Встроенный разговорник (можно заменить своим файлом через `PHRASEBOOK_PATH`)
+const BUILTIN_PHRASEBOOK: &str = include_str!("../../../data/phrasebook.json");
+
+#[derive(Debug, Deserialize)]
+struct PhrasebookFile {
+    phrases: Vec<HashMap<String, String>>,
+}
+
+/// Офлайн-переводчик по разговорнику: переводит только известные фразы целиком.
+/// Последнее звено цепочки, когда сетевые переводчики недоступны.
+pub struct PhrasebookTranslator {
+    // нормализованная фраза → индекс записи
+    index: HashMap<String, usize>,
+    phrases: Vec<HashMap<String, String>>,
+}
+
+impl PhrasebookTranslator {
+    /// Загружает разговорник из JSON вида `{"phrases": [{"EN": "...", "RU": "..."}, ...]}`
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let file: PhrasebookFile =
+            serde_json::from_str(json).map_err(|e| format!("Ошибка разбора разговорника: {}", e))?;
+
+        let phrases: Vec<HashMap<String, String>> = file
+            .phrases
+            .into_iter()
+            .map(|entry| entry.into_iter().map(|(lang, text)| (lang.to_uppercase(), text)).collect())
+            .collect();
+
+        let mut index = HashMap::new();
+        for (i, entry) in phrases.iter().enumerate() {
+            for text in entry.values() {
+                // Первая запись побеждает: «Hi» и «Hello» оба дают «Привет», но обратно — «Hello»
+                index.entry(phrase_key(text)).or_insert(i);
+            }
+        }
+
+        Ok(Self { index, phrases })
+    }
+
+    /// Встроенный разговорник
+    pub fn builtin() -> Self {
+        Self::from_json(BUILTIN_PHRASEBOOK).expect("встроенный разговорник некорректен")
+    }
+
+    /// Разговорник из `PHRASEBOOK_PATH` или встроенный
+    pub fn from_env() -> Result<Self, String> {
+        match env::var("PHRASEBOOK_PATH") {
+            Ok(path) if !path.trim().is_empty() => {
+                let json = std::fs::read_to_string(path.trim())
+                    .map_err(|e| format!("Не удалось прочитать {}: {}", path, e))?;
+                Self::from_json(&json)
+            }
+            _ => Ok(Self::builtin()),
+        }
+    }
+
+    /// Переводит одну строку, сохраняя завершающую пунктуацию
+    fn translate_line(&self, line: &str, target_lang: &str) -> Option<String> {
+        let trimmed = line.trim();
+        let body = trimmed.trim_end_matches(['.', '!', '?', ',', '…']);
+        let punctuation = &trimmed[body.len()..];
+
+        let entry = &self.phrases[*self.index.get(&phrase_key(body))?];
+        let target = Language::parse(target_lang)?.code();
+        let translated = entry.get(&target)?;
+        Some(format!("{}{}", translated, punctuation))
+    }
+}
+
+/// Ключ поиска: нижний регистр, схлопнутые пробелы, без завершающей пунктуации
+fn phrase_key(text: &str) -> String {
+    normalize(text)
+        .trim_end_matches(['.', '!', '?', ',', '…'])
+        .trim()
+        .to_string()
+}
+
+#[async_trait]
+impl Translator for PhrasebookTranslator {
+    fn name(&self) -> &str {
+        "phrasebook"
+    }
+
+    async fn translate(&self, text: &str, _source_lang: Option<&str>, target_lang: &str) -> Result<String, TranslateError> {
+        // Каждая непустая строка должна быть известной фразой — частичный перевод хуже, чем никакого
+        let mut lines = Vec::new();
+        for line in text.lines() {
+            if line.trim().is_empty() {
+                lines.push(String::new());
+                continue;
+            }
+            match self.translate_line(line, target_lang) {
+                Some(translated) => lines.push(translated),
+                None => {
+                    return Err(TranslateError::Unsupported(format!("Фраза «{}» отсутствует в разговорнике", line.trim())))
+                }
+            }
+        }
+
+        if lines.iter().all(|l| l.is_empty()) {
+            return Err(TranslateError::Unsupported("Пустой текст".to_string()));
+        }
+        Ok(lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_builtin_phrases() {
+        let phrasebook = PhrasebookTranslator::builtin();
+        assert_eq!(phrasebook.translate("Hello!", None, "RU").await.unwrap(), "Привет!");
+        assert_eq!(phrasebook.translate("спасибо", None, "EN").await.unwrap(), "Thank you");
+        assert_eq!(phrasebook.translate("Thank you\nGoodbye", None, "de-AT").await.unwrap(), "Danke\nAuf Wiedersehen");
+    }
+
+    #[tokio::test]
+    async fn test_unknown_phrase_fails() {
+        let phrasebook = PhrasebookTranslator::builtin();
+        assert!(matches!(
+            phrasebook.translate("Hello, quantum chromodynamics", None, "RU").await,
+            Err(TranslateError::Unsupported(_))
+        ));
+        assert!(phrasebook.translate("Hello", None, "JA").await.is_err());
+    }
+}
@@ -0,0 +1,414 @@
+use super::normalize::contains_cyrillic;
+
+/// Схема транслитерации кириллицы латиницей
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Scheme {
+    /// BGN/PCGN — географические названия (Moskva, Yekaterinburg, Nizhniy Novgorod)
+    #[default]
+    Bgn,
+    /// ICAO Doc 9303 (ГОСТ Р 52535.1) — как в загранпаспортах (Iuliia, Ekaterinburg)
+    Icao,
+}
+
+fn is_vowel(c: char) -> bool {
+    "аеёиоуыэюяaeiouy".contains(c)
+}
+
+fn is_consonant(c: char) -> bool {
+    c.is_alphabetic() && !is_vowel(c) && !"ьъй".contains(c)
+}
+
+/// Латинское написание одной (строчной) буквы; `prev` — предыдущая буква слова
+fn latin_letter(c: char, prev: Option<char>, scheme: Scheme) -> Option<&'static str> {
+    // В BGN/PCGN «е» и «ё» в начале слова и после гласных, й, ь, ъ пишутся с «y»
+    let iotated = prev.is_none_or(|p| is_vowel(p) || "йьъ".contains(p));
+    let latin = match (c, scheme) {
+        ('а', _) => "a",
+        ('б', _) => "b",
+        ('в', _) => "v",
+        ('г', _) => "g",
+        ('д', _) => "d",
+        ('е', Scheme::Bgn) if iotated => "ye",
+        ('е', _) => "e",
+        ('ё', Scheme::Bgn) if prev.is_some_and(|p| "жчшщ".contains(p)) => "o",
+        ('ё', Scheme::Bgn) => "yo",
+        ('ё', Scheme::Icao) => "e",
+        ('ж', _) => "zh",
+        ('з', _) => "z",
+        ('и', _) => "i",
+        ('й', Scheme::Bgn) => "y",
+        ('й', Scheme::Icao) => "i",
+        ('к', _) => "k",
+        ('л', _) => "l",
+        ('м', _) => "m",
+        ('н', _) => "n",
+        ('о', _) => "o",
+        ('п', _) => "p",
+        ('р', _) => "r",
+        ('с', _) => "s",
+        ('т', _) => "t",
+        ('у', _) => "u",
+        ('ф', _) => "f",
+        ('х', _) => "kh",
+        ('ц', _) => "ts",
+        ('ч', _) => "ch",
+        ('ш', _) => "sh",
+        ('щ', _) => "shch",
+        ('ъ', Scheme::Bgn) => "",
+        ('ъ', Scheme::Icao) => "ie",
+        ('ы', _) => "y",
+        ('ь', _) => "",
+        ('э', _) => "e",
+        ('ю', Scheme::Bgn) => "yu",
+        ('ю', Scheme::Icao) => "iu",
+        ('я', Scheme::Bgn) => "ya",
+        ('я', Scheme::Icao) => "ia",
+        // Украинские и белорусские буквы
+        ('і', _) => "i",
+        ('ї', _) => "yi",
+        ('є', _) => "ye",
+        ('ґ', _) => "g",
+        ('ў', _) => "w",
+        _ => return None,
+    };
+    Some(latin)
+}
+
+/// Транслитерирует кириллицу латиницей; остальные символы не меняются.
+/// Регистр сохраняется: `Щука` → `Shchuka`, `ЩУКА` → `SHCHUKA`.
+pub fn to_latin(text: &str, scheme: Scheme) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut result = String::with_capacity(text.len());
+    let mut prev: Option<char> = None;
+
+    for (i, &c) in chars.iter().enumerate() {
+        let lower = c.to_lowercase().next().unwrap_or(c);
+        match latin_letter(lower, prev, scheme) {
+            Some(latin) if c.is_uppercase() => {
+                // Слово целиком заглавными — и буквосочетание заглавными
+                let all_caps = chars.get(i + 1).is_some_and(|n| n.is_uppercase())
+                    || (i > 0 && chars[i - 1].is_uppercase() && !chars.get(i + 1).is_some_and(|n| n.is_lowercase()));
+                if all_caps {
+                    result.push_str(&latin.to_uppercase());
+                } else {
+                    let mut letters = latin.chars();
+                    if let Some(first) = letters.next() {
+                        result.extend(first.to_uppercase());
+                        result.push_str(letters.as_str());
+                    }
+                }
+            }
+            Some(latin) => result.push_str(latin),
+            None => result.push(c),
+        }
+        prev = c.is_alphabetic().then_some(lower);
+    }
+    result
+}
+
+/// Буквосочетания латиницы, от длинных к коротким
+const LATIN_DIGRAPHS: &[(&str, &str)] = &[
+    ("shch", "щ"),
+    ("zh", "ж"),
+    ("kh", "х"),
+    ("ts", "ц"),
+    ("ch", "ч"),
+    ("sh", "ш"),
+    ("yu", "ю"),
+    ("ya", "я"),
+    ("yo", "ё"),
+    ("ye", "е"),
+    ("iy", "ий"),
+    ("yy", "ый"),
+];
+
+/// Обратная транслитерация латиницы кириллицей (по BGN/PCGN, с типичными вольностями вроде
+/// `x` → `кс`). Однозначной она быть не может — годится для сопоставления со словарями
+/// (`moskva` → `москва`), а не для показа пользователю.
+pub fn to_cyrillic(text: &str) -> String {
+    let lower = text.to_lowercase();
+    let chars: Vec<char> = lower.chars().collect();
+    let mut result = String::with_capacity(text.len() * 2);
+    let mut i = 0;
+
+    'outer: while i < chars.len() {
+        for (latin, cyrillic) in LATIN_DIGRAPHS {
+            let len = latin.len();
+            if chars.len() >= i + len && chars[i..i + len].iter().copied().eq(latin.chars()) {
+                result.push_str(cyrillic);
+                i += len;
+                continue 'outer;
+            }
+        }
+
+        let c = chars[i];
+        let prev = i.checked_sub(1).map(|p| chars[p]);
+        let cyrillic = match c {
+            'a' => "а",
+            'b' => "б",
+            'c' => "к",
+            'd' => "д",
+            'e' if prev.is_none_or(|p| !p.is_alphabetic()) => "э",
+            'e' => "е",
+            'f' => "ф",
+            'g' => "г",
+            'h' => "х",
+            'i' => "и",
+            'j' => "дж",
+            'k' => "к",
+            'l' => "л",
+            'm' => "м",
+            'n' => "н",
+            'o' => "о",
+            'p' => "п",
+            'q' => "к",
+            'r' => "р",
+            's' => "с",
+            't' => "т",
+            'u' => "у",
+            'v' | 'w' => "в",
+            'x' => "кс",
+            // «y» после гласной — «й» (Tolstoy), иначе «ы»
+            'y' if prev.is_some_and(is_vowel) => "й",
+            'y' => "ы",
+            'z' => "з",
+            '\'' => "ь",
+            _ => {
+                result.push(c);
+                i += 1;
+                continue;
+            }
+        };
+        result.push_str(cyrillic);
+        i += 1;
+    }
+    result
+}
+
+/// Основы, которые в предложном падеже на «-е» почти всегда мужского рода
+/// (в Омске, Волгограде, Берлине, Париже), в отличие от «в Москве», «в Ялте»
+const MASCULINE_STEM_ENDINGS: &[&str] = &["к", "г", "д", "ов", "ев", "ёв", "ин", "ын", "он", "ан", "ен", "ж", "ш", "ч", "ль"];
+
+/// Окончания косвенных падежей существительных и варианты именительного падежа
+/// (первый вариант — самый вероятный)
+const NOUN_ENDINGS: &[(&str, &[&str])] = &[
+    ("ью", &["ь"]),
+    ("ии", &["ия", "ий"]),
+    ("ой", &["ой", "а"]),
+    ("ей", &["ей", "я", "ь"]),
+    ("ом", &[""]),
+    ("ём", &["ь", ""]),
+    ("ем", &["", "ь", "й"]),
+    ("е", &["", "а", "я", "ь"]),
+    ("и", &["ь", "а", "я", "и"]),
+    ("ы", &["а", "ы"]),
+    ("у", &["а", ""]),
+    ("ю", &["я", "ь"]),
+    ("а", &["а", ""]),
+    ("я", &["я", "ь", "й"]),
+];
+
+/// Окончания прилагательных в названиях (в Нижнем Новгороде, Красной Поляне)
+const ADJECTIVE_ENDINGS: &[(&str, &str)] = &[
+    ("ого", "ый"),
+    ("ому", "ый"),
+    ("ом", "ый"),
+    ("ым", "ый"),
+    ("его", "ий"),
+    ("ему", "ий"),
+    ("ем", "ий"),
+    ("им", "ий"),
+    ("ой", "ая"),
+    ("ую", "ая"),
+    ("ей", "яя"),
+    ("юю", "яя"),
+];
+
+/// Возможные формы именительного падежа для русского существительного (имени собственного),
+/// от самой вероятной к наименее вероятной. Само слово всегда среди вариантов:
+/// несклоняемые названия (Сочи, Осло) остаются как есть.
+pub fn nominative_candidates(word: &str) -> Vec<String> {
+    let lower = word.to_lowercase();
+    let mut candidates: Vec<String> = Vec::new();
+    let mut push = |candidate: String| {
+        if candidate.chars().count() >= 2 && !candidates.contains(&candidate) {
+            candidates.push(candidate);
+        }
+    };
+
+    if let Some((ending, replacements)) = NOUN_ENDINGS.iter().find(|(ending, _)| lower.ends_with(ending)) {
+        let stem = &lower[..lower.len() - ending.len()];
+
+        if *ending == "е" && stem.chars().last().is_some_and(is_vowel) {
+            // в Китае → Китай
+            push(format!("{}й", stem));
+        } else if *ending == "е" && !MASCULINE_STEM_ENDINGS.iter().any(|e| stem.ends_with(e)) {
+            // в Москве, в Ялте → сначала женский род
+            push(format!("{}а", stem));
+        }
+        // После гласной это уже не окончание: Германия, Осло
+        if stem.chars().last().is_some_and(is_consonant) {
+            for replacement in replacements.iter() {
+                push(format!("{}{}", stem, replacement));
+            }
+        }
+    }
+    push(lower.clone());
+    if candidates.is_empty() {
+        candidates.push(lower);
+    }
+    candidates
+}
+
+fn adjective_nominative(word: &str) -> String {
+    let lower = word.to_lowercase();
+    match ADJECTIVE_ENDINGS.iter().find(|(ending, _)| lower.ends_with(ending)) {
+        Some((ending, nominative)) => {
+            let stem = &lower[..lower.len() - ending.len()];
+            // После г, к, х, ж, ш, ч, щ пишется «-ий»: Великий, а не Великый
+            let nominative = if *nominative == "ый" && stem.ends_with(['г', 'к', 'х', 'ж', 'ш', 'ч', 'щ']) {
+                "ий"
+            } else {
+                nominative
+            };
+            format!("{}{}", stem, nominative)
+        }
+        None => lower,
+    }
+}
+
+/// Варианты именительного падежа для названия из нескольких слов: определения перед
+/// последним словом считаются прилагательными, в составных через дефис склоняется только
+/// первая часть (Ростове-на-Дону → Ростов-на-Дону) или последняя (Санкт-Петербурге).
+pub fn name_candidates(name: &str) -> Vec<String> {
+    let name = name.trim().to_lowercase();
+    let words: Vec<&str> = name.split_whitespace().collect();
+    let Some((last, prefix)) = words.split_last() else {
+        return Vec::new();
+    };
+
+    let prefix: Vec<String> = prefix.iter().map(|word| adjective_nominative(word)).collect();
+    let last_variants = match last.split_once("-на-") {
+        Some((head, tail)) => nominative_candidates(head)
+            .into_iter()
+            .map(|head| format!("{}-на-{}", head, tail))
+            .collect(),
+        None => match last.rsplit_once('-') {
+            Some((head, tail)) => nominative_candidates(tail)
+                .into_iter()
+                .map(|tail| format!("{}-{}", head, tail))
+                .collect(),
+            None => nominative_candidates(last),
+        },
+    };
+
+    let mut candidates: Vec<String> = last_variants
+        .into_iter()
+        .map(|last| prefix.iter().cloned().chain(std::iter::once(last)).collect::<Vec<_>>().join(" "))
+        .collect();
+    if !candidates.contains(&name) {
+        candidates.push(name);
+    }
+    candidates
+}
+
+/// Приводит название к именительному падежу. Если какой-то вариант есть в словаре `known`,
+/// возвращается написание из словаря; иначе — самый вероятный вариант.
+/// Слова без кириллицы не меняются.
+pub fn to_nominative(name: &str, known: &[&str]) -> String {
+    if !contains_cyrillic(name) {
+        return name.trim().to_string();
+    }
+    let candidates = name_candidates(name);
+
+    // Сначала само слово: «Сочи», «Рига» склонять не нужно
+    let lowered = name.trim().to_lowercase();
+    let ordered = std::iter::once(&lowered).chain(candidates.iter());
+    for candidate in ordered {
+        if let Some(entry) = known.iter().find(|k| k.to_lowercase() == *candidate) {
+            return entry.to_string();
+        }
+    }
+
+    let best = candidates.into_iter().next().unwrap_or(lowered);
+    if name.trim().starts_with(char::is_uppercase) {
+        capitalize_words(&best)
+    } else {
+        best
+    }
+}
+
+/// Заглавная буква в начале каждого слова и части через дефис (кроме предлогов «на», «де»)
+pub fn capitalize_words(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut at_start = true;
+    let mut rest = text;
+
+    while let Some(c) = rest.chars().next() {
+        if at_start && c.is_alphabetic() {
+            let word_end = rest.find(|ch: char| ch.is_whitespace() || ch == '-').unwrap_or(rest.len());
+            let word = &rest[..word_end];
+            if matches!(word, "на" | "de" | "on" | "upon") {
+                result.push_str(word);
+            } else {
+                result.extend(c.to_uppercase());
+                result.push_str(&word[c.len_utf8()..]);
+            }
+            rest = &rest[word_end..];
+            at_start = false;
+            continue;
+        }
+        at_start = c.is_whitespace() || c == '-';
+        result.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_latin_schemes() {
+        assert_eq!(to_latin("Москва", Scheme::Bgn), "Moskva");
+        assert_eq!(to_latin("Екатеринбург", Scheme::Bgn), "Yekaterinburg");
+        assert_eq!(to_latin("Екатеринбург", Scheme::Icao), "Ekaterinburg");
+        assert_eq!(to_latin("Нижний Новгород", Scheme::Bgn), "Nizhniy Novgorod");
+        assert_eq!(to_latin("Юлия", Scheme::Icao), "Iuliia");
+        assert_eq!(to_latin("ЩУКА и Щука", Scheme::Bgn), "SHCHUKA i Shchuka");
+        assert_eq!(to_latin("Лев Толстой, 1828", Scheme::Bgn), "Lev Tolstoy, 1828");
+    }
+
+    #[test]
+    fn test_to_cyrillic() {
+        assert_eq!(to_cyrillic("moskva"), "москва");
+        assert_eq!(to_cyrillic("Yekaterinburg"), "екатеринбург");
+        assert_eq!(to_cyrillic("Tolstoy"), "толстой");
+        assert_eq!(to_cyrillic("Nizhniy Novgorod"), "нижний новгород");
+    }
+
+    #[test]
+    fn test_nominative_candidates() {
+        assert_eq!(nominative_candidates("москве")[0], "москва");
+        assert_eq!(nominative_candidates("омске")[0], "омск");
+        assert_eq!(nominative_candidates("казани")[0], "казань");
+        assert_eq!(nominative_candidates("москвы")[0], "москва");
+        assert_eq!(nominative_candidates("китае")[0], "китай");
+        assert_eq!(nominative_candidates("германии")[0], "германия");
+        assert!(nominative_candidates("сочи").contains(&"сочи".to_string()));
+    }
+
+    #[test]
+    fn test_to_nominative() {
+        let known = ["Москва", "Сочи", "Нижний Новгород", "Ростов-на-Дону", "Санкт-Петербург"];
+        assert_eq!(to_nominative("москве", &known), "Москва");
+        assert_eq!(to_nominative("сочи", &known), "Сочи");
+        assert_eq!(to_nominative("нижнем новгороде", &known), "Нижний Новгород");
+        assert_eq!(to_nominative("ростове-на-дону", &known), "Ростов-на-Дону");
+        assert_eq!(to_nominative("санкт-петербурге", &known), "Санкт-Петербург");
+        assert_eq!(to_nominative("Ялте", &[]), "Ялта");
+        assert_eq!(to_nominative("Лев Толстой", &[]), "Лев Толстой");
+        assert_eq!(to_nominative("london", &known), "london");
+    }
+}
@@ -1,16 +1,48 @@
-use crate::knowledge::github::search_github_repos;
+use std::sync::OnceLock;
+
+use crate::{
+    knowledge::{
+        citation::Citation,
+        github::{
+            client::GithubError,
+            code_search::{format_snippets, search_code, CodeQuery},
+            format_repository, search_github_repos, RepoQuery, RepoSort,
+        },
+    },
+    language::{
+        fuzzy::{default_matcher, FuzzyMatcher},
+        normalize::{tokenize, KeywordSet},
+    },
+};
+
+/// Ключевые слова запросов о коде
+fn code_keywords() -> &'static KeywordSet {
+    static KEYWORDS: OnceLock<KeywordSet> = OnceLock::new();
+    KEYWORDS.get_or_init(|| {
+        KeywordSet::new(&[
+            "example in", "пример на", "код на", "code in", "how to write",
+            "show me", "покажи", "найди код", "examples for", "tutorial",
+            "learn", "изучить", "syntax", "синтаксис"
+        ])
+    })
+}
+
+/// Названия языков, совпадающие с обычными словами
+const AMBIGUOUS_LANGUAGES: &[&str] = &["go", "swift", "r"];
+
+/// Слова, по которым понятно, что речь о программировании
+fn programming_context() -> &'static KeywordSet {
+    static KEYWORDS: OnceLock<KeywordSet> = OnceLock::new();
+    KEYWORDS.get_or_init(|| {
+        KeywordSet::new(&[
+            "code", "coding", "programming", "program", "language", "snippet", "function",
+            "library", "compiler", "script", "example", "код", "программирование", "язык", "пример"
+        ])
+    })
+}
 
 /// Определяет, относится ли вопрос к коду, и извлекает язык программирования
 pub fn detect_code_query(input: &str) -> Option<String> {
-    let input_lower = input.to_lowercase();
-    
-    // Расширенные ключевые слова для поиска
-    let keywords = [
-        "example in", "пример на", "код на", "code in", "how to write",
-        "show me", "покажи", "найди код", "examples for", "tutorial",
-        "learn", "изучить", "syntax", "синтаксис"
-    ];
-
     // Популярные языки программирования
     let languages = [
         "rust", "python", "javascript", "java", "c++", "cpp", "c#", "csharp",
@@ -18,41 +50,42 @@ pub fn detect_code_query(input: &str) -> Option<String> {
         "haskell", "clojure", "dart", "r", "matlab", "perl", "lua",
         "assembly", "bash", "powershell", "sql", "html", "css"
     ];
+    // Язык программирования по слову, с исправлением опечаток («pyhton» → python)
+    let language_of = |token: &str| -> Option<String> {
+        let normalized = normalize_language_name(token);
+        if languages.contains(&normalized.as_str()) {
+            return Some(normalized);
+        }
+        // Короткие названия (swift, scala) легко спутать с обычными словами — только с 6 букв
+        let matcher = FuzzyMatcher { one_typo_min_len: 6, ..default_matcher().clone() };
+        matcher
+            .best_match(token, &languages)
+            .map(|m| normalize_language_name(m.candidate))
+    };
+
+    // Сравниваем по целым словам: «r» не должно находиться внутри любого слова
+    let tokens = tokenize(input);
 
     // Проверяем наличие ключевых слов
-    let has_keywords = keywords.iter().any(|kw| input_lower.contains(kw));
-    
-    if has_keywords {
-        // Ищем язык программирования после ключевых слов
-        let parts: Vec<&str> = input_lower.split_whitespace().collect();
-        for (i, part) in parts.iter().enumerate() {
-            if part == &"на" || part == &"in" || part == &"for" {
-                if let Some(lang) = parts.get(i + 1) {
-                    let normalized_lang = normalize_language_name(lang);
-                    if languages.contains(&normalized_lang.as_str()) {
-                        return Some(normalized_lang);
-                    }
+    if code_keywords().matches_fuzzy(input, default_matcher()) {
+        // Ищем язык программирования после предлогов
+        for (i, token) in tokens.iter().enumerate() {
+            if matches!(token.as_str(), "на" | "in" | "for") {
+                if let Some(lang) = tokens.get(i + 1).and_then(|t| language_of(t)) {
+                    return Some(lang);
                 }
             }
         }
-        
-        // Если не нашли язык после предлогов, ищем в любом месте
-        for word in parts {
-            let normalized = normalize_language_name(word);
-            if languages.contains(&normalized.as_str()) {
-                return Some(normalized);
-            }
-        }
-    }
-    
-    // Прямое упоминание языка без ключевых слов
-    for lang in &languages {
-        if input_lower.contains(lang) {
-            return Some(normalize_language_name(lang));
-        }
     }
 
-    None
+    // Язык в любом месте запроса (в том числе без ключевых слов). Названия-омонимы (go, swift, r)
+    // считаются языком только рядом со словами о программировании: «let's go home»
+    // и «a swift answer» — не запросы о коде
+    let in_context = programming_context().matches(input);
+    tokens
+        .iter()
+        .filter(|token| in_context || !AMBIGUOUS_LANGUAGES.contains(&token.as_str()))
+        .find_map(|token| language_of(token))
 }
 
 /// Нормализует название языка программирования
@@ -69,36 +102,71 @@ fn normalize_language_name(lang: &str) -> String {
     }
 }
 
+/// Ответ на вопрос о коде и источники, из которых он собран
+#[derive(Debug, Clone, PartialEq)]
+pub struct CodeAnswer {
+    pub text: String,
+    pub sources: Vec<Citation>,
+}
+
 /// Ищет примеры кода на GitHub по указанному языку
-pub async fn fetch_code_examples(language: &str) -> Result<String, String> {
+pub async fn fetch_code_examples(language: &str) -> Result<CodeAnswer, GithubError> {
     let normalized_lang = normalize_language_name(language);
-    let query = format!("{} example tutorial", normalized_lang);
+    let query = RepoQuery {
+        language: Some(normalized_lang.clone()),
+        sort: RepoSort::Stars,
+        ..RepoQuery::new("example tutorial")
+    };
     
-    println!("🔍 Поиск примеров кода: {}", query);
+    println!("🔍 Поиск примеров кода: {}", query.to_query_string());
     
-    let results = search_github_repos(&query, 5).await
-        .map_err(|e| format!("Ошибка поиска в GitHub: {}", e))?;
+    let results = search_github_repos(&query).await?.repositories;
     
     if results.is_empty() {
-        return Ok(format!("❌ Примеры кода для **{}** не найдены.\n\nПопробуйте поискать:\n• Tutorials\n• Documentation\n• Stack Overflow", 
-                         normalized_lang.to_uppercase()));
+        let text = format!("❌ Примеры кода для **{}** не найдены.\n\nПопробуйте поискать:\n• Tutorials\n• Documentation\n• Stack Overflow", 
+                         normalized_lang.to_uppercase());
+        return Ok(CodeAnswer { text, sources: Vec::new() });
     }
     
     let mut formatted = format!("🚀 **Примеры кода на {}:**\n\n", normalized_lang.to_uppercase());
     
-    for (i, (name, desc, url)) in results.iter().enumerate() {
-        formatted.push_str(&format!(
-            "{}. 📂 **{}**\n   💡 {}\n   🔗 {}\n\n",
-            i + 1,
-            name,
-            if desc.is_empty() { "Нет описания" } else { desc },
-            url
-        ));
+    for (i, repo) in results.iter().enumerate() {
+        formatted.push_str(&format!("{}. {}\n\n", i + 1, format_repository(repo)));
     }
     
     formatted.push_str("💡 **Совет:** Изучите README файлы в этих репозиториях для лучшего понимания!");
     
-    Ok(formatted)
+    let sources = results.iter().map(|repo| Citation::from_repo(&repo.name, &repo.url)).collect();
+    Ok(CodeAnswer { text: formatted, sources })
+}
+
+/// Отвечает на вопрос о коде («how to read a file in Rust») фрагментами из поиска кода GitHub.
+/// Если искать нечего, кроме языка, поиск кода недоступен (нет `GITHUB_TOKEN`) или ничего
+/// не нашёл — список репозиториев с примерами, как [`fetch_code_examples`].
+pub async fn fetch_code_snippets(input: &str, language: &str) -> Result<CodeAnswer, GithubError> {
+    let normalized_lang = normalize_language_name(language);
+    let query = CodeQuery::parse(input, Some(&normalized_lang));
+    if query.is_empty() {
+        return fetch_code_examples(language).await;
+    }
+
+    println!("🔍 Поиск кода: {}", query.to_query_string());
+    match search_code(&query).await {
+        Ok(search) if !search.snippets.is_empty() => Ok(CodeAnswer {
+            text: format_snippets(&search, &query),
+            sources: search.snippets.iter().map(Citation::from_snippet).collect(),
+        }),
+        Ok(_) => {
+            println!("⚠️ Поиск кода ничего не нашёл, ищем репозитории с примерами");
+            fetch_code_examples(language).await
+        }
+        // Об исчерпанном лимите сообщаем пользователю, а не подменяем поиском репозиториев
+        Err(e @ GithubError::RateLimited { .. }) => Err(e),
+        Err(e) => {
+            println!("⚠️ Поиск кода недоступен ({}), ищем репозитории с примерами", e);
+            fetch_code_examples(language).await
+        }
+    }
 }
 
 /// Генерирует простой пример кода для популярных языков
@@ -1,6 +1,17 @@
 use reqwest::Client;
 use serde::Deserialize;
 use std::env;
+use std::sync::OnceLock;
+
+use crate::{
+    core::settings::{UserSettings, Units, Verbosity},
+    language::{
+        code::Language,
+        fuzzy::{default_matcher, did_you_mean},
+        normalize::{contains_cyrillic, KeywordSet},
+        translit::{to_cyrillic, to_latin, to_nominative, Scheme},
+    },
+};
 
 #[derive(Debug, Deserialize)]
 struct WeatherResponse {
@@ -9,6 +20,11 @@ struct WeatherResponse {
     name: String,
     sys: Option<WeatherSys>,
     wind: Option<WeatherWind>,
+    #[serde(default)]
+    clouds: Option<WeatherClouds>,
+    /// Видимость в метрах (не больше 10 км)
+    #[serde(default)]
+    visibility: Option<u32>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -36,21 +52,71 @@ struct WeatherSys {
 #[derive(Debug, Deserialize)]
 struct WeatherWind {
     speed: f64,
+    #[serde(default)]
+    deg: Option<u16>,
+    #[serde(default)]
+    gust: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WeatherClouds {
+    all: u8,
 }
 
 /// Проверяет, является ли запрос погодным
 pub fn is_weather_query(input: &str) -> bool {
-    let input_lower = input.to_lowercase();
-    let weather_keywords = [
-        "погода", "weather", "температура", "temperature", 
-        "дождь", "rain", "снег", "snow", "солнце", "sunny",
-        "облачно", "cloudy", "туман", "fog", "ветер", "wind"
-    ];
-    
-    weather_keywords.iter().any(|kw| input_lower.contains(kw))
+    static KEYWORDS: OnceLock<KeywordSet> = OnceLock::new();
+    KEYWORDS
+        .get_or_init(|| {
+            KeywordSet::new(&[
+                "погода", "weather", "температура", "temperature",
+                "дождь", "rain", "снег", "snow", "солнце", "солнечно", "sunny",
+                "облачно", "cloudy", "туман", "fog", "ветер", "ветра", "wind",
+                // Русские слова латиницей: «pogoda v moskve»
+                "pogoda", "pogodu", "pogody", "temperatura", "prognoz"
+            ])
+        })
+        .matches_fuzzy(input, default_matcher())
 }
 
-/// Извлекает название города из запроса
+/// Крупные города для подсказок при опечатках в названии
+const KNOWN_CITIES: &[&str] = &[
+    "Moscow", "London", "Paris", "Berlin", "Madrid", "Rome", "Vienna", "Prague", "Warsaw",
+    "Amsterdam", "Brussels", "Stockholm", "Oslo", "Helsinki", "Copenhagen", "Dublin", "Lisbon",
+    "Athens", "Istanbul", "Kyiv", "Minsk", "Riga", "Vilnius", "Tallinn", "Tbilisi", "Yerevan",
+    "Almaty", "Astana", "Tashkent", "Dubai", "Tokyo", "Beijing", "Shanghai", "Seoul", "Singapore",
+    "Bangkok", "Delhi", "Mumbai", "Sydney", "Melbourne", "Toronto", "Vancouver", "Chicago",
+    "Boston", "Seattle", "San Francisco", "Los Angeles", "New York", "Mexico City", "Buenos Aires",
+    "Москва", "Санкт-Петербург", "Новосибирск", "Екатеринбург", "Казань", "Нижний Новгород",
+    "Челябинск", "Самара", "Омск", "Ростов-на-Дону", "Уфа", "Красноярск", "Воронеж", "Пермь",
+    "Волгоград", "Краснодар", "Сочи", "Калининград", "Владивосток", "Иркутск", "Хабаровск",
+    "Лондон", "Париж", "Берлин", "Киев", "Минск", "Рига", "Прага", "Варшава", "Вена", "Рим",
+];
+
+/// Подсказка для названия города с опечаткой или в неверной раскладке
+fn suggest_city(city: &str) -> Option<&'static str> {
+    default_matcher().suggest(city, KNOWN_CITIES)
+}
+
+/// Приводит название города к виду, понятному геокодеру: «москве» → «Москва»,
+/// «нижнем новгороде» → «Нижний Новгород», «moskva» → «Москва»
+pub fn normalize_city(city: &str) -> String {
+    if contains_cyrillic(city) {
+        return to_nominative(city, KNOWN_CITIES);
+    }
+    if KNOWN_CITIES.iter().any(|known| known.eq_ignore_ascii_case(city)) {
+        return city.to_string();
+    }
+    // Русское название латиницей, в том числе склонённое: «moskva», «moskve»
+    let nominative = to_nominative(&to_cyrillic(city), KNOWN_CITIES);
+    if KNOWN_CITIES.contains(&nominative.as_str()) {
+        nominative
+    } else {
+        city.to_string()
+    }
+}
+
+/// Извлекает название города из запроса (в именительном падеже)
 pub fn extract_city_from_query(input: &str) -> Option<String> {
     let input_lower = input.to_lowercase();
     
@@ -60,14 +126,16 @@ pub fn extract_city_from_query(input: &str) -> Option<String> {
         r"weather in (.+)", 
         r"температура в (.+)",
         r"какая погода в (.+)",
-        r"how is weather in (.+)"
+        r"how is weather in (.+)",
+        r"pogod[aeuy] v (.+)",
+        r"temperatura v (.+)"
     ];
     
     for pattern in &patterns {
         if let Ok(re) = regex::Regex::new(pattern) {
             if let Some(captures) = re.captures(&input_lower) {
                 if let Some(city) = captures.get(1) {
-                    return Some(city.as_str().trim().to_string());
+                    return Some(normalize_city(city.as_str().trim()));
                 }
             }
         }
@@ -76,8 +144,9 @@ pub fn extract_city_from_query(input: &str) -> Option<String> {
     None
 }
 
-/// Получает текущую погоду по названию города (асинхронная версия)
-pub async fn get_weather(city: &str) -> Result<String, String> {
+/// Получает текущую погоду по названию города с учётом настроек пользователя (единицы, подробность).
+/// Описание погоды («пасмурно») OpenWeather присылает на языке ответа `reply_lang`.
+pub async fn get_weather(city: &str, settings: &UserSettings, reply_lang: &str) -> Result<String, String> {
     // Проверяем наличие API ключа
     let api_key = match env::var("OPENWEATHER_API_KEY") {
         Ok(key) if !key.is_empty() => key,
@@ -86,26 +155,27 @@ pub async fn get_weather(city: &str) -> Result<String, String> {
         }
     };
     
-    let url = format!(
-        "https://api.openweathermap.org/data/2.5/weather?q={}&appid={}&units=metric&lang=ru",
-        urlencoding::encode(city), api_key
-    );
-
     let client = Client::builder()
         .timeout(std::time::Duration::from_secs(10))
         .build()
         .map_err(|e| format!("Ошибка создания HTTP клиента: {}", e))?;
 
-    let response = client
-        .get(&url)
-        .header("User-Agent", "Bot-Auraya/1.0")
-        .send()
-        .await
-        .map_err(|e| format!("Ошибка запроса погоды: {}", e))?;
+    let lang = api_language(reply_lang);
+    let mut response = request_weather(&client, city, &api_key, settings.units, lang).await?;
+
+    // Кириллическое название, которого геокодер не знает, пробуем ещё и латиницей
+    if response.status().as_u16() == 404 && contains_cyrillic(city) {
+        let latin = to_latin(city, Scheme::Bgn);
+        println!("⚠️ Город «{}» не найден, пробуем «{}»", city, latin);
+        response = request_weather(&client, &latin, &api_key, settings.units, lang).await?;
+    }
 
     if !response.status().is_success() {
         return match response.status().as_u16() {
-            404 => Err(format!("🏙️ Город **{}** не найден. Проверьте правильность написания.", city)),
+            404 => Err(match suggest_city(city) {
+                Some(suggestion) => format!("🏙️ Город **{}** не найден. {}", city, did_you_mean(suggestion)),
+                None => format!("🏙️ Город **{}** не найден. Проверьте правильность написания.", city),
+            }),
             401 => Err("🔑 Неверный API ключ OpenWeather".to_string()),
             429 => Err("⏰ Превышен лимит запросов к API погоды".to_string()),
             _ => Err(format!("❌ Ошибка получения погоды: {}", response.status()))
@@ -117,18 +187,48 @@ pub async fn get_weather(city: &str) -> Result<String, String> {
         .await
         .map_err(|e| format!("Ошибка разбора ответа погоды: {}", e))?;
 
-    Ok(format_weather_response(&data))
+    Ok(format_weather_response(&data, settings.units, settings.verbosity))
+}
+
+/// Код языка для параметра `lang` OpenWeather; для неизвестных кодов — русский
+fn api_language(reply_lang: &str) -> &'static str {
+    match Language::parse(reply_lang).map(|lang| lang.iso639_1()) {
+        Some("zh") => "zh_cn",
+        Some("nb") | Some("nn") => "no",
+        Some(code) => code,
+        None => "ru",
+    }
+}
+
+/// Запрос текущей погоды к OpenWeather
+async fn request_weather(client: &Client, city: &str, api_key: &str, units: Units, lang: &str) -> Result<reqwest::Response, String> {
+    let url = format!(
+        "https://api.openweathermap.org/data/2.5/weather?q={}&appid={}&units={}&lang={}",
+        urlencoding::encode(city), api_key, units.api_param(), lang
+    );
+
+    client
+        .get(&url)
+        .header("User-Agent", "Bot-Auraya/1.0")
+        .send()
+        .await
+        .map_err(|e| format!("Ошибка запроса погоды: {}", e))
 }
 
 /// Форматирует ответ о погоде с эмодзи и подробной информацией
-fn format_weather_response(data: &WeatherResponse) -> String {
+fn format_weather_response(data: &WeatherResponse, units: Units, verbosity: Verbosity) -> String {
     let weather_condition = data.weather.first();
     let description = weather_condition
         .map(|w| w.description.as_str())
         .unwrap_or("неизвестно");
     
     let weather_emoji = get_weather_emoji(weather_condition);
-    let temp_emoji = get_temperature_emoji(data.main.temp);
+    let temp_celsius = match units {
+        Units::Metric => data.main.temp,
+        Units::Imperial => (data.main.temp - 32.0) * 5.0 / 9.0,
+    };
+    let temp_emoji = get_temperature_emoji(temp_celsius);
+    let deg = units.temperature_symbol();
     
     let mut response = format!(
         "{} **Погода в {}{}**\n\n",
@@ -138,40 +238,79 @@ fn format_weather_response(data: &WeatherResponse) -> String {
     );
     
     response.push_str(&format!(
-        "🌡️ **Температура:** {:.1}°C (ощущается как {:.1}°C) {}\n",
-        data.main.temp, data.main.feels_like, temp_emoji
+        "🌡️ **Температура:** {:.1}{} (ощущается как {:.1}{}) {}\n",
+        data.main.temp, deg, data.main.feels_like, deg, temp_emoji
     ));
     
     response.push_str(&format!(
-        "📊 **Состояние:** {}\n",
+        "📊 **Состояние:** {}",
         capitalize_first_letter(description)
     ));
+
+    if verbosity == Verbosity::Brief {
+        return response;
+    }
+    response.push('\n');
     
     response.push_str(&format!(
         "💧 **Влажность:** {}%\n",
         data.main.humidity
     ));
     
-    response.push_str(&format!(
-        "📏 **Давление:** {} мм рт.ст.\n",
-        (data.main.pressure as f64 * 0.75).round() as u16
-    ));
+    match units {
+        Units::Metric => response.push_str(&format!(
+            "📏 **Давление:** {} мм рт.ст.\n",
+            (data.main.pressure as f64 * 0.75).round() as u16
+        )),
+        Units::Imperial => response.push_str(&format!(
+            "📏 **Давление:** {:.2} inHg\n",
+            data.main.pressure as f64 * 0.02953
+        )),
+    }
     
     if let Some(wind) = &data.wind {
         response.push_str(&format!(
-            "💨 **Ветер:** {:.1} м/с\n",
-            wind.speed
+            "💨 **Ветер:** {:.1} {}",
+            wind.speed,
+            units.wind_speed_unit()
         ));
+        if verbosity == Verbosity::Detailed {
+            if let Some(deg) = wind.deg {
+                response.push_str(&format!(", {}", wind_direction(deg)));
+            }
+            if let Some(gust) = wind.gust {
+                response.push_str(&format!(", порывы до {:.1} {}", gust, units.wind_speed_unit()));
+            }
+        }
+        response.push('\n');
     }
     
     response.push_str(&format!(
-        "📈 **Диапазон:** {:.1}°C ... {:.1}°C",
-        data.main.temp_min, data.main.temp_max
+        "📈 **Диапазон:** {:.1}{} ... {:.1}{}",
+        data.main.temp_min, deg, data.main.temp_max, deg
     ));
+
+    if verbosity == Verbosity::Detailed {
+        if let Some(clouds) = &data.clouds {
+            response.push_str(&format!("\n☁️ **Облачность:** {}%", clouds.all));
+        }
+        if let Some(visibility) = data.visibility {
+            match units {
+                Units::Metric => response.push_str(&format!("\n👁️ **Видимость:** {:.1} км", visibility as f64 / 1000.0)),
+                Units::Imperial => response.push_str(&format!("\n👁️ **Видимость:** {:.1} mi", visibility as f64 / 1609.34)),
+            }
+        }
+    }
     
     response
 }
 
+/// Откуда дует ветер: направление в градусах → румб
+fn wind_direction(deg: u16) -> &'static str {
+    const DIRECTIONS: [&str; 8] = ["северный", "северо-восточный", "восточный", "юго-восточный", "южный", "юго-западный", "западный", "северо-западный"];
+    DIRECTIONS[((deg as usize % 360) * 2 + 45) / 90 % 8]
+}
+
 /// Получает эмодзи для погодных условий
 fn get_weather_emoji(weather: Option<&WeatherCondition>) -> &'static str {
     match weather.map(|w| w.main.as_str()) {
@@ -231,15 +370,74 @@ mod tests {
         assert!(is_weather_query("Weather in London"));
         assert!(is_weather_query("температура сегодня"));
         assert!(!is_weather_query("Hello world"));
+        assert!(is_weather_query("Узнай погоду в Сочи"));
+        assert!(is_weather_query("Is it raining?"));
+        assert!(!is_weather_query("Open the window"));
+        assert!(is_weather_query("wether in londn"));
+        assert!(is_weather_query("pogoda v moskve"));
+        assert!(is_weather_query("kakaya pogoda segodnya"));
+    }
+
+    #[test]
+    fn test_suggest_city() {
+        assert_eq!(suggest_city("londn"), Some("London"));
+        assert_eq!(suggest_city("vjcrdf"), Some("Москва"));
+        assert_eq!(suggest_city("London"), None);
     }
 
     #[test]
     fn test_extract_city() {
-        assert_eq!(extract_city_from_query("погода в москве"), Some("москве".to_string()));
+        assert_eq!(extract_city_from_query("погода в москве"), Some("Москва".to_string()));
+        assert_eq!(extract_city_from_query("погода в нижнем новгороде"), Some("Нижний Новгород".to_string()));
+        assert_eq!(extract_city_from_query("какая погода в казани"), Some("Казань".to_string()));
         assert_eq!(extract_city_from_query("weather in london"), Some("london".to_string()));
+        assert_eq!(extract_city_from_query("weather in moskva"), Some("Москва".to_string()));
+        assert_eq!(extract_city_from_query("pogoda v moskve"), Some("Москва".to_string()));
+        assert_eq!(extract_city_from_query("pogodu v kazani"), Some("Казань".to_string()));
         assert_eq!(extract_city_from_query("hello"), None);
     }
 
+    fn sample_response() -> WeatherResponse {
+        WeatherResponse {
+            weather: vec![WeatherCondition { main: "Clouds".to_string(), description: "пасмурно".to_string(), icon: "04d".to_string() }],
+            main: WeatherMain { temp: 12.0, feels_like: 10.5, humidity: 80, pressure: 1013, temp_min: 10.0, temp_max: 14.0 },
+            name: "Москва".to_string(),
+            sys: Some(WeatherSys { country: "RU".to_string() }),
+            wind: Some(WeatherWind { speed: 4.0, deg: Some(200), gust: Some(9.0) }),
+            clouds: Some(WeatherClouds { all: 90 }),
+            visibility: Some(10000),
+        }
+    }
+
+    #[test]
+    fn test_format_verbosity() {
+        let data = sample_response();
+        let brief = format_weather_response(&data, Units::Metric, Verbosity::Brief);
+        let normal = format_weather_response(&data, Units::Metric, Verbosity::Normal);
+        let detailed = format_weather_response(&data, Units::Metric, Verbosity::Detailed);
+        assert!(!brief.contains("Влажность"));
+        assert!(normal.contains("Влажность") && !normal.contains("Облачность") && !normal.contains("порывы"));
+        assert!(detailed.contains("Облачность:** 90%"));
+        assert!(detailed.contains("Видимость:** 10.0 км"));
+        assert!(detailed.contains("4.0 м/с, южный, порывы до 9.0 м/с"));
+    }
+
+    #[test]
+    fn test_api_language() {
+        assert_eq!(api_language("EN"), "en");
+        assert_eq!(api_language("de-AT"), "de");
+        assert_eq!(api_language("ZH"), "zh_cn");
+        assert_eq!(api_language("??"), "ru");
+    }
+
+    #[test]
+    fn test_wind_direction() {
+        assert_eq!(wind_direction(0), "северный");
+        assert_eq!(wind_direction(350), "северный");
+        assert_eq!(wind_direction(90), "восточный");
+        assert_eq!(wind_direction(225), "юго-западный");
+    }
+
     #[test]
     fn test_weather_emoji() {
         assert_eq!(get_temperature_emoji(35.0), "🔥");
@@ -0,0 +1,334 @@
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use sha2::{Digest, Sha256};
+use std::env;
+
+use crate::storage::db::get_db;
+
+/// Одна запись о взаимодействии пользователя с ботом
+#[derive(Debug, Clone)]
+pub struct InteractionRecord {
+    pub timestamp: DateTime<Utc>,
+    pub platform: String,
+    pub user_hash: String,          // анонимизированный идентификатор пользователя
+    pub language: String,
+    pub intent: String,             // выбранный навык: "wikipedia", "math", "weather", ...
+    pub latency_ms: i64,
+    pub cache_hit: bool,
+    pub error_kind: Option<String>, // например: "translation", "wikipedia_api"
+}
+
+/// Анонимизирует идентификатор пользователя: SHA-256 от соли, платформы и id.
+/// Соль берётся из `ANALYTICS_SALT`, чтобы хэши нельзя было сопоставить между развёртываниями.
+pub fn anonymize_user_id(platform: &str, user_id: &str) -> String {
+    let salt = env::var("ANALYTICS_SALT").unwrap_or_default();
+    let mut hasher = Sha256::new();
+    hasher.update(salt.as_bytes());
+    hasher.update(b":");
+    hasher.update(platform.as_bytes());
+    hasher.update(b":");
+    hasher.update(user_id.as_bytes());
+    hex::encode(&hasher.finalize()[..16])
+}
+
+/// Создаёт таблицу журнала взаимодействий
+pub async fn init_analytics_table() -> Result<(), sqlx::Error> {
+    let db = get_db().await;
+
+    let create_table_query = r#"
+        CREATE TABLE IF NOT EXISTS interactions (
+            id BIGSERIAL PRIMARY KEY,
+            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+            platform VARCHAR(32) NOT NULL,
+            user_hash VARCHAR(64) NOT NULL,
+            language VARCHAR(16) NOT NULL,
+            intent VARCHAR(32) NOT NULL,
+            latency_ms BIGINT NOT NULL,
+            cache_hit BOOLEAN NOT NULL DEFAULT FALSE,
+            error_kind VARCHAR(64)
+        )
+    "#;
+
+    sqlx::query(create_table_query)
+        .execute(db)
+        .await?;
+
+    let create_index_query = r#"
+        CREATE INDEX IF NOT EXISTS idx_interactions_created_at ON interactions(created_at)
+    "#;
+
+    sqlx::query(create_index_query)
+        .execute(db)
+        .await?;
+
+    Ok(())
+}
+
+/// Сохраняет запись о взаимодействии (ошибки только логируются)
+pub async fn record_interaction(record: &InteractionRecord) {
+    let db = get_db().await;
+
+    let query = r#"
+        INSERT INTO interactions
+            (created_at, platform, user_hash, language, intent, latency_ms, cache_hit, error_kind)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+    "#;
+
+    if let Err(e) = sqlx::query(query)
+        .bind(record.timestamp)
+        .bind(&record.platform)
+        .bind(&record.user_hash)
+        .bind(&record.language)
+        .bind(&record.intent)
+        .bind(record.latency_ms)
+        .bind(record.cache_hit)
+        .bind(&record.error_kind)
+        .execute(db)
+        .await
+    {
+        eprintln!("Ошибка записи аналитики: {}", e);
+    }
+}
+
+/// Статистика по одному навыку
+#[derive(Debug, Clone)]
+pub struct SkillStats {
+    pub intent: String,
+    pub requests: i64,
+    pub errors: i64,
+    pub p50_latency_ms: f64,
+    pub p95_latency_ms: f64,
+}
+
+/// Отчёт об использовании бота за период
+#[derive(Debug, Clone)]
+pub struct UsageReport {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub total_requests: i64,
+    pub unique_users: i64,
+    pub errors: i64,
+    pub cache_hits: i64,
+    pub p50_latency_ms: f64,
+    pub p95_latency_ms: f64,
+    pub daily_active_users: Vec<(NaiveDate, i64)>,
+    pub skills: Vec<SkillStats>,
+    pub error_kinds: Vec<(String, i64)>,
+}
+
+impl UsageReport {
+    /// Доля запросов, завершившихся ошибкой (0.0 ..= 1.0)
+    pub fn error_rate(&self) -> f64 {
+        if self.total_requests == 0 {
+            0.0
+        } else {
+            self.errors as f64 / self.total_requests as f64
+        }
+    }
+
+    /// Форматирует отчёт для отправки пользователю
+    pub fn format(&self) -> String {
+        let mut out = format!("📊 **Статистика {} — {}**\n\n", self.from, self.to);
+
+        if self.total_requests == 0 {
+            out.push_str("За этот период запросов не было.");
+            return out;
+        }
+
+        out.push_str(&format!(
+            "💬 Запросов: {}\n👤 Уникальных пользователей: {}\n❌ Ошибок: {} ({:.1}%)\n⚡ Из кэша: {}\n⏱️ Задержка: p50 {:.0} мс, p95 {:.0} мс\n",
+            self.total_requests,
+            self.unique_users,
+            self.errors,
+            self.error_rate() * 100.0,
+            self.cache_hits,
+            self.p50_latency_ms,
+            self.p95_latency_ms
+        ));
+
+        out.push_str("\n📅 **Активные пользователи по дням:**\n");
+        for (day, users) in &self.daily_active_users {
+            out.push_str(&format!("• {}: {}\n", day, users));
+        }
+
+        out.push_str("\n🧩 **Навыки:**\n");
+        for skill in &self.skills {
+            out.push_str(&format!(
+                "• {}: {} запросов, ошибок {}, p50 {:.0} мс, p95 {:.0} мс\n",
+                skill.intent, skill.requests, skill.errors, skill.p50_latency_ms, skill.p95_latency_ms
+            ));
+        }
+
+        if !self.error_kinds.is_empty() {
+            out.push_str("\n⚠️ **Типы ошибок:**\n");
+            for (kind, count) in &self.error_kinds {
+                out.push_str(&format!("• {}: {}\n", kind, count));
+            }
+        }
+
+        out.trim_end().to_string()
+    }
+}
+
+/// Строит отчёт об использовании за период `[from, to]` (обе даты включительно, UTC)
+pub async fn usage_report(from: NaiveDate, to: NaiveDate) -> Result<UsageReport, sqlx::Error> {
+    let db = get_db().await;
+    let (start, end) = date_range_bounds(from, to);
+
+    let totals_query = r#"
+        SELECT
+            COUNT(*),
+            COUNT(DISTINCT user_hash),
+            COUNT(*) FILTER (WHERE error_kind IS NOT NULL),
+            COUNT(*) FILTER (WHERE cache_hit),
+            COALESCE(percentile_cont(0.5) WITHIN GROUP (ORDER BY latency_ms), 0)::FLOAT8,
+            COALESCE(percentile_cont(0.95) WITHIN GROUP (ORDER BY latency_ms), 0)::FLOAT8
+        FROM interactions
+        WHERE created_at >= $1 AND created_at < $2
+    "#;
+
+    let (total_requests, unique_users, errors, cache_hits, p50, p95): (i64, i64, i64, i64, f64, f64) =
+        sqlx::query_as(totals_query)
+            .bind(start)
+            .bind(end)
+            .fetch_one(db)
+            .await?;
+
+    let dau_query = r#"
+        SELECT (created_at AT TIME ZONE 'UTC')::DATE AS day, COUNT(DISTINCT user_hash)
+        FROM interactions
+        WHERE created_at >= $1 AND created_at < $2
+        GROUP BY day
+        ORDER BY day
+    "#;
+
+    let daily_active_users: Vec<(NaiveDate, i64)> = sqlx::query_as(dau_query)
+        .bind(start)
+        .bind(end)
+        .fetch_all(db)
+        .await?;
+
+    let skills_query = r#"
+        SELECT
+            intent,
+            COUNT(*),
+            COUNT(*) FILTER (WHERE error_kind IS NOT NULL),
+            percentile_cont(0.5) WITHIN GROUP (ORDER BY latency_ms)::FLOAT8,
+            percentile_cont(0.95) WITHIN GROUP (ORDER BY latency_ms)::FLOAT8
+        FROM interactions
+        WHERE created_at >= $1 AND created_at < $2
+        GROUP BY intent
+        ORDER BY COUNT(*) DESC
+    "#;
+
+    let skills = sqlx::query_as::<_, (String, i64, i64, f64, f64)>(skills_query)
+        .bind(start)
+        .bind(end)
+        .fetch_all(db)
+        .await?
+        .into_iter()
+        .map(|(intent, requests, errors, p50_latency_ms, p95_latency_ms)| SkillStats {
+            intent,
+            requests,
+            errors,
+            p50_latency_ms,
+            p95_latency_ms,
+        })
+        .collect();
+
+    let errors_query = r#"
+        SELECT error_kind, COUNT(*)
+        FROM interactions
+        WHERE created_at >= $1 AND created_at < $2 AND error_kind IS NOT NULL
+        GROUP BY error_kind
+        ORDER BY COUNT(*) DESC
+    "#;
+
+    let error_kinds: Vec<(String, i64)> = sqlx::query_as(errors_query)
+        .bind(start)
+        .bind(end)
+        .fetch_all(db)
+        .await?;
+
+    Ok(UsageReport {
+        from,
+        to,
+        total_requests,
+        unique_users,
+        errors,
+        cache_hits,
+        p50_latency_ms: p50,
+        p95_latency_ms: p95,
+        daily_active_users,
+        skills,
+        error_kinds,
+    })
+}
+
+/// Разбирает период отчёта из аргументов команды:
+/// пусто — последние 7 дней, одна дата — этот день, две даты — диапазон (`YYYY-MM-DD`)
+pub fn parse_report_range(args: &str, today: NaiveDate) -> Result<(NaiveDate, NaiveDate), String> {
+    let parse = |s: &str| {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d")
+            .map_err(|_| format!("Некорректная дата «{}». Формат: ГГГГ-ММ-ДД", s))
+    };
+
+    let parts: Vec<&str> = args.split_whitespace().collect();
+    let (from, to) = match parts.as_slice() {
+        [] => (today - Duration::days(6), today),
+        [day] => {
+            let day = parse(day)?;
+            (day, day)
+        }
+        [from, to] => (parse(from)?, parse(to)?),
+        _ => return Err("Укажите не больше двух дат: /stats [с] [по]".to_string()),
+    };
+
+    if from > to {
+        return Err("Начальная дата позже конечной".to_string());
+    }
+
+    Ok((from, to))
+}
+
+/// Полуинтервал `[начало from, начало дня после to)` в UTC
+fn date_range_bounds(from: NaiveDate, to: NaiveDate) -> (DateTime<Utc>, DateTime<Utc>) {
+    let start = from.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc();
+    let end = (to + Duration::days(1)).and_hms_opt(0, 0, 0).unwrap_or_default().and_utc();
+    (start, end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_anonymize_user_id() {
+        let a = anonymize_user_id("telegram", "42");
+        assert_eq!(a.len(), 32);
+        assert_eq!(a, anonymize_user_id("telegram", "42"));
+        assert_ne!(a, anonymize_user_id("cli", "42"));
+    }
+
+    #[test]
+    fn test_parse_report_range() {
+        let today = date("2026-10-19");
+        assert_eq!(parse_report_range("", today), Ok((date("2026-10-13"), today)));
+        assert_eq!(parse_report_range("2026-10-01", today), Ok((date("2026-10-01"), date("2026-10-01"))));
+        assert_eq!(
+            parse_report_range("2026-10-01 2026-10-05", today),
+            Ok((date("2026-10-01"), date("2026-10-05")))
+        );
+        assert!(parse_report_range("2026-10-05 2026-10-01", today).is_err());
+        assert!(parse_report_range("вчера", today).is_err());
+    }
+
+    #[test]
+    fn test_date_range_bounds() {
+        let (start, end) = date_range_bounds(date("2026-10-01"), date("2026-10-01"));
+        assert_eq!((end - start).num_hours(), 24);
+    }
+}
@@ -0,0 +1,221 @@
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use chacha20poly1305::{
+    aead::{Aead, AeadCore, KeyInit, OsRng, Payload},
+    ChaCha20Poly1305, Key, Nonce,
+};
+use std::env;
+use std::sync::OnceLock;
+
+/// Префикс зашифрованных значений: `enc:v1:<key_id>:<base64(nonce || ciphertext)>`
+const ENCRYPTED_PREFIX: &str = "enc:v1:";
+/// Длина nonce ChaCha20-Poly1305 в байтах
+const NONCE_LEN: usize = 12;
+
+static KEYRING: OnceLock<Keyring> = OnceLock::new();
+
+/// Набор ключей шифрования «на диске».
+///
+/// Первый ключ активный: им шифруются новые записи. Остальные нужны только
+/// для расшифровки старых записей после ротации.
+pub struct Keyring {
+    keys: Vec<(String, ChaCha20Poly1305)>,
+}
+
+impl std::fmt::Debug for Keyring {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Сами ключи в лог не выводим
+        f.debug_struct("Keyring")
+            .field("key_ids", &self.keys.iter().map(|(id, _)| id.as_str()).collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl Keyring {
+    /// Пустой набор: шифрование выключено, значения хранятся как есть
+    pub fn disabled() -> Self {
+        Self { keys: Vec::new() }
+    }
+
+    /// Разбирает спецификацию ключей `id:base64key[,id:base64key...]` (ключ — 32 байта)
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut keys = Vec::new();
+
+        for entry in spec.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (id, encoded) = entry
+                .split_once(':')
+                .ok_or_else(|| format!("Ключ «{}» должен иметь вид id:base64", entry))?;
+            if id.is_empty() || id.contains(':') {
+                return Err("Идентификатор ключа не может быть пустым".to_string());
+            }
+            if keys.iter().any(|(existing, _)| existing == id) {
+                return Err(format!("Ключ «{}» указан дважды", id));
+            }
+
+            let bytes = BASE64
+                .decode(encoded)
+                .map_err(|e| format!("Ключ «{}»: некорректный base64: {}", id, e))?;
+            if bytes.len() != 32 {
+                return Err(format!("Ключ «{}» должен быть 32 байта, получено {}", id, bytes.len()));
+            }
+
+            keys.push((id.to_string(), ChaCha20Poly1305::new(Key::from_slice(&bytes))));
+        }
+
+        Ok(Self { keys })
+    }
+
+    /// Загружает ключи из `ENCRYPTION_KEYS`; без переменной шифрование выключено
+    pub fn from_env() -> Result<Self, String> {
+        match env::var("ENCRYPTION_KEYS") {
+            Ok(spec) if !spec.trim().is_empty() => Self::parse(&spec),
+            _ => Ok(Self::disabled()),
+        }
+    }
+
+    /// Включено ли шифрование
+    pub fn is_enabled(&self) -> bool {
+        !self.keys.is_empty()
+    }
+
+    /// Идентификатор активного ключа
+    pub fn active_key_id(&self) -> Option<&str> {
+        self.keys.first().map(|(id, _)| id.as_str())
+    }
+
+    /// Шифрует строку активным ключом со случайным nonce.
+    /// `context` (например, "telegram:42:dialog") аутентифицируется, но не шифруется —
+    /// запись нельзя незаметно перенести к другому пользователю или полю.
+    pub fn encrypt(&self, plaintext: &str, context: &str) -> Result<String, String> {
+        let Some((key_id, cipher)) = self.keys.first() else {
+            return Ok(plaintext.to_string());
+        };
+
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = cipher
+            .encrypt(&nonce, Payload { msg: plaintext.as_bytes(), aad: context.as_bytes() })
+            .map_err(|_| "Ошибка шифрования".to_string())?;
+
+        let mut sealed = nonce.to_vec();
+        sealed.extend_from_slice(&ciphertext);
+        Ok(format!("{}{}:{}", ENCRYPTED_PREFIX, key_id, BASE64.encode(sealed)))
+    }
+
+    /// Расшифровывает значение; незашифрованные значения возвращаются как есть
+    pub fn decrypt(&self, stored: &str, context: &str) -> Result<String, String> {
+        let Some((key_id, payload)) = parse_sealed(stored) else {
+            return Ok(stored.to_string());
+        };
+
+        let cipher = self
+            .keys
+            .iter()
+            .find(|(id, _)| id == key_id)
+            .map(|(_, cipher)| cipher)
+            .ok_or_else(|| format!("Ключ шифрования «{}» не найден", key_id))?;
+
+        let sealed = BASE64
+            .decode(payload)
+            .map_err(|_| "Повреждённая зашифрованная запись".to_string())?;
+        if sealed.len() < NONCE_LEN {
+            return Err("Повреждённая зашифрованная запись".to_string());
+        }
+        let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+
+        let plaintext = cipher
+            .decrypt(Nonce::from_slice(nonce), Payload { msg: ciphertext, aad: context.as_bytes() })
+            .map_err(|_| "Не удалось расшифровать запись (неверный ключ или данные изменены)".to_string())?;
+
+        String::from_utf8(plaintext).map_err(|_| "Расшифрованные данные не UTF-8".to_string())
+    }
+
+    /// Нужно ли перешифровать значение: оно открытое или зашифровано неактивным ключом
+    pub fn needs_rotation(&self, stored: &str) -> bool {
+        match (self.active_key_id(), parse_sealed(stored)) {
+            (None, _) => false,
+            (Some(_), None) => true,
+            (Some(active), Some((key_id, _))) => key_id != active,
+        }
+    }
+
+    /// Перешифровывает значение активным ключом (для ротации ключей)
+    pub fn reencrypt(&self, stored: &str, context: &str) -> Result<String, String> {
+        let plaintext = self.decrypt(stored, context)?;
+        self.encrypt(&plaintext, context)
+    }
+}
+
+/// Проверяет, зашифровано ли значение
+pub fn is_encrypted(stored: &str) -> bool {
+    parse_sealed(stored).is_some()
+}
+
+/// Разбирает `enc:v1:<key_id>:<payload>` на (key_id, payload)
+fn parse_sealed(stored: &str) -> Option<(&str, &str)> {
+    stored.strip_prefix(ENCRYPTED_PREFIX)?.split_once(':')
+}
+
+/// Глобальный набор ключей из окружения.
+/// Некорректная конфигурация — фатальная ошибка: молча писать открытый текст нельзя.
+pub fn keyring() -> &'static Keyring {
+    KEYRING.get_or_init(|| Keyring::from_env().expect("Некорректная переменная ENCRYPTION_KEYS"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_key(byte: u8) -> String {
+        BASE64.encode([byte; 32])
+    }
+
+    #[test]
+    fn test_roundtrip_with_context() {
+        let keyring = Keyring::parse(&format!("k1:{}", test_key(1))).unwrap();
+        let sealed = keyring.encrypt("Привет, мир", "telegram:42:dialog").unwrap();
+
+        assert!(is_encrypted(&sealed));
+        assert!(!sealed.contains("Привет"));
+        assert_eq!(keyring.decrypt(&sealed, "telegram:42:dialog").unwrap(), "Привет, мир");
+        assert!(keyring.decrypt(&sealed, "telegram:43:dialog").is_err());
+    }
+
+    #[test]
+    fn test_nonces_are_unique() {
+        let keyring = Keyring::parse(&format!("k1:{}", test_key(1))).unwrap();
+        let a = keyring.encrypt("same", "ctx").unwrap();
+        let b = keyring.encrypt("same", "ctx").unwrap();
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_key_rotation() {
+        let old = Keyring::parse(&format!("k1:{}", test_key(1))).unwrap();
+        let sealed = old.encrypt("secret", "ctx").unwrap();
+
+        let rotated = Keyring::parse(&format!("k2:{},k1:{}", test_key(2), test_key(1))).unwrap();
+        assert_eq!(rotated.decrypt(&sealed, "ctx").unwrap(), "secret");
+        assert!(rotated.needs_rotation(&sealed));
+
+        let resealed = rotated.reencrypt(&sealed, "ctx").unwrap();
+        assert!(resealed.starts_with("enc:v1:k2:"));
+        assert!(!rotated.needs_rotation(&resealed));
+        assert!(old.decrypt(&resealed, "ctx").is_err());
+    }
+
+    #[test]
+    fn test_disabled_and_plaintext_passthrough() {
+        let disabled = Keyring::disabled();
+        assert_eq!(disabled.encrypt("plain", "ctx").unwrap(), "plain");
+
+        let keyring = Keyring::parse(&format!("k1:{}", test_key(1))).unwrap();
+        assert_eq!(keyring.decrypt("old plaintext", "ctx").unwrap(), "old plaintext");
+        assert!(keyring.needs_rotation("old plaintext"));
+    }
+
+    #[test]
+    fn test_invalid_spec() {
+        assert!(Keyring::parse("k1:short").is_err());
+        assert!(Keyring::parse("no-separator").is_err());
+        assert!(Keyring::parse(&format!("k1:{},k1:{}", test_key(1), test_key(2))).is_err());
+    }
+}
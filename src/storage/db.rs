@@ -90,10 +90,46 @@ pub async fn init_cache_table() -> Result<(), sqlx::Error> {
     sqlx::query(create_index_query)
         .execute(db)
         .await?;
+
+    // Какие ключи кэша получены из ввода какого пользователя (для /forget)
+    let create_origins_query = r#"
+        CREATE TABLE IF NOT EXISTS cache_origins (
+            platform VARCHAR(32) NOT NULL,
+            user_id VARCHAR(64) NOT NULL,
+            key VARCHAR(255) NOT NULL,
+            created_at TIMESTAMP DEFAULT NOW(),
+            PRIMARY KEY (platform, user_id, key)
+        )
+    "#;
+
+    sqlx::query(create_origins_query)
+        .execute(db)
+        .await?;
     
     Ok(())
 }
 
+// Функция для запоминания, что ключ кэша получен из ввода пользователя
+pub async fn record_cache_origin(platform: &str, user_id: &str, key: &str) {
+    let db = get_db().await;
+
+    let query = r#"
+        INSERT INTO cache_origins (platform, user_id, key)
+        VALUES ($1, $2, $3)
+        ON CONFLICT DO NOTHING
+    "#;
+
+    if let Err(e) = sqlx::query(query)
+        .bind(platform)
+        .bind(user_id)
+        .bind(key)
+        .execute(db)
+        .await
+    {
+        eprintln!("Ошибка сохранения происхождения кэша: {}", e);
+    }
+}
+
 // Функция для очистки просроченных записей кэша
 pub async fn cleanup_expired_cache() -> Result<u64, sqlx::Error> {
     let db = match get_db().await {
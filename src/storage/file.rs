@@ -1,13 +1,24 @@
-use std::fs::{self, File};
+use fs2::FileExt;
+use serde::{de::DeserializeOwned, Serialize};
+use std::fs::{self, File, OpenOptions};
 use std::io::{Read, Write};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::error::Error;
+use std::sync::atomic::{AtomicU64, Ordering};
 
-/// Сохраняет строку в файл по указанному пути
+use crate::storage::crypto::{is_encrypted, Keyring};
+
+/// Расширение файлов со значениями в хранилище
+const VALUE_EXT: &str = "json";
+/// Имя lock-файла внутри каждого пространства имён
+const LOCK_FILE: &str = ".lock";
+
+// Счётчик для уникальных имён временных файлов внутри процесса
+static TMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Сохраняет строку в файл по указанному пути (атомарно: запись во временный файл + rename)
 pub fn save_to_file(path: &str, content: &str) -> Result<(), Box<dyn Error>> {
-    let mut file = File::create(path)?;
-    file.write_all(content.as_bytes())?;
-    Ok(())
+    write_atomic(Path::new(path), content.as_bytes())
 }
 
 /// Загружает содержимое файла как строку
@@ -29,3 +40,386 @@ pub fn delete_file(path: &str) -> Result<(), Box<dyn Error>> {
     }
     Ok(())
 }
+
+/// Атомарно записывает данные: сначала во временный файл рядом с целевым,
+/// затем fsync и rename поверх. При падении посреди записи старое содержимое сохраняется.
+fn write_atomic(path: &Path, data: &[u8]) -> Result<(), Box<dyn Error>> {
+    let dir = match path.parent() {
+        Some(p) if !p.as_os_str().is_empty() => p.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    let file_name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or("Некорректный путь к файлу")?;
+
+    let tmp_path = dir.join(format!(
+        ".{}.tmp-{}-{}",
+        file_name,
+        std::process::id(),
+        TMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+
+    let result = (|| -> Result<(), Box<dyn Error>> {
+        let mut tmp = File::create(&tmp_path)?;
+        tmp.write_all(data)?;
+        tmp.sync_all()?;
+        fs::rename(&tmp_path, path)?;
+        Ok(())
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp_path);
+    }
+    result
+}
+
+/// Файловое key-value хранилище: каталог с пространствами имён (подкаталогами),
+/// значения сериализуются в JSON, по одному файлу на ключ.
+///
+/// Используется как офлайн-хранилище для CLI и мобильных встраиваний.
+#[derive(Debug, Clone)]
+pub struct KvStore {
+    root: PathBuf,
+}
+
+impl KvStore {
+    /// Открывает (и при необходимости создаёт) хранилище в каталоге `root`
+    pub fn open(root: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        let root = root.as_ref().to_path_buf();
+        fs::create_dir_all(&root)?;
+        Ok(Self { root })
+    }
+
+    /// Открывает хранилище из каталога `FILE_STORE_DIR`; `Ok(None)`, если переменная не задана
+    pub fn from_env() -> Result<Option<Self>, Box<dyn Error>> {
+        match std::env::var("FILE_STORE_DIR") {
+            Ok(dir) if !dir.trim().is_empty() => Ok(Some(Self::open(dir.trim())?)),
+            _ => Ok(None),
+        }
+    }
+
+    /// Корневой каталог хранилища
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Возвращает пространство имён (каталог создаётся при первом обращении)
+    pub fn namespace(&self, name: &str) -> Result<Namespace, Box<dyn Error>> {
+        if name.is_empty() {
+            return Err("Имя пространства имён не может быть пустым".into());
+        }
+        let dir = self.root.join(encode_key(name));
+        fs::create_dir_all(&dir)?;
+        Ok(Namespace { name: name.to_string(), dir })
+    }
+
+    /// Список существующих пространств имён
+    pub fn namespaces(&self) -> Result<Vec<String>, Box<dyn Error>> {
+        let mut names = Vec::new();
+        for entry in fs::read_dir(&self.root)? {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() {
+                if let Some(name) = entry.file_name().to_str().and_then(decode_key) {
+                    names.push(name);
+                }
+            }
+        }
+        names.sort();
+        Ok(names)
+    }
+
+    /// Полностью удаляет пространство имён со всеми ключами
+    pub fn drop_namespace(&self, name: &str) -> Result<(), Box<dyn Error>> {
+        let dir = self.root.join(encode_key(name));
+        if dir.exists() {
+            fs::remove_dir_all(dir)?;
+        }
+        Ok(())
+    }
+}
+
+/// Пространство имён внутри [`KvStore`]
+#[derive(Debug, Clone)]
+pub struct Namespace {
+    name: String,
+    dir: PathBuf,
+}
+
+impl Namespace {
+    /// Сохраняет значение под ключом (JSON, атомарная запись под эксклюзивной блокировкой)
+    pub fn put<T: Serialize>(&self, key: &str, value: &T) -> Result<(), Box<dyn Error>> {
+        let data = serde_json::to_vec_pretty(value)?;
+        self.put_raw(key, &data)
+    }
+
+    /// Загружает значение по ключу; `Ok(None)`, если ключа нет
+    pub fn get<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>, Box<dyn Error>> {
+        match self.get_raw(key)? {
+            Some(data) => Ok(Some(serde_json::from_slice(&data)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Сохраняет значение в зашифрованном виде (JSON, затем AEAD-шифрование ключами `keyring`).
+    /// Если шифрование не настроено, значение сохраняется открытым JSON.
+    pub fn put_sealed<T: Serialize>(&self, key: &str, value: &T, keyring: &Keyring) -> Result<(), Box<dyn Error>> {
+        let json = serde_json::to_string(value)?;
+        let sealed = keyring.encrypt(&json, &self.sealed_context(key))?;
+        self.put_raw(key, sealed.as_bytes())
+    }
+
+    /// Загружает значение, сохранённое через [`Namespace::put_sealed`] (открытые значения тоже читаются)
+    pub fn get_sealed<T: DeserializeOwned>(&self, key: &str, keyring: &Keyring) -> Result<Option<T>, Box<dyn Error>> {
+        match self.get_raw(key)? {
+            Some(data) => {
+                let stored = String::from_utf8(data)?;
+                let json = keyring.decrypt(&stored, &self.sealed_context(key))?;
+                Ok(Some(serde_json::from_str(&json)?))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Перешифровывает активным ключом значение, сохранённое через [`Namespace::put_sealed`]
+    /// старым ключом. Открытые значения не трогает: их читают через [`Namespace::get`].
+    /// Возвращает `true`, если значение перезаписано.
+    pub fn reseal(&self, key: &str, keyring: &Keyring) -> Result<bool, Box<dyn Error>> {
+        let Some(data) = self.get_raw(key)? else {
+            return Ok(false);
+        };
+        let Ok(stored) = String::from_utf8(data) else {
+            return Ok(false);
+        };
+        if !is_encrypted(&stored) || !keyring.needs_rotation(&stored) {
+            return Ok(false);
+        }
+        let resealed = keyring.reencrypt(&stored, &self.sealed_context(key))?;
+        self.put_raw(key, resealed.as_bytes())?;
+        Ok(true)
+    }
+
+    /// Сохраняет «сырые» байты под ключом
+    pub fn put_raw(&self, key: &str, data: &[u8]) -> Result<(), Box<dyn Error>> {
+        let path = self.key_path(key)?;
+        let lock = self.lock_exclusive()?;
+        let result = write_atomic(&path, data);
+        lock.unlock()?;
+        result
+    }
+
+    /// Загружает «сырые» байты по ключу
+    pub fn get_raw(&self, key: &str) -> Result<Option<Vec<u8>>, Box<dyn Error>> {
+        let path = self.key_path(key)?;
+        let lock = self.lock_shared()?;
+        let result = match fs::read(&path) {
+            Ok(data) => Ok(Some(data)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        };
+        lock.unlock()?;
+        result
+    }
+
+    /// Проверяет наличие ключа
+    pub fn contains(&self, key: &str) -> Result<bool, Box<dyn Error>> {
+        Ok(self.key_path(key)?.exists())
+    }
+
+    /// Удаляет ключ; возвращает `true`, если он существовал
+    pub fn delete(&self, key: &str) -> Result<bool, Box<dyn Error>> {
+        let path = self.key_path(key)?;
+        let lock = self.lock_exclusive()?;
+        let result = match fs::remove_file(&path) {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(e.into()),
+        };
+        lock.unlock()?;
+        result
+    }
+
+    /// Список всех ключей в пространстве имён (отсортирован)
+    pub fn keys(&self) -> Result<Vec<String>, Box<dyn Error>> {
+        self.keys_with_prefix("")
+    }
+
+    /// Список ключей, начинающихся с `prefix` (отсортирован)
+    pub fn keys_with_prefix(&self, prefix: &str) -> Result<Vec<String>, Box<dyn Error>> {
+        let lock = self.lock_shared()?;
+        let mut keys = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some(VALUE_EXT) {
+                continue;
+            }
+            if let Some(key) = path.file_stem().and_then(|s| s.to_str()).and_then(decode_key) {
+                if key.starts_with(prefix) {
+                    keys.push(key);
+                }
+            }
+        }
+        lock.unlock()?;
+        keys.sort();
+        Ok(keys)
+    }
+
+    /// Возвращает все пары (ключ, значение) с ключами, начинающимися с `prefix`
+    pub fn scan_prefix<T: DeserializeOwned>(&self, prefix: &str) -> Result<Vec<(String, T)>, Box<dyn Error>> {
+        let mut items = Vec::new();
+        for key in self.keys_with_prefix(prefix)? {
+            // Ключ мог быть удалён между листингом и чтением
+            if let Some(value) = self.get(&key)? {
+                items.push((key, value));
+            }
+        }
+        Ok(items)
+    }
+
+    /// Удаляет все ключи с указанным префиксом; возвращает количество удалённых
+    pub fn delete_prefix(&self, prefix: &str) -> Result<usize, Box<dyn Error>> {
+        let mut removed = 0;
+        for key in self.keys_with_prefix(prefix)? {
+            if self.delete(&key)? {
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    /// Контекст шифрования: привязывает запись к пространству имён и ключу
+    fn sealed_context(&self, key: &str) -> String {
+        format!("file:{}/{}", self.name, key)
+    }
+
+    fn key_path(&self, key: &str) -> Result<PathBuf, Box<dyn Error>> {
+        if key.is_empty() {
+            return Err("Ключ не может быть пустым".into());
+        }
+        Ok(self.dir.join(format!("{}.{}", encode_key(key), VALUE_EXT)))
+    }
+
+    fn lock_file(&self) -> Result<File, Box<dyn Error>> {
+        Ok(OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(self.dir.join(LOCK_FILE))?)
+    }
+
+    fn lock_exclusive(&self) -> Result<File, Box<dyn Error>> {
+        let file = self.lock_file()?;
+        file.lock_exclusive()?;
+        Ok(file)
+    }
+
+    fn lock_shared(&self) -> Result<File, Box<dyn Error>> {
+        let file = self.lock_file()?;
+        file.lock_shared()?;
+        Ok(file)
+    }
+}
+
+/// Кодирует ключ в безопасное имя файла (percent-encoding, включая `.` в начале)
+fn encode_key(key: &str) -> String {
+    let encoded = urlencoding::encode(key).into_owned();
+    match encoded.strip_prefix('.') {
+        Some(rest) => format!("%2E{}", rest),
+        None => encoded,
+    }
+}
+
+/// Декодирует имя файла обратно в ключ
+fn decode_key(name: &str) -> Option<String> {
+    if name.starts_with('.') {
+        return None; // служебные файлы (.lock, временные)
+    }
+    urlencoding::decode(name).ok().map(|s| s.into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Note {
+        text: String,
+        pinned: bool,
+    }
+
+    fn temp_store(name: &str) -> KvStore {
+        let dir = std::env::temp_dir().join(format!("bot_core_kv_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        KvStore::open(dir).unwrap()
+    }
+
+    #[test]
+    fn test_put_get_delete() {
+        let store = temp_store("put_get");
+        let ns = store.namespace("notes").unwrap();
+        let note = Note { text: "Привет".to_string(), pinned: true };
+
+        ns.put("user/42", &note).unwrap();
+        assert_eq!(ns.get::<Note>("user/42").unwrap(), Some(note));
+        assert!(ns.delete("user/42").unwrap());
+        assert_eq!(ns.get::<Note>("user/42").unwrap(), None);
+        assert!(!ns.delete("user/42").unwrap());
+
+        fs::remove_dir_all(store.root()).unwrap();
+    }
+
+    #[test]
+    fn test_prefix_scan() {
+        let store = temp_store("prefix");
+        let ns = store.namespace("cache").unwrap();
+        ns.put("a:1", &1).unwrap();
+        ns.put("a:2", &2).unwrap();
+        ns.put("b:1", &3).unwrap();
+
+        assert_eq!(ns.keys().unwrap(), vec!["a:1", "a:2", "b:1"]);
+        let scanned: Vec<(String, i32)> = ns.scan_prefix("a:").unwrap();
+        assert_eq!(scanned, vec![("a:1".to_string(), 1), ("a:2".to_string(), 2)]);
+        assert_eq!(ns.delete_prefix("a:").unwrap(), 2);
+        assert_eq!(ns.keys().unwrap(), vec!["b:1"]);
+        assert_eq!(store.namespaces().unwrap(), vec!["cache"]);
+
+        fs::remove_dir_all(store.root()).unwrap();
+    }
+
+    #[test]
+    fn test_sealed_values() {
+        use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+
+        let store = temp_store("sealed");
+        let ns = store.namespace("memory").unwrap();
+        let keyring = Keyring::parse(&format!("k1:{}", BASE64.encode([7u8; 32]))).unwrap();
+        let note = Note { text: "секрет".to_string(), pinned: false };
+
+        ns.put_sealed("user", &note, &keyring).unwrap();
+        let raw = String::from_utf8(ns.get_raw("user").unwrap().unwrap()).unwrap();
+        assert!(!raw.contains("секрет"));
+        assert_eq!(ns.get_sealed::<Note>("user", &keyring).unwrap(), Some(note));
+
+        // После ротации запечатанное значение перешифровывается новым ключом, открытое остаётся как есть
+        let rotated = Keyring::parse(&format!("k2:{},k1:{}", BASE64.encode([9u8; 32]), BASE64.encode([7u8; 32]))).unwrap();
+        ns.put("plain", &1).unwrap();
+        assert!(ns.reseal("user", &rotated).unwrap());
+        assert!(!ns.reseal("user", &rotated).unwrap());
+        assert!(!ns.reseal("plain", &rotated).unwrap());
+        let raw = String::from_utf8(ns.get_raw("user").unwrap().unwrap()).unwrap();
+        assert!(raw.starts_with("enc:v1:k2:"));
+        assert_eq!(ns.get_sealed::<Note>("user", &rotated).unwrap().map(|n| n.text), Some("секрет".to_string()));
+        assert_eq!(ns.get::<i32>("plain").unwrap(), Some(1));
+
+        fs::remove_dir_all(store.root()).unwrap();
+    }
+
+    #[test]
+    fn test_key_encoding() {
+        assert_eq!(decode_key(&encode_key("../etc/passwd")), Some("../etc/passwd".to_string()));
+        assert!(!encode_key("../etc").contains('/'));
+        assert!(!encode_key(".hidden").starts_with('.'));
+    }
+}
@@ -0,0 +1,237 @@
+use chrono::{DateTime, Utc};
+
+use crate::{
+    core::{dialog::Message, memory::MemoryItem},
+    storage::{crypto::{keyring, Keyring}, db::get_db, file::KvStore},
+};
+
+/// Функция, строящая контекст шифрования по (platform, user_id)
+type ContextFn = fn(&str, &str) -> String;
+
+/// Контекст шифрования истории диалога пользователя
+fn dialog_context(platform: &str, user_id: &str) -> String {
+    format!("{}:{}:dialog", platform, user_id)
+}
+
+/// Контекст шифрования памяти пользователя
+fn memory_context(platform: &str, user_id: &str) -> String {
+    format!("{}:{}:memory", platform, user_id)
+}
+
+/// Создаёт таблицы истории диалогов и памяти
+pub async fn init_history_tables() -> Result<(), sqlx::Error> {
+    let db = get_db().await;
+
+    let create_dialog_query = r#"
+        CREATE TABLE IF NOT EXISTS dialog_messages (
+            id BIGSERIAL PRIMARY KEY,
+            platform VARCHAR(32) NOT NULL,
+            user_id VARCHAR(64) NOT NULL,
+            role VARCHAR(16) NOT NULL,
+            text TEXT NOT NULL,
+            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+        )
+    "#;
+
+    sqlx::query(create_dialog_query)
+        .execute(db)
+        .await?;
+
+    let create_memory_query = r#"
+        CREATE TABLE IF NOT EXISTS memory_items (
+            id BIGSERIAL PRIMARY KEY,
+            platform VARCHAR(32) NOT NULL,
+            user_id VARCHAR(64) NOT NULL,
+            source VARCHAR(32) NOT NULL,
+            content TEXT NOT NULL,
+            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+        )
+    "#;
+
+    sqlx::query(create_memory_query)
+        .execute(db)
+        .await?;
+
+    let create_index_query = r#"
+        CREATE INDEX IF NOT EXISTS idx_dialog_messages_user ON dialog_messages(platform, user_id, created_at)
+    "#;
+
+    sqlx::query(create_index_query)
+        .execute(db)
+        .await?;
+
+    Ok(())
+}
+
+/// Сохраняет сообщение диалога (текст шифруется, если настроены ключи)
+pub async fn append_dialog_message(platform: &str, user_id: &str, message: &Message) -> Result<(), String> {
+    let db = get_db().await;
+    let text = keyring().encrypt(&message.text, &dialog_context(platform, user_id))?;
+
+    let query = r#"
+        INSERT INTO dialog_messages (platform, user_id, role, text, created_at)
+        VALUES ($1, $2, $3, $4, $5)
+    "#;
+
+    sqlx::query(query)
+        .bind(platform)
+        .bind(user_id)
+        .bind(&message.user)
+        .bind(text)
+        .bind(message.timestamp)
+        .execute(db)
+        .await
+        .map_err(|e| format!("Ошибка сохранения сообщения: {}", e))?;
+
+    Ok(())
+}
+
+/// Загружает последние `limit` сообщений диалога в хронологическом порядке
+pub async fn load_dialog_history(platform: &str, user_id: &str, limit: i64) -> Result<Vec<Message>, String> {
+    let db = get_db().await;
+    let context = dialog_context(platform, user_id);
+
+    let query = r#"
+        SELECT role, text, created_at FROM (
+            SELECT id, role, text, created_at
+            FROM dialog_messages
+            WHERE platform = $1 AND user_id = $2
+            ORDER BY id DESC
+            LIMIT $3
+        ) recent
+        ORDER BY id
+    "#;
+
+    let rows: Vec<(String, String, DateTime<Utc>)> = sqlx::query_as(query)
+        .bind(platform)
+        .bind(user_id)
+        .bind(limit)
+        .fetch_all(db)
+        .await
+        .map_err(|e| format!("Ошибка загрузки истории: {}", e))?;
+
+    rows.into_iter()
+        .map(|(user, text, timestamp)| {
+            Ok(Message {
+                user,
+                text: keyring().decrypt(&text, &context)?,
+                timestamp,
+            })
+        })
+        .collect()
+}
+
+/// Сохраняет элемент памяти (содержимое шифруется, если настроены ключи)
+pub async fn save_memory_item(platform: &str, user_id: &str, item: &MemoryItem) -> Result<(), String> {
+    let db = get_db().await;
+    let content = keyring().encrypt(&item.content, &memory_context(platform, user_id))?;
+
+    let query = r#"
+        INSERT INTO memory_items (platform, user_id, source, content, created_at)
+        VALUES ($1, $2, $3, $4, $5)
+    "#;
+
+    sqlx::query(query)
+        .bind(platform)
+        .bind(user_id)
+        .bind(&item.source)
+        .bind(content)
+        .bind(item.created_at)
+        .execute(db)
+        .await
+        .map_err(|e| format!("Ошибка сохранения памяти: {}", e))?;
+
+    Ok(())
+}
+
+/// Загружает все элементы памяти пользователя в хронологическом порядке
+pub async fn load_memory_items(platform: &str, user_id: &str) -> Result<Vec<MemoryItem>, String> {
+    let db = get_db().await;
+    let context = memory_context(platform, user_id);
+
+    let query = r#"
+        SELECT source, content, created_at
+        FROM memory_items
+        WHERE platform = $1 AND user_id = $2
+        ORDER BY id
+    "#;
+
+    let rows: Vec<(String, String, DateTime<Utc>)> = sqlx::query_as(query)
+        .bind(platform)
+        .bind(user_id)
+        .fetch_all(db)
+        .await
+        .map_err(|e| format!("Ошибка загрузки памяти: {}", e))?;
+
+    rows.into_iter()
+        .map(|(source, content, created_at)| {
+            Ok(MemoryItem {
+                content: keyring().decrypt(&content, &context)?,
+                created_at,
+                source,
+            })
+        })
+        .collect()
+}
+
+/// Перешифровывает активным ключом все записи, зашифрованные старыми ключами
+/// или сохранённые открытым текстом, а также запечатанные значения файлового хранилища
+/// (`FILE_STORE_DIR`). Возвращает количество обновлённых записей.
+pub async fn rotate_encryption_keys() -> Result<u64, String> {
+    let keyring = keyring();
+    if !keyring.is_enabled() {
+        return Err("Шифрование не настроено (ENCRYPTION_KEYS)".to_string());
+    }
+
+    let db = get_db().await;
+    let mut updated = 0;
+
+    // (таблица, колонка, функция контекста)
+    let tables: [(&str, &str, ContextFn); 2] = [
+        ("dialog_messages", "text", dialog_context),
+        ("memory_items", "content", memory_context),
+    ];
+
+    for (table, column, context_fn) in tables {
+        let select_query = format!("SELECT id, platform, user_id, {} FROM {}", column, table);
+        let rows: Vec<(i64, String, String, String)> = sqlx::query_as(&select_query)
+            .fetch_all(db)
+            .await
+            .map_err(|e| format!("Ошибка чтения {}: {}", table, e))?;
+
+        let update_query = format!("UPDATE {} SET {} = $1 WHERE id = $2", table, column);
+        for (id, platform, user_id, value) in rows {
+            if !keyring.needs_rotation(&value) {
+                continue;
+            }
+            let resealed = keyring.reencrypt(&value, &context_fn(&platform, &user_id))?;
+            sqlx::query(&update_query)
+                .bind(resealed)
+                .bind(id)
+                .execute(db)
+                .await
+                .map_err(|e| format!("Ошибка обновления {}: {}", table, e))?;
+            updated += 1;
+        }
+    }
+
+    if let Some(store) = KvStore::from_env().map_err(|e| e.to_string())? {
+        updated += rotate_file_store(&store, keyring).map_err(|e| format!("Ошибка ротации файлового хранилища: {}", e))?;
+    }
+
+    Ok(updated)
+}
+
+/// Перешифровывает запечатанные значения во всех пространствах имён файлового хранилища
+pub fn rotate_file_store(store: &KvStore, keyring: &Keyring) -> Result<u64, Box<dyn std::error::Error>> {
+    let mut updated = 0;
+    for name in store.namespaces()? {
+        let namespace = store.namespace(&name)?;
+        for key in namespace.keys()? {
+            if namespace.reseal(&key, keyring)? {
+                updated += 1;
+            }
+        }
+    }
+    Ok(updated)
+}
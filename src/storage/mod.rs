@@ -1,2 +1,8 @@
+pub mod analytics;
+pub mod crypto;
 pub mod db;
 pub mod file;
+pub mod history;
+pub mod privacy;
+pub mod translation_memory;
+pub mod users;
@@ -0,0 +1,294 @@
+use chrono::{DateTime, Utc};
+use serde_json::{json, Map, Value};
+
+use crate::{
+    core::dialog::Message,
+    storage::{
+        analytics::anonymize_user_id,
+        crypto::keyring,
+        db::get_db,
+        file::KvStore,
+        history::{load_dialog_history, load_memory_items},
+        users::load_user_settings,
+    },
+};
+
+/// Префикс ключей пользователя в файловом хранилище: `platform:user_id:`.
+/// Все данные пользователя в [`KvStore`] должны храниться под ключами с этим префиксом,
+/// иначе их не найдут `/mydata` и `/forget`.
+pub fn user_key_prefix(platform: &str, user_id: &str) -> String {
+    format!("{}:{}:", platform, user_id)
+}
+
+/// Создаёт таблицу журнала аудита операций с персональными данными
+pub async fn init_privacy_audit_table() -> Result<(), sqlx::Error> {
+    let db = get_db().await;
+
+    let create_table_query = r#"
+        CREATE TABLE IF NOT EXISTS privacy_audit (
+            id BIGSERIAL PRIMARY KEY,
+            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+            action VARCHAR(32) NOT NULL,
+            user_hash VARCHAR(64) NOT NULL,
+            details TEXT NOT NULL
+        )
+    "#;
+
+    sqlx::query(create_table_query)
+        .execute(db)
+        .await?;
+
+    Ok(())
+}
+
+/// Записывает событие в журнал аудита (только анонимизированный id, без содержимого)
+async fn write_audit(action: &str, platform: &str, user_id: &str, details: &str) -> Result<(), String> {
+    let db = get_db().await;
+
+    let query = r#"
+        INSERT INTO privacy_audit (action, user_hash, details)
+        VALUES ($1, $2, $3)
+    "#;
+
+    sqlx::query(query)
+        .bind(action)
+        .bind(anonymize_user_id(platform, user_id))
+        .bind(details)
+        .execute(db)
+        .await
+        .map_err(|e| format!("Ошибка записи аудита: {}", e))?;
+
+    Ok(())
+}
+
+// created_at, language, intent, latency_ms, cache_hit, error_kind
+type AnalyticsRow = (DateTime<Utc>, String, String, i64, bool, Option<String>);
+
+/// Собирает все данные пользователя из всех хранилищ в один JSON-документ.
+/// `active_dialog` — сообщения текущего диалога в памяти процесса.
+pub async fn export_user_data(
+    platform: &str,
+    user_id: &str,
+    active_dialog: &[Message],
+) -> Result<Value, String> {
+    let db = get_db().await;
+
+    let settings = load_user_settings(platform, user_id).await;
+    let dialog_history = load_dialog_history(platform, user_id, i64::MAX).await?;
+    let memory = load_memory_items(platform, user_id).await?;
+
+    let cache_query = r#"
+        SELECT c.key, c.value
+        FROM cache c
+        JOIN cache_origins o ON o.key = c.key
+        WHERE o.platform = $1 AND o.user_id = $2
+        ORDER BY c.key
+    "#;
+
+    let cached: Vec<(String, String)> = sqlx::query_as(cache_query)
+        .bind(platform)
+        .bind(user_id)
+        .fetch_all(db)
+        .await
+        .map_err(|e| format!("Ошибка чтения кэша: {}", e))?;
+
+    let analytics_query = r#"
+        SELECT created_at, language, intent, latency_ms, cache_hit, error_kind
+        FROM interactions
+        WHERE user_hash = $1
+        ORDER BY created_at
+    "#;
+
+    let analytics: Vec<AnalyticsRow> = sqlx::query_as(analytics_query)
+        .bind(anonymize_user_id(platform, user_id))
+        .fetch_all(db)
+        .await
+        .map_err(|e| format!("Ошибка чтения аналитики: {}", e))?;
+
+    let offline = match KvStore::from_env().map_err(|e| e.to_string())? {
+        Some(store) => export_user_files(&store, platform, user_id).map_err(|e| e.to_string())?,
+        None => Value::Null,
+    };
+
+    let document = json!({
+        "exported_at": Utc::now().to_rfc3339(),
+        "platform": platform,
+        "user_id": user_id,
+        "settings": {
+            "reply_language": settings.reply_language,
+            "units": settings.units.as_str(),
+            "timezone": settings.timezone,
+            "default_city": settings.default_city,
+            "verbosity": settings.verbosity.as_str(),
+            "emoji": settings.emoji,
+            "formality": settings.formality.map(|f| f.as_str()),
+        },
+        "dialog_history": dialog_history,
+        "active_dialog": active_dialog,
+        "memory": memory,
+        "cached_answers": cached
+            .into_iter()
+            .map(|(key, value)| json!({ "query": key, "answer": value }))
+            .collect::<Vec<_>>(),
+        "analytics": analytics
+            .into_iter()
+            .map(|(timestamp, language, intent, latency_ms, cache_hit, error_kind)| json!({
+                "timestamp": timestamp.to_rfc3339(),
+                "language": language,
+                "intent": intent,
+                "latency_ms": latency_ms,
+                "cache_hit": cache_hit,
+                "error_kind": error_kind,
+            }))
+            .collect::<Vec<_>>(),
+        "offline_store": offline,
+    });
+
+    write_audit("export", platform, user_id, "mydata").await?;
+    Ok(document)
+}
+
+/// Сколько записей удалено из каждого хранилища
+#[derive(Debug, Clone, Default)]
+pub struct ForgetReport {
+    pub cached_answers: u64,
+    pub dialog_messages: u64,
+    pub memory_items: u64,
+    pub settings: u64,
+    pub analytics: u64,
+    pub offline_records: u64,
+}
+
+impl ForgetReport {
+    /// Краткая сводка (без содержимого) — для пользователя и журнала аудита
+    pub fn summary(&self) -> String {
+        format!(
+            "cache={}, dialog={}, memory={}, settings={}, analytics={}, offline={}",
+            self.cached_answers,
+            self.dialog_messages,
+            self.memory_items,
+            self.settings,
+            self.analytics,
+            self.offline_records
+        )
+    }
+}
+
+/// Удаляет все данные пользователя из БД и файлового хранилища и пишет запись аудита.
+/// Удаление в БД выполняется одной транзакцией.
+pub async fn forget_user(platform: &str, user_id: &str) -> Result<ForgetReport, String> {
+    let db = get_db().await;
+    let user_hash = anonymize_user_id(platform, user_id);
+    let mut report = ForgetReport::default();
+
+    let mut tx = db
+        .begin()
+        .await
+        .map_err(|e| format!("Ошибка начала транзакции: {}", e))?;
+
+    // Кэшированные ответы, ключом которых был ввод пользователя
+    report.cached_answers = sqlx::query(
+        "DELETE FROM cache WHERE key IN (SELECT key FROM cache_origins WHERE platform = $1 AND user_id = $2)",
+    )
+    .bind(platform)
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| format!("Ошибка удаления кэша: {}", e))?
+    .rows_affected();
+
+    let per_user_tables = [
+        ("cache_origins", None),
+        ("dialog_messages", Some(&mut report.dialog_messages)),
+        ("memory_items", Some(&mut report.memory_items)),
+        ("users", Some(&mut report.settings)),
+    ];
+
+    for (table, counter) in per_user_tables {
+        let query = format!("DELETE FROM {} WHERE platform = $1 AND user_id = $2", table);
+        let affected = sqlx::query(&query)
+            .bind(platform)
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Ошибка удаления из {}: {}", table, e))?
+            .rows_affected();
+        if let Some(counter) = counter {
+            *counter = affected;
+        }
+    }
+
+    report.analytics = sqlx::query("DELETE FROM interactions WHERE user_hash = $1")
+        .bind(&user_hash)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Ошибка удаления аналитики: {}", e))?
+        .rows_affected();
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Ошибка фиксации транзакции: {}", e))?;
+
+    if let Some(store) = KvStore::from_env().map_err(|e| e.to_string())? {
+        report.offline_records = forget_user_files(&store, platform, user_id).map_err(|e| e.to_string())? as u64;
+    }
+
+    write_audit("forget", platform, user_id, &report.summary()).await?;
+    Ok(report)
+}
+
+/// Экспортирует записи пользователя из всех пространств имён файлового хранилища
+pub fn export_user_files(store: &KvStore, platform: &str, user_id: &str) -> Result<Value, Box<dyn std::error::Error>> {
+    let prefix = user_key_prefix(platform, user_id);
+    let mut namespaces = Map::new();
+
+    for name in store.namespaces()? {
+        let namespace = store.namespace(&name)?;
+        let mut records = Map::new();
+        for key in namespace.keys_with_prefix(&prefix)? {
+            if let Some(value) = namespace.get_sealed::<Value>(&key, keyring())? {
+                records.insert(key, value);
+            }
+        }
+        if !records.is_empty() {
+            namespaces.insert(name, Value::Object(records));
+        }
+    }
+
+    Ok(Value::Object(namespaces))
+}
+
+/// Удаляет записи пользователя из всех пространств имён файлового хранилища
+pub fn forget_user_files(store: &KvStore, platform: &str, user_id: &str) -> Result<usize, Box<dyn std::error::Error>> {
+    let prefix = user_key_prefix(platform, user_id);
+    let mut removed = 0;
+    for name in store.namespaces()? {
+        removed += store.namespace(&name)?.delete_prefix(&prefix)?;
+    }
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_files_export_and_forget() {
+        let dir = std::env::temp_dir().join(format!("bot_core_privacy_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let store = KvStore::open(&dir).unwrap();
+
+        let notes = store.namespace("notes").unwrap();
+        notes.put("telegram:42:note", &"моя заметка").unwrap();
+        notes.put("telegram:420:note", &"чужая заметка").unwrap();
+
+        let exported = export_user_files(&store, "telegram", "42").unwrap();
+        assert_eq!(exported["notes"]["telegram:42:note"], "моя заметка");
+        assert!(exported["notes"].get("telegram:420:note").is_none());
+
+        assert_eq!(forget_user_files(&store, "telegram", "42").unwrap(), 1);
+        assert_eq!(notes.keys().unwrap(), vec!["telegram:420:note"]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
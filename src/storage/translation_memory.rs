@@ -0,0 +1,428 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+use crate::storage::{
+    db::get_db,
+    file::{KvStore, Namespace},
+};
+
+/// Срок жизни машинного перевода по умолчанию (`TRANSLATION_CACHE_TTL_DAYS`)
+const DEFAULT_TTL_DAYS: i64 = 90;
+
+static TRANSLATION_MEMORY: OnceLock<TranslationMemory> = OnceLock::new();
+
+/// Ключ памяти переводов: нормализованный исходный текст, языковая пара, формальность
+/// и глоссарий, с которым сделан перевод
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TranslationKey {
+    pub text: String,
+    pub source_lang: String,
+    pub target_lang: String,
+    pub formality: Option<String>,
+    pub glossary: Option<String>,
+}
+
+impl TranslationKey {
+    pub fn new(text: &str, source_lang: Option<&str>, target_lang: &str, formality: Option<&str>) -> Self {
+        Self {
+            // Регистр не трогаем — от него зависит перевод; схлопываем только пробелы
+            text: text.split_whitespace().collect::<Vec<_>>().join(" "),
+            source_lang: source_lang.unwrap_or("auto").to_uppercase(),
+            target_lang: target_lang.to_uppercase(),
+            formality: formality.map(str::to_lowercase),
+            glossary: None,
+        }
+    }
+
+    /// Привязывает ключ к глоссарию: после синхронизации у глоссария новый ID,
+    /// и переводы со старыми терминами перестают находиться
+    pub fn with_glossary(mut self, glossary: Option<&str>) -> Self {
+        self.glossary = glossary.map(str::to_string);
+        self
+    }
+
+    /// Хэш ключа — первичный ключ записи в хранилище
+    pub fn digest(&self) -> String {
+        let mut hasher = Sha256::new();
+        for part in [
+            self.source_lang.as_str(),
+            self.target_lang.as_str(),
+            self.formality.as_deref().unwrap_or("default"),
+            self.text.as_str(),
+        ] {
+            hasher.update(part.as_bytes());
+            hasher.update([0]);
+        }
+        // Без глоссария хэш прежний — уже сохранённые записи остаются доступны
+        if let Some(glossary) = &self.glossary {
+            hasher.update(b"glossary:");
+            hasher.update(glossary.as_bytes());
+        }
+        hex::encode(hasher.finalize())
+    }
+}
+
+/// Запись памяти переводов
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranslationEntry {
+    pub source_text: String,
+    pub source_lang: String,
+    pub target_lang: String,
+    pub formality: Option<String>,
+    pub translation: String,
+    /// Проверенный человеком перевод: не истекает и не перезаписывается машинным
+    pub reviewed: bool,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl TranslationEntry {
+    fn is_fresh(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_none_or(|expires_at| expires_at > now)
+    }
+}
+
+/// Строка файла предзаполнения (`--seed-translations`)
+#[derive(Debug, Clone, Deserialize)]
+pub struct SeedTranslation {
+    pub source: String,
+    pub source_lang: Option<String>,
+    pub target_lang: String,
+    pub translation: String,
+    pub formality: Option<String>,
+}
+
+/// Счётчики попаданий и промахов
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TranslationMemoryStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub stores: u64,
+}
+
+impl TranslationMemoryStats {
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            0.0
+        } else {
+            self.hits as f64 / total as f64
+        }
+    }
+
+    pub fn format(&self) -> String {
+        format!(
+            "🗂 Память переводов: {} попаданий, {} промахов ({:.1}%), сохранено {}",
+            self.hits,
+            self.misses,
+            self.hit_rate() * 100.0,
+            self.stores
+        )
+    }
+}
+
+/// Где хранятся переводы
+enum Backend {
+    Postgres,
+    File(Namespace),
+    Memory(Mutex<HashMap<String, TranslationEntry>>),
+}
+
+/// Память переводов (кэш с долгим сроком жизни) поверх настроенного хранилища:
+/// Postgres при `DATABASE_URL`, файловое хранилище при `FILE_STORE_DIR`, иначе память процесса.
+pub struct TranslationMemory {
+    backend: Backend,
+    ttl: Duration,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    stores: AtomicU64,
+}
+
+impl TranslationMemory {
+    /// Память переводов в памяти процесса (для тестов и режима без хранилища)
+    pub fn in_memory(ttl: Duration) -> Self {
+        Self::with_backend(Backend::Memory(Mutex::new(HashMap::new())), ttl)
+    }
+
+    /// Память переводов в пространстве имён файлового хранилища
+    pub fn in_file_store(store: &KvStore, ttl: Duration) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(Self::with_backend(Backend::File(store.namespace("translation_memory")?), ttl))
+    }
+
+    fn with_backend(backend: Backend, ttl: Duration) -> Self {
+        Self {
+            backend,
+            ttl,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            stores: AtomicU64::new(0),
+        }
+    }
+
+    /// Выбирает хранилище по переменным окружения
+    pub fn from_env() -> Self {
+        let ttl = Duration::days(
+            std::env::var("TRANSLATION_CACHE_TTL_DAYS")
+                .ok()
+                .and_then(|days| days.trim().parse().ok())
+                .unwrap_or(DEFAULT_TTL_DAYS),
+        );
+
+        if std::env::var("DATABASE_URL").is_ok() {
+            return Self::with_backend(Backend::Postgres, ttl);
+        }
+        match KvStore::from_env() {
+            Ok(Some(store)) => match Self::in_file_store(&store, ttl) {
+                Ok(memory) => return memory,
+                Err(e) => eprintln!("⚠️ Память переводов в файловом хранилище недоступна: {}", e),
+            },
+            Ok(None) => {}
+            Err(e) => eprintln!("⚠️ Файловое хранилище недоступно: {}", e),
+        }
+        Self::in_memory(ttl)
+    }
+
+    /// Ищет перевод; учитывает попадание/промах в метриках. Проверенные переводы
+    /// заводятся без глоссария и подходят для любого из них
+    pub async fn get(&self, key: &TranslationKey) -> Option<String> {
+        let mut found = self.lookup(key).await.map(|e| e.translation);
+        if found.is_none() && key.glossary.is_some() {
+            let plain = key.clone().with_glossary(None);
+            found = self.lookup(&plain).await.filter(|e| e.reviewed).map(|e| e.translation);
+        }
+
+        let counter = if found.is_some() { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+        found
+    }
+
+    async fn lookup(&self, key: &TranslationKey) -> Option<TranslationEntry> {
+        match self.load(key).await {
+            Ok(entry) => entry.filter(|e| e.is_fresh(Utc::now())),
+            Err(e) => {
+                eprintln!("Ошибка чтения памяти переводов: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Сохраняет машинный перевод со сроком жизни (проверенный перевод не перезаписывается)
+    pub async fn put(&self, key: &TranslationKey, translation: &str) {
+        let entry = self.entry(key, translation, false);
+        if let Err(e) = self.store(key, entry).await {
+            eprintln!("Ошибка сохранения в память переводов: {}", e);
+        }
+    }
+
+    /// Сохраняет проверенный перевод без срока жизни
+    pub async fn put_reviewed(&self, key: &TranslationKey, translation: &str) -> Result<(), String> {
+        let entry = self.entry(key, translation, true);
+        self.store(key, entry).await
+    }
+
+    /// Загружает проверенные переводы из JSON-массива [`SeedTranslation`]; возвращает их количество
+    pub async fn seed_from_json(&self, json: &str) -> Result<usize, String> {
+        let seeds: Vec<SeedTranslation> =
+            serde_json::from_str(json).map_err(|e| format!("Ошибка разбора файла переводов: {}", e))?;
+
+        for seed in &seeds {
+            let key = TranslationKey::new(
+                &seed.source,
+                seed.source_lang.as_deref(),
+                &seed.target_lang,
+                seed.formality.as_deref(),
+            );
+            self.put_reviewed(&key, &seed.translation).await?;
+        }
+        Ok(seeds.len())
+    }
+
+    /// Текущие значения метрик
+    pub fn stats(&self) -> TranslationMemoryStats {
+        TranslationMemoryStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            stores: self.stores.load(Ordering::Relaxed),
+        }
+    }
+
+    fn entry(&self, key: &TranslationKey, translation: &str, reviewed: bool) -> TranslationEntry {
+        TranslationEntry {
+            source_text: key.text.clone(),
+            source_lang: key.source_lang.clone(),
+            target_lang: key.target_lang.clone(),
+            formality: key.formality.clone(),
+            translation: translation.to_string(),
+            reviewed,
+            expires_at: (!reviewed).then(|| Utc::now() + self.ttl),
+        }
+    }
+
+    async fn load(&self, key: &TranslationKey) -> Result<Option<TranslationEntry>, String> {
+        let digest = key.digest();
+        match &self.backend {
+            Backend::Postgres => {
+                let query = r#"
+                    SELECT source_text, source_lang, target_lang, formality, translation, reviewed, expires_at
+                    FROM translation_memory
+                    WHERE key = $1
+                "#;
+                let row: Option<EntryRow> = sqlx::query_as(query)
+                    .bind(&digest)
+                    .fetch_optional(get_db().await)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                Ok(row.map(
+                    |(source_text, source_lang, target_lang, formality, translation, reviewed, expires_at)| {
+                        TranslationEntry { source_text, source_lang, target_lang, formality, translation, reviewed, expires_at }
+                    },
+                ))
+            }
+            Backend::File(namespace) => namespace.get(&digest).map_err(|e| e.to_string()),
+            Backend::Memory(map) => Ok(map.lock().unwrap().get(&digest).cloned()),
+        }
+    }
+
+    async fn store(&self, key: &TranslationKey, entry: TranslationEntry) -> Result<(), String> {
+        let digest = key.digest();
+        match &self.backend {
+            Backend::Postgres => {
+                let query = r#"
+                    INSERT INTO translation_memory
+                        (key, source_text, source_lang, target_lang, formality, translation, reviewed, expires_at)
+                    VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                    ON CONFLICT (key) DO UPDATE SET
+                        translation = EXCLUDED.translation,
+                        reviewed = EXCLUDED.reviewed,
+                        expires_at = EXCLUDED.expires_at
+                    WHERE EXCLUDED.reviewed OR NOT translation_memory.reviewed
+                "#;
+                sqlx::query(query)
+                    .bind(&digest)
+                    .bind(&entry.source_text)
+                    .bind(&entry.source_lang)
+                    .bind(&entry.target_lang)
+                    .bind(&entry.formality)
+                    .bind(&entry.translation)
+                    .bind(entry.reviewed)
+                    .bind(entry.expires_at)
+                    .execute(get_db().await)
+                    .await
+                    .map_err(|e| e.to_string())?;
+            }
+            Backend::File(namespace) => {
+                let existing: Option<TranslationEntry> = namespace.get(&digest).map_err(|e| e.to_string())?;
+                if entry.reviewed || !existing.is_some_and(|e| e.reviewed) {
+                    namespace.put(&digest, &entry).map_err(|e| e.to_string())?;
+                }
+            }
+            Backend::Memory(map) => {
+                let mut map = map.lock().unwrap();
+                if entry.reviewed || !map.get(&digest).is_some_and(|e| e.reviewed) {
+                    map.insert(digest, entry);
+                }
+            }
+        }
+
+        self.stores.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+// source_text, source_lang, target_lang, formality, translation, reviewed, expires_at
+type EntryRow = (String, String, String, Option<String>, String, bool, Option<DateTime<Utc>>);
+
+/// Создаёт таблицу памяти переводов
+pub async fn init_translation_memory_table() -> Result<(), sqlx::Error> {
+    let db = get_db().await;
+
+    let create_table_query = r#"
+        CREATE TABLE IF NOT EXISTS translation_memory (
+            key VARCHAR(64) PRIMARY KEY,
+            source_text TEXT NOT NULL,
+            source_lang VARCHAR(16) NOT NULL,
+            target_lang VARCHAR(16) NOT NULL,
+            formality VARCHAR(16),
+            translation TEXT NOT NULL,
+            reviewed BOOLEAN NOT NULL DEFAULT FALSE,
+            expires_at TIMESTAMPTZ,
+            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+        )
+    "#;
+
+    sqlx::query(create_table_query)
+        .execute(db)
+        .await?;
+
+    Ok(())
+}
+
+/// Глобальная память переводов (хранилище выбирается из окружения один раз)
+pub fn translation_memory() -> &'static TranslationMemory {
+    TRANSLATION_MEMORY.get_or_init(TranslationMemory::from_env)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_normalisation() {
+        let a = TranslationKey::new("Hello   world ", Some("en"), "ru", None);
+        let b = TranslationKey::new("Hello world", Some("EN"), "RU", None);
+        assert_eq!(a.digest(), b.digest());
+
+        let formal = TranslationKey::new("Hello world", Some("EN"), "RU", Some("more"));
+        assert_ne!(a.digest(), formal.digest());
+        let lower = TranslationKey::new("hello world", Some("EN"), "RU", None);
+        assert_ne!(a.digest(), lower.digest());
+    }
+
+    #[tokio::test]
+    async fn test_hits_misses_and_seeding() {
+        let memory = TranslationMemory::in_memory(Duration::days(1));
+        let key = TranslationKey::new("Thank you", Some("EN"), "RU", None);
+
+        assert_eq!(memory.get(&key).await, None);
+        memory.put(&key, "Спасибо").await;
+        assert_eq!(memory.get(&key).await.as_deref(), Some("Спасибо"));
+
+        let seeded = memory
+            .seed_from_json(r#"[{"source": "Thank you", "source_lang": "EN", "target_lang": "RU", "translation": "Благодарю"}]"#)
+            .await
+            .unwrap();
+        assert_eq!(seeded, 1);
+        // Машинный перевод не затирает проверенный
+        memory.put(&key, "Спасибо").await;
+        assert_eq!(memory.get(&key).await.as_deref(), Some("Благодарю"));
+
+        let stats = memory.stats();
+        assert_eq!((stats.hits, stats.misses), (2, 1));
+    }
+
+    #[tokio::test]
+    async fn test_glossary_change_invalidates_machine_translations() {
+        let memory = TranslationMemory::in_memory(Duration::days(1));
+        let key = TranslationKey::new("Add the crate", Some("EN"), "RU", None);
+        memory.put(&key.clone().with_glossary(Some("g-1")), "Добавьте крейт").await;
+
+        assert_eq!(memory.get(&key.clone().with_glossary(Some("g-1"))).await.as_deref(), Some("Добавьте крейт"));
+        assert_eq!(memory.get(&key.clone().with_glossary(Some("g-2"))).await, None);
+        assert_eq!(memory.get(&key).await, None);
+
+        // Проверенный перевод без глоссария находится при любом глоссарии
+        memory.put_reviewed(&key, "Подключите крейт").await.unwrap();
+        assert_eq!(memory.get(&key.with_glossary(Some("g-2"))).await.as_deref(), Some("Подключите крейт"));
+    }
+
+    #[tokio::test]
+    async fn test_expired_entries_are_misses() {
+        let memory = TranslationMemory::in_memory(Duration::seconds(-1));
+        let key = TranslationKey::new("Hello", Some("EN"), "DE", None);
+        memory.put(&key, "Hallo").await;
+        assert_eq!(memory.get(&key).await, None);
+    }
+}
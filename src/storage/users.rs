@@ -0,0 +1,110 @@
+use crate::{
+    core::settings::{Units, UserSettings, Verbosity},
+    language::translator::Formality,
+    storage::db::get_db,
+};
+
+// reply_language, units, timezone, default_city, verbosity, emoji, formality
+type SettingsRow = (Option<String>, String, Option<String>, Option<String>, String, bool, Option<String>);
+
+/// Создаёт таблицу пользователей с персональными настройками
+pub async fn init_users_table() -> Result<(), sqlx::Error> {
+    let db = get_db().await;
+
+    let create_table_query = r#"
+        CREATE TABLE IF NOT EXISTS users (
+            platform VARCHAR(32) NOT NULL,
+            user_id VARCHAR(64) NOT NULL,
+            reply_language VARCHAR(8),
+            units VARCHAR(16) NOT NULL DEFAULT 'metric',
+            timezone VARCHAR(64),
+            default_city VARCHAR(128),
+            verbosity VARCHAR(16) NOT NULL DEFAULT 'normal',
+            emoji BOOLEAN NOT NULL DEFAULT TRUE,
+            formality VARCHAR(16),
+            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+            updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+            PRIMARY KEY (platform, user_id)
+        )
+    "#;
+
+    sqlx::query(create_table_query)
+        .execute(db)
+        .await?;
+
+    // Таблицы, созданные до появления настройки обращения
+    sqlx::query("ALTER TABLE users ADD COLUMN IF NOT EXISTS formality VARCHAR(16)")
+        .execute(db)
+        .await?;
+
+    Ok(())
+}
+
+/// Загружает настройки пользователя; при отсутствии записи или ошибке — настройки по умолчанию
+pub async fn load_user_settings(platform: &str, user_id: &str) -> UserSettings {
+    let db = get_db().await;
+
+    let query = r#"
+        SELECT reply_language, units, timezone, default_city, verbosity, emoji, formality
+        FROM users
+        WHERE platform = $1 AND user_id = $2
+    "#;
+
+    let row: Result<Option<SettingsRow>, _> = sqlx::query_as(query)
+        .bind(platform)
+        .bind(user_id)
+        .fetch_optional(db)
+        .await;
+
+    match row {
+        Ok(Some((reply_language, units, timezone, default_city, verbosity, emoji, formality))) => UserSettings {
+            reply_language,
+            units: Units::parse(&units).unwrap_or(Units::Metric),
+            timezone,
+            default_city,
+            verbosity: Verbosity::parse(&verbosity).unwrap_or(Verbosity::Normal),
+            emoji,
+            formality: formality.as_deref().and_then(Formality::parse).filter(|f| *f != Formality::Default),
+        },
+        Ok(None) => UserSettings::default(),
+        Err(e) => {
+            eprintln!("Ошибка загрузки настроек пользователя: {}", e);
+            UserSettings::default()
+        }
+    }
+}
+
+/// Сохраняет настройки пользователя (создаёт запись при необходимости)
+pub async fn save_user_settings(platform: &str, user_id: &str, settings: &UserSettings) -> Result<(), sqlx::Error> {
+    let db = get_db().await;
+
+    let query = r#"
+        INSERT INTO users (platform, user_id, reply_language, units, timezone, default_city, verbosity, emoji, formality)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        ON CONFLICT (platform, user_id)
+        DO UPDATE SET
+            reply_language = EXCLUDED.reply_language,
+            units = EXCLUDED.units,
+            timezone = EXCLUDED.timezone,
+            default_city = EXCLUDED.default_city,
+            verbosity = EXCLUDED.verbosity,
+            emoji = EXCLUDED.emoji,
+            formality = EXCLUDED.formality,
+            updated_at = NOW()
+    "#;
+
+    sqlx::query(query)
+        .bind(platform)
+        .bind(user_id)
+        .bind(&settings.reply_language)
+        .bind(settings.units.as_str())
+        .bind(&settings.timezone)
+        .bind(&settings.default_city)
+        .bind(settings.verbosity.as_str())
+        .bind(settings.emoji)
+        .bind(settings.formality.map(|f| f.as_str()))
+        .execute(db)
+        .await?;
+
+    Ok(())
+}